@@ -0,0 +1,226 @@
+//! Compares KEON with JSON, RON and TOML on the same data, run with `cargo bench --bench compare`.
+//!
+//! Each row is the median of several runs, in MB/s of the respective output, and the output size.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Dataset {
+    records: Vec<Record>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record {
+    id: u64,
+    name: String,
+    description: String,
+    score: f64,
+    active: bool,
+    kind: Kind,
+    position: (f32, f32),
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Kind {
+    Sensor,
+    Actuator,
+    Controller,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    title: String,
+    version: u32,
+    servers: BTreeMap<String, Server>,
+    features: Vec<String>,
+    limits: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Server {
+    host: String,
+    port: u16,
+    weight: f64,
+    enabled: bool,
+    aliases: Vec<String>,
+}
+
+fn dataset(len: u64) -> Dataset {
+    Dataset {
+        records: (0..len)
+            .map(|i| Record {
+                id: i * 7919,
+                name: format!("record-{i}"),
+                description: format!("The {i}th record, with \"quotes\" and a path C:\\data\\{i}."),
+                score: i as f64 / 4.0,
+                active: i % 3 != 0,
+                kind: [Kind::Sensor, Kind::Actuator, Kind::Controller][i as usize % 3],
+                position: (i as f32 * 0.5, -(i as f32) * 1.25),
+                tags: (0..i % 4).map(|t| format!("tag{t}")).collect(),
+            })
+            .collect(),
+    }
+}
+
+fn config() -> Config {
+    Config {
+        title: "Example configuration".into(),
+        version: 3,
+        servers: (0..16)
+            .map(|i| {
+                let server = Server {
+                    host: format!("10.0.{}.{}", i / 8, i % 8),
+                    port: 8000 + i,
+                    weight: 1.0 / (1 << (i % 4)) as f64,
+                    enabled: i % 2 == 0,
+                    aliases: vec![format!("srv{i}"), format!("node-{i}.local")],
+                };
+                (format!("server_{i}"), server)
+            })
+            .collect(),
+        features: ["auth", "metrics", "tracing", "compression"].map(String::from).to_vec(),
+        limits: [
+            ("connections", 1024),
+            ("requests_per_second", 10_000),
+            ("timeout_ms", -1),
+        ]
+        .map(|(k, v)| (k.to_string(), v))
+        .into(),
+    }
+}
+
+struct Format {
+    name: &'static str,
+    ser: fn(&dyn erased::Value) -> String,
+}
+
+/// Serializes to a string with each format, without boxing the format in generics.
+mod erased {
+    pub trait Value {
+        fn keon(&self) -> String;
+        fn keon_pretty(&self) -> String;
+        fn json(&self) -> String;
+        fn json_pretty(&self) -> String;
+        fn ron(&self) -> String;
+        fn ron_pretty(&self) -> String;
+        fn toml(&self) -> String;
+    }
+    impl<T: serde::Serialize> Value for T {
+        fn keon(&self) -> String {
+            keon::to_string(self).unwrap()
+        }
+        fn keon_pretty(&self) -> String {
+            keon::to_string_pretty(self).unwrap()
+        }
+        fn json(&self) -> String {
+            serde_json::to_string(self).unwrap()
+        }
+        fn json_pretty(&self) -> String {
+            serde_json::to_string_pretty(self).unwrap()
+        }
+        fn ron(&self) -> String {
+            ron::to_string(self).unwrap()
+        }
+        fn ron_pretty(&self) -> String {
+            ron::ser::to_string_pretty(self, Default::default()).unwrap()
+        }
+        fn toml(&self) -> String {
+            toml::to_string(self).unwrap()
+        }
+    }
+}
+
+const FORMATS: &[Format] = &[
+    Format {
+        name: "keon",
+        ser: |v| v.keon(),
+    },
+    Format {
+        name: "keon (pretty)",
+        ser: |v| v.keon_pretty(),
+    },
+    Format {
+        name: "json",
+        ser: |v| v.json(),
+    },
+    Format {
+        name: "json (pretty)",
+        ser: |v| v.json_pretty(),
+    },
+    Format {
+        name: "ron",
+        ser: |v| v.ron(),
+    },
+    Format {
+        name: "ron (pretty)",
+        ser: |v| v.ron_pretty(),
+    },
+    Format {
+        name: "toml",
+        ser: |v| v.toml(),
+    },
+];
+
+fn deserialize<T: DeserializeOwned>(format: &str, s: &str) -> T {
+    match format.split(' ').next().unwrap() {
+        "keon" => keon::from_str(s).unwrap(),
+        "json" => serde_json::from_str(s).unwrap(),
+        "ron" => ron::from_str(s).unwrap(),
+        "toml" => toml::from_str(s).unwrap(),
+        _ => unreachable!(),
+    }
+}
+
+/// The median time of running `f` for at least a tenth of a second, at least 5 times.
+fn measure(mut f: impl FnMut()) -> Duration {
+    let mut times = Vec::new();
+    let start = Instant::now();
+    while times.len() < 5 || start.elapsed() < Duration::from_millis(100) {
+        let t = Instant::now();
+        f();
+        times.push(t.elapsed());
+    }
+    times.sort();
+    times[times.len() / 2]
+}
+
+fn throughput(len: usize, time: Duration) -> f64 {
+    len as f64 / time.as_secs_f64() / 1e6
+}
+
+fn run<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(workload: &str, value: &T) {
+    println!("\n{workload}\n");
+    println!("{:<16}{:>12}{:>16}{:>16}", "format", "bytes", "ser MB/s", "de MB/s");
+    for format in FORMATS {
+        let out = (format.ser)(value);
+        assert_eq!(&deserialize::<T>(format.name, &out), value, "{}", format.name);
+
+        let ser = measure(|| {
+            black_box((format.ser)(black_box(value)));
+        });
+        let de = measure(|| {
+            black_box(deserialize::<T>(format.name, black_box(&out)));
+        });
+        println!(
+            "{:<16}{:>12}{:>16.1}{:>16.1}",
+            format.name,
+            out.len(),
+            throughput(out.len(), ser),
+            throughput(out.len(), de)
+        );
+    }
+}
+
+fn main() {
+    run(
+        "records: 10000 structs with strings, floats, a unit enum and a tuple",
+        &dataset(10_000),
+    );
+    run("config: maps of structs, as in a configuration file", &config());
+}
@@ -6,6 +6,7 @@ use std::{
 };
 
 fn main() -> io::Result<()> {
+    #[allow(clippy::excessive_precision)]
     let save = Save {
         greeting: "Hello world!".to_string(),
         keybinds: BTreeMap::from_iter(vec![
@@ -11,13 +11,10 @@ fn main() {
     )
     .unwrap();
 
-    let val2 = Value::Map(Map::from_iter(
-        vec![
-            ("secs_since_epoch".into(), 1736172788u64.into()),
-            ("nanos_since_epoch".into(), 855221200u64.into()),
-        ]
-        .into_iter(),
-    ));
+    let val2 = Value::Map(Map::from_iter(vec![
+        ("secs_since_epoch".into(), 1736172788u64.into()),
+        ("nanos_since_epoch".into(), 855221200u64.into()),
+    ]));
 
     assert_eq!(val, val2);
 
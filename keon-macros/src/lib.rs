@@ -0,0 +1,90 @@
+//! Procedural macros of [`keon`](https://docs.rs/keon), in a crate of their own as they read documents with it.
+
+use proc_macro::{Delimiter, Group, Literal, Span, TokenStream, TokenTree};
+use std::path::PathBuf;
+
+/// Embeds a KEON document as a `&'static T`, read when first used, e.g.
+///
+/// ```ignore
+/// let config: &'static Config = keon_macros::include_keon!("assets/config.keon" as Config);
+/// ```
+///
+/// The path is relative to the package root, i.e. `CARGO_MANIFEST_DIR`, and the crate is rebuilt when the file changes.
+///
+/// The syntax of the document is checked at compile time, failing with its diagnostic.
+/// Whether it matches `T` is only known when first used, where a mismatch panics,
+/// check that in advance with `keon::build::validate_dir` from a build script.
+///
+/// `T` has to be `DeserializeOwned`, `Send` and `Sync`, and the crate has to depend on `keon`.
+#[proc_macro]
+pub fn include_keon(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(output) => output,
+        Err((span, message)) => compile_error(span, &message),
+    }
+}
+
+const USAGE: &str = "expected `\"path/to/document.keon\" as Type`";
+
+fn expand(input: TokenStream) -> Result<TokenStream, (Span, String)> {
+    let mut tokens = input.into_iter();
+    let (span, path) = match tokens.next() {
+        // Rust strings without a prefix are KEON strings as well.
+        Some(TokenTree::Literal(literal)) => match keon::from_str::<String>(&literal.to_string()) {
+            Ok(path) => (literal.span(), path),
+            Err(_) => return Err((literal.span(), USAGE.into())),
+        },
+        Some(token) => return Err((token.span(), USAGE.into())),
+        None => return Err((Span::call_site(), USAGE.into())),
+    };
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "as" => (),
+        Some(token) => return Err((token.span(), USAGE.into())),
+        None => return Err((span, USAGE.into())),
+    }
+    let ty = tokens.collect::<TokenStream>();
+    if ty.is_empty() {
+        return Err((span, USAGE.into()));
+    }
+
+    let root = std::env::var_os("CARGO_MANIFEST_DIR").map_or_else(PathBuf::new, PathBuf::from);
+    let path = root.join(path);
+    if let Some(e) = keon::build::check_file::<keon::Value>(&path) {
+        // The compiler already says it's an error.
+        let diagnostic = e.to_string();
+        return Err((span, diagnostic.trim_start_matches("error: ").trim_end().into()));
+    }
+    let path = match path.to_str() {
+        Some(path) => Literal::string(path),
+        None => return Err((span, "the path has to be UTF-8".into())),
+    };
+
+    let expanded = [
+        "{ static VALUE: ::std::sync::OnceLock<",
+        &ty.to_string(),
+        "> = ::std::sync::OnceLock::new(); VALUE.get_or_init(|| ::keon::from_str(include_str!(",
+        &path.to_string(),
+        ")).unwrap_or_else(|e| panic!(\"cannot read `{}` as `{}`: {:#}\", ",
+        &path.to_string(),
+        ", stringify!(",
+        &ty.to_string(),
+        "), e))) }",
+    ];
+    Ok(expanded.concat().parse().unwrap())
+}
+
+fn compile_error(span: Span, message: &str) -> TokenStream {
+    let mut message = Literal::string(message);
+    message.set_span(span);
+    let mut args = Group::new(Delimiter::Parenthesis, TokenTree::Literal(message).into());
+    args.set_span(span);
+
+    let path = "::core::compile_error!".parse::<TokenStream>().unwrap();
+    path.into_iter()
+        .map(|mut token| {
+            token.set_span(span);
+            token
+        })
+        .chain([TokenTree::Group(args)])
+        .collect()
+}
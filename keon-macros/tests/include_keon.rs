@@ -0,0 +1,45 @@
+use keon_macros::include_keon;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Mode {
+    Fast,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    name: String,
+    port: u16,
+    mode: Mode,
+    tags: Vec<String>,
+}
+
+fn config() -> &'static Config {
+    include_keon!("tests/assets/config.keon" as Config)
+}
+
+#[test]
+fn include_keon() {
+    assert_eq!(
+        config(),
+        &Config {
+            name: "server".into(),
+            port: 8080,
+            mode: Mode::Fast,
+            tags: vec!["web".into(), "api".into()],
+        }
+    );
+    // Read once.
+    assert!(std::ptr::eq(config(), config()));
+
+    // Another type reads the same document elsewhere.
+    let value = include_keon!("tests/assets/config.keon" as BTreeMap<String, keon::Value>);
+    assert_eq!(value["port"], keon::Value::from(8080u16));
+}
+
+#[test]
+#[should_panic(expected = "as `u8`")]
+fn mismatched_type() {
+    include_keon!("tests/assets/config.keon" as u8);
+}
@@ -0,0 +1,363 @@
+//! Validating adapters to be used with `#[serde(with = "...")]`.
+//!
+//! Errors raised by these adapters are located at the start of the offending value
+//! when deserializing from KEON.
+
+use serde::{
+    de::{DeserializeSeed, MapAccess, SeqAccess, Unexpected, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+};
+
+/// Human-readable forms of [`SystemTime`](std::time::SystemTime) and [`Duration`](std::time::Duration).
+mod time;
+
+pub use time::{duration, system_time};
+
+/// Numbers with units, e.g. `10k`, `2GiB` or `3ms`.
+#[cfg(feature = "unit-suffixes")]
+mod units;
+
+#[cfg(feature = "unit-suffixes")]
+pub use units::{byte_size, si, SiNumber};
+#[cfg(feature = "unit-suffixes")]
+pub(crate) use units::{is_unit_suffixed, SUFFIXED};
+
+/// Collections that can be deserialized by [`strict_set`] and [`set`].
+pub trait Set: Default {
+    type Item;
+    fn contains_item(&self, item: &Self::Item) -> bool;
+    fn insert_item(&mut self, item: Self::Item);
+}
+
+impl<T: Ord> Set for BTreeSet<T> {
+    type Item = T;
+    fn contains_item(&self, item: &T) -> bool {
+        self.contains(item)
+    }
+    fn insert_item(&mut self, item: T) {
+        self.insert(item);
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> Set for HashSet<T, S> {
+    type Item = T;
+    fn contains_item(&self, item: &T) -> bool {
+        self.contains(item)
+    }
+    fn insert_item(&mut self, item: T) {
+        self.insert(item);
+    }
+}
+
+/// Rejects duplicate elements of a set, which would otherwise be dropped silently.
+///
+/// Works with [`BTreeSet`] and [`HashSet`], serialization is unchanged.
+pub mod strict_set {
+    use super::*;
+
+    pub fn serialize<T: Serialize, S: Serializer>(set: &T, ser: S) -> Result<S::Ok, S::Error> {
+        set.serialize(ser)
+    }
+
+    pub fn deserialize<'de, T, D>(der: D) -> Result<T, D::Error>
+    where
+        T: Set,
+        T::Item: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        der.deserialize_seq(StrictSetVisitor(PhantomData))
+    }
+
+    struct StrictSetVisitor<T>(PhantomData<T>);
+    impl<'de, T> Visitor<'de> for StrictSetVisitor<T>
+    where
+        T: Set,
+        T::Item: Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a sequence without duplicate elements")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+            let mut set = T::default();
+            while seq.next_element_seed(UniqueSeed(&mut set))?.is_some() {}
+            Ok(set)
+        }
+    }
+
+    /// Inserts the element right after it was parsed, so the error points at the duplicate.
+    struct UniqueSeed<'a, T>(&'a mut T);
+    impl<'de, T> DeserializeSeed<'de> for UniqueSeed<'_, T>
+    where
+        T: Set,
+        T::Item: Deserialize<'de>,
+    {
+        type Value = ();
+
+        fn deserialize<D: Deserializer<'de>>(self, der: D) -> Result<(), D::Error> {
+            let item = T::Item::deserialize(der)?;
+            if self.0.contains_item(&item) {
+                return Err(serde::de::Error::custom("duplicate element in set"));
+            }
+            self.0.insert_item(item);
+            Ok(())
+        }
+    }
+}
+
+/// Writes a set as a map of unit values, e.g. `{ Audio, Video }` with
+/// [`SerializeConfig::set_sugar`](crate::SerializeConfig::set_sugar), as sets are sequences to Serde otherwise,
+/// and without it `{ Audio => (), Video => () }`.
+///
+/// Works with [`BTreeSet`] and [`HashSet`], read back from either such a map or a sequence.
+/// Other serializers see a map of unit values as well.
+pub mod set {
+    use super::*;
+
+    pub fn serialize<T, E, S>(set: &T, ser: S) -> Result<S::Ok, S::Error>
+    where
+        for<'a> &'a T: IntoIterator<Item = &'a E>,
+        E: Serialize,
+        S: Serializer,
+    {
+        ser.collect_map(set.into_iter().map(|item| (item, ())))
+    }
+
+    pub fn deserialize<'de, T, D>(der: D) -> Result<T, D::Error>
+    where
+        T: Set,
+        T::Item: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        der.deserialize_any(SetVisitor(PhantomData))
+    }
+
+    struct SetVisitor<T>(PhantomData<T>);
+    impl<'de, T> Visitor<'de> for SetVisitor<T>
+    where
+        T: Set,
+        T::Item: Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map of unit values or a sequence")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+            let mut set = T::default();
+            while let Some(item) = seq.next_element()? {
+                set.insert_item(item);
+            }
+            Ok(set)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+            let mut set = T::default();
+            while let Some(item) = map.next_key()? {
+                map.next_value::<()>()?;
+                set.insert_item(item);
+            }
+            Ok(set)
+        }
+    }
+}
+
+/// Writes the elements of a set in their order, e.g. for a `HashSet` to be written the same each time,
+/// as [`SerializeConfig::sort_keys`](crate::SerializeConfig::sort_keys) does for maps.
+///
+/// Deserialization is unchanged. Other serializers see a sorted sequence as well.
+pub mod sorted {
+    use super::*;
+
+    pub fn serialize<T, E, S>(set: &T, ser: S) -> Result<S::Ok, S::Error>
+    where
+        for<'a> &'a T: IntoIterator<Item = &'a E>,
+        E: Ord + Serialize,
+        S: Serializer,
+    {
+        let mut items = set.into_iter().collect::<Vec<_>>();
+        items.sort();
+        ser.collect_seq(items)
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(der: D) -> Result<T, D::Error> {
+        T::deserialize(der)
+    }
+}
+
+/// Rejects integers outside of `MIN..=MAX`, serialization is unchanged.
+///
+/// Used as `#[serde(with = "keon::adapters::ranged::<1, 65535>")]`.
+#[allow(non_camel_case_types)]
+pub struct ranged<const MIN: i128, const MAX: i128>;
+
+impl<const MIN: i128, const MAX: i128> ranged<MIN, MAX> {
+    pub fn serialize<T: Serialize, S: Serializer>(value: &T, ser: S) -> Result<S::Ok, S::Error> {
+        value.serialize(ser)
+    }
+
+    pub fn deserialize<'de, T, D>(der: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de> + Copy + TryInto<i128>,
+        D: Deserializer<'de>,
+    {
+        let value = T::deserialize(der)?;
+        match value.try_into() {
+            Ok(i) if (MIN..=MAX).contains(&i) => Ok(value),
+            Ok(i) => {
+                // Integers beyond 64 bits are written in full rather than truncated.
+                let full = format!("integer `{}`", i);
+                let unexpected = match i < 0 {
+                    true => i64::try_from(i).map_or(Unexpected::Other(&full), Unexpected::Signed),
+                    false => u64::try_from(i).map_or(Unexpected::Other(&full), Unexpected::Unsigned),
+                };
+                Err(serde::de::Error::invalid_value(
+                    unexpected,
+                    &format!("an integer within {}..={}", MIN, MAX).as_str(),
+                ))
+            }
+            Err(_) => Err(serde::de::Error::custom(format_args!(
+                "expected an integer within {}..={}",
+                MIN, MAX
+            ))),
+        }
+    }
+}
+
+/// Writes floats as hexadecimal float literals, e.g. `0x1.8p3` for `12.0`, which represent their bits exactly.
+///
+/// Deserialization is unchanged, any float literal can be read. Other serializers see a newtype struct.
+pub mod hex_float {
+    use super::*;
+
+    pub fn serialize<T: Copy + Into<f64>, S: Serializer>(v: &T, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_newtype_struct(HEX_FLOAT, &(*v).into())
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(der: D) -> Result<T, D::Error> {
+        T::deserialize(der)
+    }
+}
+
+/// The newtype name recognized by the serializer, see [`hex_float`].
+pub(crate) const HEX_FLOAT: &str = "$keon::private::HexFloat";
+
+/// Marks values behind a shared pointer, e.g. `Arc<T>` or `Rc<T>`, whose output is then written once
+/// and copied for the same pointer with [`SerializeConfig::memoize_shared`](crate::SerializeConfig::memoize_shared).
+///
+/// For elements of a collection, wrap the pointer in a `#[serde(transparent)]` newtype with this adapter on its field.
+/// Deserialization is unchanged, every value gets its own pointer. Other serializers see a newtype struct.
+pub mod shared {
+    use super::*;
+    use std::ops::Deref;
+
+    pub fn serialize<P, S>(v: &P, ser: S) -> Result<S::Ok, S::Error>
+    where
+        P: Deref,
+        P::Target: Serialize,
+        S: Serializer,
+    {
+        ser.serialize_newtype_struct(SHARED, &**v)
+    }
+
+    pub fn deserialize<'de, P, D>(der: D) -> Result<P, D::Error>
+    where
+        P: Deref + From<P::Target>,
+        P::Target: Deserialize<'de> + Sized,
+        D: Deserializer<'de>,
+    {
+        P::Target::deserialize(der).map(P::from)
+    }
+}
+
+/// The newtype name recognized by the serializer, see [`shared`].
+pub(crate) const SHARED: &str = "$keon::private::Shared";
+
+/// Rejects empty strings, serialization is unchanged.
+pub mod nonempty_string {
+    use super::*;
+
+    pub fn serialize<T: Serialize, S: Serializer>(s: &T, ser: S) -> Result<S::Ok, S::Error> {
+        s.serialize(ser)
+    }
+
+    pub fn deserialize<'de, T, D>(der: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de> + AsRef<str>,
+        D: Deserializer<'de>,
+    {
+        let s = T::deserialize(der)?;
+        match s.as_ref().is_empty() {
+            false => Ok(s),
+            true => Err(serde::de::Error::invalid_value(
+                Unexpected::Str(""),
+                &"a non-empty string",
+            )),
+        }
+    }
+}
+
+/// A regular expression for [`matching`].
+#[cfg(feature = "regex")]
+pub trait Pattern {
+    const PATTERN: &'static str;
+}
+
+/// Rejects strings not matching the regular expression of `P`, serialization is unchanged.
+///
+/// Used as `#[serde(with = "keon::adapters::matching::<MyPattern>")]`, where `MyPattern` implements [`Pattern`].
+/// Compiled expressions are cached, an invalid expression is reported as a deserialization error.
+#[cfg(feature = "regex")]
+#[allow(non_camel_case_types)]
+pub struct matching<P>(PhantomData<P>);
+
+#[cfg(feature = "regex")]
+impl<P: Pattern> matching<P> {
+    pub fn serialize<T: Serialize, S: Serializer>(s: &T, ser: S) -> Result<S::Ok, S::Error> {
+        s.serialize(ser)
+    }
+
+    pub fn deserialize<'de, T, D>(der: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de> + AsRef<str>,
+        D: Deserializer<'de>,
+    {
+        use regex::Regex;
+        use std::{
+            collections::BTreeMap,
+            sync::{Mutex, OnceLock},
+        };
+
+        static CACHE: OnceLock<Mutex<BTreeMap<&'static str, Regex>>> = OnceLock::new();
+
+        let re = {
+            let mut cache = CACHE.get_or_init(Default::default).lock().unwrap();
+            match cache.get(P::PATTERN) {
+                Some(re) => re.clone(),
+                None => {
+                    let re = Regex::new(P::PATTERN).map_err(serde::de::Error::custom)?;
+                    cache.insert(P::PATTERN, re.clone());
+                    re
+                }
+            }
+        };
+
+        let s = T::deserialize(der)?;
+        match re.is_match(s.as_ref()) {
+            true => Ok(s),
+            false => Err(serde::de::Error::invalid_value(
+                Unexpected::Str(s.as_ref()),
+                &format!("a string matching /{}/", P::PATTERN).as_str(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use super::*;
+use serde::de::{value::MapAccessDeserializer, MapAccess};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+const SECS_PER_DAY: i64 = 86400;
+
+/// Writes [`SystemTime`] as an RFC 3339 string in UTC, e.g. `"2025-01-06T14:13:08.8552212Z"`.
+///
+/// Years outside of `0000..=9999` are written with a sign and at least four digits as ISO 8601 expands them,
+/// e.g. `"+10000-01-01T00:00:00Z"`, and read back so.
+///
+/// Reads RFC 3339 strings with any offset, as well as the struct form
+/// `{ secs_since_epoch: ..., nanos_since_epoch: ... }` written without this adapter.
+pub mod system_time {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(time: &SystemTime, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(&format_rfc3339(*time))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(der: D) -> Result<SystemTime, D::Error> {
+        der.deserialize_any(SystemTimeVisitor)
+    }
+
+    struct SystemTimeVisitor;
+    impl<'de> Visitor<'de> for SystemTimeVisitor {
+        type Value = SystemTime;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an RFC 3339 timestamp or a SystemTime struct")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<SystemTime, E> {
+            parse_rfc3339(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<SystemTime, A::Error> {
+            SystemTime::deserialize(MapAccessDeserializer::new(map))
+        }
+    }
+}
+
+/// Writes [`Duration`] as a string in seconds, e.g. `"90s"` or `"0.25s"`.
+///
+/// Reads a sequence of numbers with units, e.g. `"1h 30m"`, `"1.5s"` or `"250ms"`,
+/// where units are `d`, `h`, `m`, `s`, `ms`, `us` and `ns`, `m` being minutes rather than milli as for `si`,
+/// as well as the struct form `{ secs: ..., nanos: ... }` written without this adapter.
+pub mod duration {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(dur: &Duration, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(&format!("{}{}s", dur.as_secs(), fraction(dur.subsec_nanos())))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(der: D) -> Result<Duration, D::Error> {
+        der.deserialize_any(DurationVisitor)
+    }
+
+    struct DurationVisitor;
+    impl<'de> Visitor<'de> for DurationVisitor {
+        type Value = Duration;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a duration like \"1h 30m\" or a Duration struct")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Duration, E> {
+            parse_duration(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Duration, A::Error> {
+            Duration::deserialize(MapAccessDeserializer::new(map))
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// `.123` without trailing zeros, or nothing.
+fn fraction(nanos: u32) -> String {
+    match nanos {
+        0 => String::new(),
+        _ => format!(".{:09}", nanos).trim_end_matches('0').to_string(),
+    }
+}
+
+fn format_rfc3339(time: SystemTime) -> String {
+    let (secs, nanos) = match time.duration_since(UNIX_EPOCH) {
+        Ok(dur) => (dur.as_secs() as i64, dur.subsec_nanos()),
+        Err(e) => {
+            let dur = e.duration();
+            match dur.subsec_nanos() {
+                0 => (-(dur.as_secs() as i64), 0),
+                n => (-(dur.as_secs() as i64) - 1, 1_000_000_000 - n),
+            }
+        }
+    };
+
+    let (days, secs_of_day) = (secs.div_euclid(SECS_PER_DAY), secs.rem_euclid(SECS_PER_DAY));
+    let (y, m, d) = civil_from_days(days);
+    let y = match y {
+        0..=9999 => format!("{:04}", y),
+        _ => format!("{:+05}", y),
+    };
+    format!(
+        "{}-{:02}-{:02}T{:02}:{:02}:{:02}{}Z",
+        y,
+        m,
+        d,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        fraction(nanos)
+    )
+}
+
+fn parse_rfc3339(s: &str) -> Option<SystemTime> {
+    fn digits(s: &str, range: core::ops::Range<usize>) -> Option<u32> {
+        let part = s.get(range)?;
+        match part.bytes().all(|b| b.is_ascii_digit()) {
+            true => part.parse().ok(),
+            false => None,
+        }
+    }
+
+    // Four digits, or a sign and up to twelve digits, which is beyond what `SystemTime` holds anyway.
+    let signed = s.starts_with(['+', '-']) as usize;
+    let year_len = signed + s[signed..].bytes().take_while(u8::is_ascii_digit).count();
+    if year_len - signed < 4 || year_len - signed > 12 || (signed == 0 && year_len != 4) {
+        return None;
+    }
+    let (y, s) = (s[..year_len].parse::<i64>().ok()?, &s[year_len..]);
+
+    let b = s.as_bytes();
+    if b.len() < 16 || (b[0], b[3], b[9], b[12]) != (b'-', b'-', b':', b':') || !matches!(b[6], b'T' | b't' | b' ') {
+        return None;
+    }
+
+    let (m, d) = (digits(s, 1..3)?, digits(s, 4..6)?);
+    let (hh, mm, ss) = (digits(s, 7..9)?, digits(s, 10..12)?, digits(s, 13..15)?);
+    if !(1..=12).contains(&m) || d == 0 || d > days_in_month(y, m) || hh > 23 || mm > 59 || ss > 59 {
+        return None;
+    }
+
+    let mut rest = &s[15..];
+    let mut nanos = 0;
+    if let Some(frac) = rest.strip_prefix('.') {
+        let len = frac.bytes().take_while(u8::is_ascii_digit).count();
+        if len == 0 || len > 9 {
+            return None;
+        }
+        nanos = frac[..len].parse::<u32>().ok()? * 10u32.pow(9 - len as u32);
+        rest = &frac[len..];
+    }
+
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let (oh, om) = (digits(rest, 1..3)?, digits(rest, 4..6)?);
+            if oh > 23 || om > 59 {
+                return None;
+            }
+            sign * (oh as i64 * 3600 + om as i64 * 60)
+        }
+        _ => return None,
+    };
+
+    let secs = days_from_civil(y, m, d)
+        .checked_mul(SECS_PER_DAY)?
+        .checked_add((hh * 3600 + mm * 60 + ss) as i64)?
+        .checked_sub(offset)?;
+    match secs >= 0 {
+        true => UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos)),
+        false => UNIX_EPOCH
+            .checked_sub(Duration::from_secs(secs.unsigned_abs()))?
+            .checked_add(Duration::from_nanos(nanos as u64)),
+    }
+}
+
+fn parse_duration(s: &str) -> Option<Duration> {
+    let mut total: u128 = 0;
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let int_len = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let int = &rest[..int_len];
+        rest = &rest[int_len..];
+
+        let mut frac = "";
+        if let Some(r) = rest.strip_prefix('.') {
+            let frac_len = r.bytes().take_while(u8::is_ascii_digit).count();
+            frac = &r[..frac_len];
+            rest = &r[frac_len..];
+        }
+        if int.is_empty() && frac.is_empty() {
+            return None;
+        }
+
+        let unit_len = rest.bytes().take_while(u8::is_ascii_alphabetic).count();
+        let unit: u128 = match &rest[..unit_len] {
+            "d" => 86400 * NANOS_PER_SEC,
+            "h" => 3600 * NANOS_PER_SEC,
+            "m" => 60 * NANOS_PER_SEC,
+            "s" => NANOS_PER_SEC,
+            "ms" => 1_000_000,
+            "us" => 1_000,
+            "ns" => 1,
+            _ => return None,
+        };
+        rest = rest[unit_len..].trim_start();
+
+        let int = match int {
+            "" => 0,
+            int => int.parse::<u128>().ok()?,
+        };
+        let frac = match frac.len() {
+            0 => 0,
+            n if n <= 18 => frac.parse::<u128>().ok()? * unit / 10u128.pow(n as u32),
+            _ => return None,
+        };
+        total = total.checked_add(int.checked_mul(unit)?.checked_add(frac)?)?;
+    }
+
+    let secs = u64::try_from(total / NANOS_PER_SEC).ok()?;
+    Some(Duration::new(secs, (total % NANOS_PER_SEC) as u32))
+}
+
+//------------------------------------------------------------------------------
+
+fn is_leap_year(y: i64) -> bool {
+    y % 4 == 0 && (y % 100 != 0 || y % 400 == 0)
+}
+
+fn days_in_month(y: i64, m: u32) -> u32 {
+    match m {
+        2 if is_leap_year(y) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar.
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let doy = (153 * ((m as i64 + 9) % 12) + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// The inverse of [`days_from_civil`].
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + (m <= 2) as i64;
+    (y, m, d)
+}
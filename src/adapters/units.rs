@@ -0,0 +1,285 @@
+use super::*;
+use serde::de::{
+    value::{F64Deserializer, I64Deserializer, U64Deserializer},
+    Error,
+};
+
+/// SI prefixes and their decimal exponents.
+const SI_PREFIXES: &[(&str, i32)] = &[
+    ("E", 18),
+    ("P", 15),
+    ("T", 12),
+    ("G", 9),
+    ("M", 6),
+    ("k", 3),
+    ("m", -3),
+    ("u", -6),
+    ("n", -9),
+    ("p", -12),
+];
+
+/// Units of [`byte_size`], binary ones first, as they are preferred when writing.
+const BYTE_UNITS: &[(&str, u64)] = &[
+    ("EiB", 1 << 60),
+    ("PiB", 1 << 50),
+    ("TiB", 1 << 40),
+    ("GiB", 1 << 30),
+    ("MiB", 1 << 20),
+    ("KiB", 1 << 10),
+    ("EB", 1_000_000_000_000_000_000),
+    ("PB", 1_000_000_000_000_000),
+    ("TB", 1_000_000_000_000),
+    ("GB", 1_000_000_000),
+    ("MB", 1_000_000),
+    ("kB", 1_000),
+    ("B", 1),
+];
+
+/// Units of [`duration`](super::duration).
+const TIME_UNITS: &[&str] = &["d", "h", "m", "s", "ms", "us", "ns"];
+
+/// Whether a number literal like `10k`, `2GiB` or `3ms` ends with a known unit,
+/// which the deserializer then visits as a string.
+pub(crate) fn is_unit_suffixed(text: &str) -> bool {
+    match split_suffix(text) {
+        Some((_, _, unit)) => {
+            SI_PREFIXES.iter().any(|(prefix, _)| *prefix == unit)
+                || BYTE_UNITS.iter().any(|(name, _)| *name == unit)
+                || TIME_UNITS.contains(&unit)
+        }
+        None => false,
+    }
+}
+
+/// Splits `-1_500.25kB` into the sign, the decimal number and the unit.
+fn split_suffix(text: &str) -> Option<(bool, &str, &str)> {
+    let (neg, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, text),
+    };
+    let len = text
+        .find(|ch: char| !(ch.is_ascii_digit() || ch == '_' || ch == '.'))
+        .unwrap_or(text.len());
+    let (num, unit) = text.split_at(len);
+
+    let valid_num =
+        num.starts_with(|ch: char| ch.is_ascii_digit()) && num.matches('.').count() <= 1 && !num.ends_with('.');
+    match valid_num && !unit.is_empty() && unit.bytes().all(|b| b.is_ascii_alphabetic()) {
+        true => Some((neg, num, unit)),
+        false => None,
+    }
+}
+
+/// The digits of a decimal number and its exponent, e.g. `(15025, -2)` for `150.25`.
+fn parse_decimal(num: &str) -> Option<(u128, i32)> {
+    let mut mant: u128 = 0;
+    let mut exp = 0;
+    let mut frac = false;
+    for ch in num.chars() {
+        match ch {
+            '0'..='9' => {
+                mant = mant.checked_mul(10)?.checked_add(ch as u128 - '0' as u128)?;
+                exp -= frac as i32;
+            }
+            '.' => frac = true,
+            _ => (),
+        }
+    }
+    Some((mant, exp))
+}
+
+/// A number as the most precise of `u64`, `i64` and `f64`.
+#[derive(Debug, Clone, Copy)]
+enum Num {
+    UInt(u64),
+    Int(i64),
+    Float(f64),
+}
+
+impl Num {
+    fn from_decimal(neg: bool, mant: u128, exp: i32) -> Option<Num> {
+        let int = match exp >= 0 {
+            true => 10u128.checked_pow(exp as u32).and_then(|pow| mant.checked_mul(pow)),
+            false => None,
+        };
+        match (int, neg) {
+            (Some(0), _) => Some(Num::UInt(0)),
+            (Some(n), false) => u64::try_from(n).ok().map(Num::UInt),
+            (Some(n), true) => i64::try_from(-i128::try_from(n).ok()?).ok().map(Num::Int),
+            (None, _) => {
+                let f = format!("{}e{}", mant, exp).parse::<f64>().ok()?;
+                Some(Num::Float(if neg { -f } else { f }))
+            }
+        }
+    }
+
+    fn deserialize<'de, T: Deserialize<'de>, E: Error>(self) -> Result<T, E> {
+        match self {
+            Num::UInt(u) => T::deserialize(U64Deserializer::<E>::new(u)),
+            Num::Int(i) => T::deserialize(I64Deserializer::<E>::new(i)),
+            Num::Float(f) => T::deserialize(F64Deserializer::<E>::new(f)),
+        }
+    }
+}
+
+/// Numbers that [`si`] can write with prefixes.
+pub trait SiNumber: Copy {
+    #[doc(hidden)]
+    fn to_i128(self) -> Option<i128>;
+    #[doc(hidden)]
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_si_number {
+    ($($ty:ty)*) => {$(
+        impl SiNumber for $ty {
+            fn to_i128(self) -> Option<i128> {
+                Some(self as i128)
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*};
+}
+
+impl_si_number! { i8 i16 i32 i64 isize u8 u16 u32 u64 usize }
+
+impl SiNumber for f32 {
+    fn to_i128(self) -> Option<i128> {
+        (self as f64).to_i128()
+    }
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl SiNumber for f64 {
+    fn to_i128(self) -> Option<i128> {
+        match self.fract() == 0.0 && self.abs() < 1e36 {
+            true => Some(self as i128),
+            false => None,
+        }
+    }
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+/// Writes the string as it is in KEON, e.g. `10k` without quotes. Other serializers see a newtype struct.
+fn serialize_suffixed<S: Serializer>(text: &str, ser: S) -> Result<S::Ok, S::Error> {
+    ser.serialize_newtype_struct(SUFFIXED, text)
+}
+
+/// The newtype name recognized by the serializer, see [`si`] and [`byte_size`].
+pub(crate) const SUFFIXED: &str = "$keon::private::Suffixed";
+
+/// Reads numbers with SI prefixes, e.g. `10k`, `2.5M` or `500u`, as well as plain numbers,
+/// into any primitive number type that can hold the value exactly.
+///
+/// Writes integers with the largest prefix that keeps them exact, e.g. `10k` but `1500`.
+/// Other numbers are written as they are.
+///
+/// `m` is milli here, so `3m` reads as `0.003`, whereas [`duration`](super::duration) reads `"3m"` as three minutes.
+pub mod si {
+    use super::*;
+
+    pub fn serialize<T: SiNumber, S: Serializer>(v: &T, ser: S) -> Result<S::Ok, S::Error> {
+        let text = match v.to_i128() {
+            Some(n) => {
+                let prefix = SI_PREFIXES.iter().filter(|(_, exp)| *exp > 0).find(|(_, exp)| {
+                    let pow = 10i128.pow(*exp as u32);
+                    n != 0 && n % pow == 0
+                });
+                match prefix {
+                    Some((prefix, exp)) => format!("{}{}", n / 10i128.pow(*exp as u32), prefix),
+                    None => n.to_string(),
+                }
+            }
+            None => format!("{:?}", v.to_f64()),
+        };
+        serialize_suffixed(&text, ser)
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(der: D) -> Result<T, D::Error> {
+        der.deserialize_any(SiVisitor(PhantomData))
+    }
+
+    struct SiVisitor<T>(PhantomData<T>);
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for SiVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a number, optionally with an SI prefix like `10k`")
+        }
+
+        fn visit_u64<E: Error>(self, v: u64) -> Result<T, E> {
+            Num::UInt(v).deserialize()
+        }
+        fn visit_i64<E: Error>(self, v: i64) -> Result<T, E> {
+            Num::Int(v).deserialize()
+        }
+        fn visit_f64<E: Error>(self, v: f64) -> Result<T, E> {
+            Num::Float(v).deserialize()
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<T, E> {
+            let num = split_suffix(v).and_then(|(neg, num, prefix)| {
+                let (_, exp) = SI_PREFIXES.iter().find(|(name, _)| *name == prefix)?;
+                let (mant, frac) = parse_decimal(num)?;
+                Num::from_decimal(neg, mant, frac + exp)
+            });
+            match num {
+                Some(num) => num.deserialize(),
+                None => Err(E::invalid_value(Unexpected::Str(v), &self)),
+            }
+        }
+    }
+}
+
+/// Reads sizes in bytes with units, e.g. `2GiB`, `1.5kB` or `512B`, as well as plain integers, into `u64`.
+///
+/// Units are `B`, decimal `kB` to `EB` and binary `KiB` to `EiB`, the size must be a whole number of bytes.
+/// Writes the largest binary unit that keeps the size exact, otherwise the largest decimal one, e.g. `2GiB` or `1500B`.
+pub mod byte_size {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(v: &u64, ser: S) -> Result<S::Ok, S::Error> {
+        let (name, unit) = BYTE_UNITS
+            .iter()
+            .find(|(_, unit)| *v != 0 && v % unit == 0)
+            .unwrap_or(&("B", 1));
+        serialize_suffixed(&format!("{}{}", v / unit, name), ser)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(der: D) -> Result<u64, D::Error> {
+        der.deserialize_any(ByteSizeVisitor)
+    }
+
+    struct ByteSizeVisitor;
+    impl<'de> Visitor<'de> for ByteSizeVisitor {
+        type Value = u64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a size in bytes like `2GiB`")
+        }
+
+        fn visit_u64<E: Error>(self, v: u64) -> Result<u64, E> {
+            Ok(v)
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<u64, E> {
+            let size = split_suffix(v).and_then(|(neg, num, name)| {
+                let (_, unit) = BYTE_UNITS.iter().find(|(unit, _)| *unit == name)?;
+                let (mant, exp) = parse_decimal(num)?;
+                let bytes = mant.checked_mul(*unit as u128)?;
+                let pow = 10u128.checked_pow(exp.unsigned_abs())?;
+                match !neg && bytes % pow == 0 {
+                    true => u64::try_from(bytes / pow).ok(),
+                    false => None,
+                }
+            });
+            size.ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+}
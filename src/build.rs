@@ -0,0 +1,140 @@
+//! Checking `.keon` assets bundled into binaries from build scripts, so that malformed ones fail compilation.
+//!
+//! ```no_run
+//! // build.rs
+//! # #[derive(serde::Deserialize)] struct Level;
+//! fn main() {
+//!     keon::build::validate_dir::<Level>("assets/levels");
+//! }
+//! ```
+
+use crate::{Error, Result};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/// A `.keon` file that is malformed or doesn't match the expected type.
+///
+/// Displayed as a diagnostic with the line of the source where it failed, e.g.
+///
+/// ```text
+/// error: expected comma
+///   --> assets/levels/intro.keon:3:15
+///    |
+///  3 |     name: "Intro" 5,
+///    |               ^
+///    = while parsing value of field `name`
+/// ```
+#[derive(Debug, Clone)]
+pub struct AssetError {
+    pub path: PathBuf,
+    pub error: Error,
+    source: String,
+}
+
+impl std::error::Error for AssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "error: {}", self.error.kind)?;
+        let Some(line) = self.error.line else {
+            writeln!(f, "  --> {}", self.path.display())?;
+            for context in &self.error.context {
+                writeln!(f, "   = while parsing {}", context)?;
+            }
+            return Ok(());
+        };
+
+        write!(f, "  --> {}:{}", self.path.display(), line)?;
+        if let Some(col) = self.error.col {
+            write!(f, ":{}", col)?;
+        }
+        writeln!(f)?;
+
+        let text = self.source.lines().nth(line.get() as usize - 1).unwrap_or_default();
+        let gutter = " ".repeat(line.to_string().len());
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line, text)?;
+        if let Some(col) = self.error.col {
+            // Tabs are kept to line up with the text.
+            let pad = text
+                .chars()
+                .take(col.get() as usize - 1)
+                .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                .collect::<String>();
+            writeln!(f, "{} | {}^", gutter, pad)?;
+        }
+        for context in &self.error.context {
+            writeln!(f, "{} = while parsing {}", gutter, context)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the file at `path` as `T`, and returns why it failed if it did.
+///
+/// A file that cannot be read fails with the error of the reader and no source.
+pub fn check_file<T: DeserializeOwned>(path: impl AsRef<Path>) -> Option<AssetError> {
+    let path = path.as_ref();
+    let (source, error) = match fs::read(path) {
+        Ok(bytes) => match crate::from_bytes::<T>(&bytes) {
+            Ok(_) => return None,
+            Err(e) => (String::from_utf8_lossy(&bytes).into_owned(), e),
+        },
+        Err(e) => (String::new(), e.into()),
+    };
+    Some(AssetError {
+        path: path.to_owned(),
+        error,
+        source,
+    })
+}
+
+/// Reads every `.keon` file under `dir`, including subdirectories, as `T`,
+/// and returns those that failed in the order of their paths, see [`check_file`].
+///
+/// Errors only if the directories cannot be read.
+pub fn check_dir<T: DeserializeOwned>(dir: impl AsRef<Path>) -> Result<Vec<AssetError>> {
+    let mut paths = Vec::new();
+    collect_paths(dir.as_ref(), &mut paths)?;
+    paths.sort();
+
+    Ok(paths.into_iter().filter_map(check_file::<T>).collect())
+}
+
+/// Like [`check_dir`], for build scripts: tells Cargo to rerun the script when anything under `dir` changes,
+/// and panics with the diagnostics of all failed files, which fails compilation.
+pub fn validate_dir<T: DeserializeOwned>(dir: impl AsRef<Path>) {
+    let dir = dir.as_ref();
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let failed = check_dir::<T>(dir).unwrap_or_else(|e| panic!("cannot read `{}`: {}", dir.display(), e));
+    if !failed.is_empty() {
+        let diagnostics = failed.iter().map(ToString::to_string).collect::<Vec<_>>();
+        panic!(
+            "{} of the KEON assets in `{}` are invalid:\n\n{}",
+            failed.len(),
+            dir.display(),
+            diagnostics.join("\n")
+        );
+    }
+}
+
+fn collect_paths(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        match entry.file_type()?.is_dir() {
+            true => collect_paths(&path, paths)?,
+            false if path.extension().is_some_and(|ext| ext == "keon") => paths.push(path),
+            false => (),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+//! Adapters to be used with `#[serde(with = "...")]`, writing a single field of bytes in a specific flavor
+//! regardless of [`SerializeConfig::bytes_flavor`](crate::SerializeConfig::bytes_flavor).
+//!
+//! The field is anything that is [`AsRef<[u8]>`](AsRef) and [`TryFrom<Vec<u8>>`](TryFrom),
+//! e.g. `Vec<u8>`, `Box<[u8]>` or a `[u8; 32]` holding a SHA-256 digest, and is always written as bytes
+//! rather than as a sequence. Deserialization accepts any flavor, as well as sequences of integers.
+//! Other serializers see a newtype struct of bytes.
+
+use crate::ser::BytesFlavor;
+use serde::{
+    de::{Error, SeqAccess, Visitor},
+    Deserializer, Serialize, Serializer,
+};
+use std::fmt;
+
+const PREFIX: &str = "$keon::private::bytes::";
+
+pub(crate) fn flavor_from_name(name: &str) -> Option<BytesFlavor> {
+    match name.strip_prefix(PREFIX)? {
+        "Normal" => Some(BytesFlavor::Normal),
+        #[cfg(feature = "syntax-basexx")]
+        "Base16" => Some(BytesFlavor::Base16),
+        #[cfg(feature = "syntax-basexx")]
+        "Base32" => Some(BytesFlavor::Base32),
+        #[cfg(feature = "syntax-basexx")]
+        "Base64" => Some(BytesFlavor::Base64),
+        _ => None,
+    }
+}
+
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_bytes(self.0)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("bytes")
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+macro_rules! flavor_adapter {
+    ($(#[$attr:meta])* $module:ident => $name:literal) => {
+        $(#[$attr])*
+        pub mod $module {
+            use super::*;
+
+            pub fn serialize<T: ?Sized + AsRef<[u8]>, S: Serializer>(v: &T, ser: S) -> Result<S::Ok, S::Error> {
+                ser.serialize_newtype_struct(concat!("$keon::private::bytes::", $name), &Bytes(v.as_ref()))
+            }
+
+            pub fn deserialize<'de, T: TryFrom<Vec<u8>>, D: Deserializer<'de>>(der: D) -> Result<T, D::Error> {
+                let bytes = der.deserialize_byte_buf(BytesVisitor)?;
+                let len = bytes.len();
+                T::try_from(bytes).map_err(|_| D::Error::invalid_length(len, &"bytes of the length of the field"))
+            }
+        }
+    };
+}
+
+flavor_adapter! {
+    /// Writes bytes as escaped strings, e.g. `b"\x7fELF"`.
+    normal => "Normal"
+}
+
+flavor_adapter! {
+    /// Writes bytes in Base16, e.g. `b16"7F454C46"`.
+    #[cfg(feature = "syntax-basexx")]
+    base16 => "Base16"
+}
+
+flavor_adapter! {
+    /// Writes bytes in Base32, e.g. `b32"P5CUYRQ"`.
+    #[cfg(feature = "syntax-basexx")]
+    base32 => "Base32"
+}
+
+flavor_adapter! {
+    /// Writes bytes in Base64 with the URL-safe alphabet, e.g. `b64"f0VMRg"`.
+    #[cfg(feature = "syntax-basexx")]
+    base64 => "Base64"
+}
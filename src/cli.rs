@@ -0,0 +1,223 @@
+//! Layering a KEON config file under the arguments matched by [`clap`], command line first.
+//!
+//! Each argument is placed at the path of its id, where `.` separates nested fields and `-` reads as `_`,
+//! e.g. `#[arg(long, id = "server.port")]` overrides `server: { port: 80 }`. The precedence is:
+//!
+//! 1. Values from the command line or the environment, as [`Source::CommandLine`] or [`Source::Env`].
+//! 2. Values from the file, as [`Source::File`].
+//! 3. Default values of the arguments, including `false` of flags, as [`Source::Default`].
+//!
+//! Arguments parsed as [`String`], [`OsString`](std::ffi::OsString) or [`PathBuf`](std::path::PathBuf) are
+//! taken verbatim, others are read as KEON values if they are valid ones, and as strings otherwise.
+//! With the builder API, give arguments that are not strings a value parser such as `value_parser!(u16)`.
+//! Arguments that can occur multiple times become sequences. A value replacing an option of the file
+//! is written as `? value`, so fields of `Option` type have to be in the file, e.g. as `?`, to be overridden.
+//! Subcommands are left to the caller, who can merge their matches into the same [`Merged`] with [`Merged::apply`].
+//!
+//! Requires the feature `clap`.
+
+use crate::{value::Map, Result, Value};
+use ::clap::{
+    builder::ValueParser,
+    parser::{ArgMatches, ValueSource},
+    Arg, ArgAction, Command,
+};
+use std::{collections::BTreeMap, ffi::OsStr, fs::File, path::Path, sync::Arc};
+
+/// Where a value of a [`Merged`] config came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Source {
+    /// The default value of the argument.
+    Default,
+    /// The config file.
+    File,
+    /// The environment variable of the argument.
+    Env,
+    /// The command line.
+    CommandLine,
+}
+
+/// A config file with the arguments applied, and where each value came from.
+#[derive(Debug, Clone, Default)]
+pub struct Merged {
+    value: Value,
+    sources: BTreeMap<String, Source>,
+}
+
+/// Reads the config file at `path` and applies the arguments, see the [module documentation](self).
+pub fn load<P: AsRef<Path>>(path: P, cmd: &Command, matches: &ArgMatches) -> Result<Merged> {
+    let config = Value::from_reader(File::open(path)?)?;
+    Ok(merge(config, cmd, matches))
+}
+
+/// Applies the arguments to a config, see the [module documentation](self).
+///
+/// A config that is not a map is replaced by one if any argument is present.
+pub fn merge(config: Value, cmd: &Command, matches: &ArgMatches) -> Merged {
+    let mut merged = Merged::new(config);
+    merged.apply(cmd, matches);
+    merged
+}
+
+impl Merged {
+    /// A config with every value from the file, to [`apply`](Self::apply) arguments to.
+    pub fn new(config: Value) -> Self {
+        let mut sources = BTreeMap::new();
+        record_leaves(&config, &mut String::new(), &mut sources);
+        Self { value: config, sources }
+    }
+
+    /// Applies the arguments of `cmd` present in `matches`, values from the command line or the environment
+    /// replace anything at their path, default values only fill paths without a value.
+    pub fn apply(&mut self, cmd: &Command, matches: &ArgMatches) {
+        for arg in cmd.get_arguments() {
+            let id = arg.get_id().as_str();
+            let source = match matches.value_source(id) {
+                Some(ValueSource::DefaultValue) => Source::Default,
+                Some(ValueSource::EnvVariable) => Source::Env,
+                Some(_) => Source::CommandLine,
+                None => continue,
+            };
+            let Some(mut raw) = matches.get_raw(id) else {
+                continue;
+            };
+
+            let value = if is_multiple(arg) {
+                Value::Seq(raw.map(|raw| parse(arg, raw)).collect())
+            } else {
+                match raw.next_back() {
+                    Some(raw) => parse(arg, raw),
+                    None => continue,
+                }
+            };
+
+            let path = id.replace('-', "_");
+            let value = match self.get(&path) {
+                Some(_) if source == Source::Default => continue,
+                Some(Value::Opt(_)) => Value::Opt(Some(Box::new(value))),
+                _ => value,
+            };
+            self.insert(path, value, source);
+        }
+    }
+
+    /// The value at a dotted path, e.g. `server.port`, looking into maps with string keys.
+    pub fn get(&self, path: &str) -> Option<&Value> {
+        path.split('.')
+            .try_fold(&self.value, |value, key| map_of(value)?.get(&Value::from(key)))
+    }
+
+    /// Where the value at a dotted path came from, that is, the source of the value itself
+    /// or of the nearest map containing it that was written as a whole.
+    ///
+    /// Maps with values from different sources have none, and neither do absent values.
+    pub fn source(&self, path: &str) -> Option<Source> {
+        self.get(path)?;
+        let mut prefix = path;
+        loop {
+            if let Some(&source) = self.sources.get(prefix) {
+                return Some(source);
+            }
+            prefix = &prefix[..prefix.rfind('.')?];
+        }
+    }
+
+    /// The dotted paths of all values and where they came from, in order of the paths.
+    pub fn sources(&self) -> impl Iterator<Item = (&str, Source)> + '_ {
+        self.sources.iter().map(|(path, &source)| (path.as_str(), source))
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+
+    /// Tries to deserialize the merged config into `T`.
+    pub fn into_rust<T: serde::de::DeserializeOwned>(self) -> Result<T> {
+        self.value.into_rust()
+    }
+
+    fn insert(&mut self, path: String, value: Value, source: Source) {
+        let mut keys = path.split('.').peekable();
+        let mut map = as_map(&mut self.value);
+        while let Some(key) = keys.next() {
+            let key = Value::from(key);
+            if keys.peek().is_none() {
+                map.insert(key, value);
+                break;
+            }
+            map = as_map(map.entry(key).or_insert(Value::Unit));
+        }
+
+        let nested = format!("{}.", path);
+        self.sources.retain(|other, _| !other.starts_with(&nested));
+        self.sources.insert(path, source);
+    }
+}
+
+/// Whether the argument can take more than one value, so that a sequence is expected at its path.
+fn is_multiple(arg: &Arg) -> bool {
+    matches!(arg.get_action(), ArgAction::Append) || arg.get_num_args().is_some_and(|range| range.max_values() > 1)
+}
+
+fn parse(arg: &Arg, raw: &OsStr) -> Value {
+    let raw = raw.to_string_lossy();
+    let ty = arg.get_value_parser().type_id();
+    let verbatim = [ValueParser::string(), ValueParser::os_string(), ValueParser::path_buf()]
+        .iter()
+        .any(|parser| parser.type_id() == ty);
+
+    match verbatim {
+        true => Value::from(raw.into_owned()),
+        false => Value::from_str(&raw).unwrap_or_else(|_| Value::from(raw.into_owned())),
+    }
+}
+
+/// The map in `value`, through options, newtypes and shared values.
+fn map_of(value: &Value) -> Option<&Map> {
+    match value {
+        Value::Map(map) => Some(map),
+        Value::Opt(Some(inner)) | Value::Newtype(inner) => map_of(inner),
+        Value::Shared(shared) => map_of(shared),
+        _ => None,
+    }
+}
+
+/// Like [`map_of`], replacing anything else by an empty map.
+fn as_map(value: &mut Value) -> &mut Map {
+    if map_of(value).is_none() {
+        *value = Value::Map(Map::new());
+    }
+    match value {
+        Value::Map(map) => map,
+        Value::Opt(Some(inner)) | Value::Newtype(inner) => as_map(inner),
+        Value::Shared(shared) => as_map(Arc::make_mut(shared)),
+        _ => unreachable!(),
+    }
+}
+
+fn record_leaves(value: &Value, path: &mut String, sources: &mut BTreeMap<String, Source>) {
+    match map_of(value) {
+        Some(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let Value::String(key) = key else {
+                    continue;
+                };
+                let len = path.len();
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+                record_leaves(value, path, sources);
+                path.truncate(len);
+            }
+        }
+        _ if !path.is_empty() => {
+            sources.insert(path.clone(), Source::File);
+        }
+        _ => (),
+    }
+}
@@ -0,0 +1,212 @@
+//! Writes KEON at compile time, see [`const_str!`](crate::const_str).
+
+/// Writes a value in Rust syntax as a KEON string literal at compile time, as
+/// [`SerializeConfig::minimal`](crate::SerializeConfig::minimal) writes it at runtime,
+/// e.g. to embed default configs into binaries.
+///
+/// ```
+/// # use serde::Deserialize;
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Config {
+///     name: String,
+///     port: u16,
+///     mode: Mode,
+///     tags: Vec<String>,
+///     proxy: Option<String>,
+/// }
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// enum Mode {
+///     Fast,
+/// }
+///
+/// const DEFAULT: &str = keon::const_str!(Config {
+///     name: "server",
+///     port: 8080,
+///     mode: Mode::Fast,
+///     tags: ["web", "api"],
+///     proxy: None,
+/// });
+/// assert_eq!(DEFAULT, r#"{name:"server",port:8080,mode:Fast,tags:["web","api"],proxy:?}"#);
+/// assert_eq!(keon::from_str::<Config>(DEFAULT).unwrap().mode, Mode::Fast);
+/// ```
+///
+/// Nothing is type checked, values are told apart by their syntax only:
+///
+/// - Literals are written as their tokens, which KEON reads the same, except raw strings and suffixed numbers, e.g. `1u8`.
+/// - Enum variants come with their paths, e.g. `Mode::Fast` or `Shape::Circle(1.0)`, and structs without,
+///   e.g. `Point(1, 2)` or `Marker`.
+/// - `None` and `Some(..)` are options.
+/// - `[..]` are sequences, `(..)` are tuples and `{ key => value, .. }` are maps.
+///
+/// Elements are munched one by one, or token by token if they're long, so large values may need
+/// a higher `#![recursion_limit]`.
+#[macro_export]
+macro_rules! const_str {
+    ($($value:tt)+) => {
+        $crate::__const_str_value!($($value)+)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_str_value {
+    (None) => {
+        "?"
+    };
+    (Some($($value:tt)+)) => {
+        concat!("?", $crate::__const_str_value!($($value)+))
+    };
+    ($literal:literal) => {
+        stringify!($literal)
+    };
+    (- $literal:literal) => {
+        concat!("-", stringify!($literal))
+    };
+    ([$($elems:tt)*]) => {
+        $crate::__const_str_elems!(seq [] [] $($elems)*)
+    };
+    (($($elems:tt)*)) => {
+        $crate::__const_str_elems!(tuple [] [] $($elems)*)
+    };
+    ({$($entries:tt)*}) => {
+        $crate::__const_str_elems!(map [] [] $($entries)*)
+    };
+    ($head:ident :: $($path:tt)+) => {
+        $crate::__const_str_variant!($($path)+)
+    };
+    ($name:ident($($elems:tt)*)) => {
+        $crate::__const_str_elems!(args [] [] $($elems)*)
+    };
+    ($name:ident {$($fields:tt)*}) => {
+        $crate::__const_str_elems!(fields [] [] $($fields)*)
+    };
+    ($name:ident) => {
+        "()"
+    };
+}
+
+/// The rest of the path of a variant.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_str_variant {
+    ($head:ident :: $($path:tt)+) => {
+        $crate::__const_str_variant!($($path)+)
+    };
+    ($variant:ident($($elems:tt)*)) => {
+        $crate::__const_str_elems!((args $variant) [] [] $($elems)*)
+    };
+    ($variant:ident {$($fields:tt)*}) => {
+        $crate::__const_str_elems!((fields $variant) [] [] $($fields)*)
+    };
+    ($variant:ident) => {
+        stringify!($variant)
+    };
+}
+
+/// Splits elements at commas, with the written ones so far and the tokens of the current one.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_str_elems {
+    // Elements of a few tokens at once, to stay within the recursion limit.
+    ($kind:tt [$($out:expr),*] [] $a:tt , $($rest:tt)*) => {
+        $crate::__const_str_elems!($kind [$($out,)* $crate::__const_str_entry!($kind $a)] [] $($rest)*)
+    };
+    ($kind:tt [$($out:expr),*] [] $a:tt $b:tt , $($rest:tt)*) => {
+        $crate::__const_str_elems!($kind [$($out,)* $crate::__const_str_entry!($kind $a $b)] [] $($rest)*)
+    };
+    ($kind:tt [$($out:expr),*] [] $a:tt $b:tt $c:tt , $($rest:tt)*) => {
+        $crate::__const_str_elems!($kind [$($out,)* $crate::__const_str_entry!($kind $a $b $c)] [] $($rest)*)
+    };
+    ($kind:tt [$($out:expr),*] [] $a:tt $b:tt $c:tt $d:tt , $($rest:tt)*) => {
+        $crate::__const_str_elems!($kind [$($out,)* $crate::__const_str_entry!($kind $a $b $c $d)] [] $($rest)*)
+    };
+    ($kind:tt [$($out:expr),*] [] $a:tt $b:tt $c:tt $d:tt $e:tt , $($rest:tt)*) => {
+        $crate::__const_str_elems!($kind [$($out,)* $crate::__const_str_entry!($kind $a $b $c $d $e)] [] $($rest)*)
+    };
+    ($kind:tt [$($out:expr),*] [] $a:tt $b:tt $c:tt $d:tt $e:tt $f:tt , $($rest:tt)*) => {
+        $crate::__const_str_elems!($kind [$($out,)* $crate::__const_str_entry!($kind $a $b $c $d $e $f)] [] $($rest)*)
+    };
+    ($kind:tt [$($out:expr),*] [$($cur:tt)+] , $($rest:tt)*) => {
+        $crate::__const_str_elems!($kind [$($out,)* $crate::__const_str_entry!($kind $($cur)+)] [] $($rest)*)
+    };
+    ($kind:tt [$($out:expr),*] [$($cur:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::__const_str_elems!($kind [$($out),*] [$($cur)* $token] $($rest)*)
+    };
+    ($kind:tt [$($out:expr),*] [$($cur:tt)+]) => {
+        $crate::__const_str_elems!($kind [$($out,)* $crate::__const_str_entry!($kind $($cur)+)] [])
+    };
+    (tuple [$elem:expr] []) => {
+        concat!("(", $elem, ",)")
+    };
+    (args [$elem:expr] []) => {
+        concat!("%", $elem)
+    };
+    ((args $variant:ident) [$elem:expr] []) => {
+        concat!(stringify!($variant), "%", $elem)
+    };
+    ($kind:tt [$($out:expr),*] []) => {
+        $crate::__const_str_join!($kind $($out),*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_str_entry {
+    (map $($pair:tt)+) => {
+        $crate::__const_str_pair!([] $($pair)+)
+    };
+    (fields $field:ident : $($value:tt)+) => {
+        concat!(stringify!($field), ":", $crate::__const_str_value!($($value)+))
+    };
+    ((fields $variant:ident) $field:ident : $($value:tt)+) => {
+        concat!(stringify!($field), ":", $crate::__const_str_value!($($value)+))
+    };
+    ($kind:tt $($value:tt)+) => {
+        $crate::__const_str_value!($($value)+)
+    };
+}
+
+/// Splits a map entry at `=>`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_str_pair {
+    ([$($key:tt)+] => $($value:tt)+) => {
+        concat!($crate::__const_str_value!($($key)+), "=>", $crate::__const_str_value!($($value)+))
+    };
+    ([$($key:tt)*] $token:tt $($rest:tt)*) => {
+        $crate::__const_str_pair!([$($key)* $token] $($rest)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_str_join {
+    (seq $($out:expr),*) => {
+        concat!("[", $crate::__const_str_join!(, $($out),*), "]")
+    };
+    (tuple $($out:expr),*) => {
+        concat!("(", $crate::__const_str_join!(, $($out),*), ")")
+    };
+    (args $($out:expr),*) => {
+        concat!("(", $crate::__const_str_join!(, $($out),*), ")")
+    };
+    ((args $variant:ident) $($out:expr),*) => {
+        concat!(stringify!($variant), "(", $crate::__const_str_join!(, $($out),*), ")")
+    };
+    (map $($out:expr),*) => {
+        concat!("{", $crate::__const_str_join!(, $($out),*), "}")
+    };
+    (fields $($out:expr),*) => {
+        concat!("{", $crate::__const_str_join!(, $($out),*), "}")
+    };
+    ((fields $variant:ident) $($out:expr),*) => {
+        concat!(stringify!($variant), "{", $crate::__const_str_join!(, $($out),*), "}")
+    };
+    (,) => {
+        ""
+    };
+    (, $first:expr $(, $rest:expr)*) => {
+        concat!($first $(, ",", $rest)*)
+    };
+}
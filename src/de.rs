@@ -1,11 +1,11 @@
 use super::{lexer::*, *};
 use logos::{Lexer, Logos};
 use serde::de::{
-    value::{EnumAccessDeserializer, StrDeserializer},
-    DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+    value::{BorrowedStrDeserializer, SeqAccessDeserializer, UnitDeserializer},
+    Deserialize, DeserializeSeed, EnumAccess, IgnoredAny, MapAccess, SeqAccess, Unexpected, VariantAccess, Visitor,
 };
 use smol_str::SmolStr;
-use std::num::NonZeroU32;
+use std::{borrow::Cow, num::NonZeroU32};
 
 /// Conveniently get `T` from deserialize a str.
 pub fn from_str<'de, T: serde::Deserialize<'de>>(s: &'de str) -> Result<T> {
@@ -15,6 +15,288 @@ pub fn from_str<'de, T: serde::Deserialize<'de>>(s: &'de str) -> Result<T> {
     Ok(val)
 }
 
+/// Conveniently get `T` from deserialize a reader.
+///
+/// The input is read to the end before deserializing, so `T` cannot borrow from it.
+pub fn from_reader<R: std::io::Read, T: serde::de::DeserializeOwned>(mut reader: R) -> Result<T> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    from_bytes(&bytes)
+}
+
+/// Conveniently get `T` from deserialize UTF-8 encoded bytes.
+///
+/// Invalid UTF-8 is reported with [`ErrorKind::InvalidUtf8`], located at where it starts.
+pub fn from_bytes<'de, T: serde::Deserialize<'de>>(bytes: &'de [u8]) -> Result<T> {
+    from_str(decode_utf8(bytes)?)
+}
+
+/// Like [`from_reader`], but see [`from_bytes_lossy`].
+pub fn from_reader_lossy<R: std::io::Read, T: serde::de::DeserializeOwned>(mut reader: R) -> Result<T> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    from_bytes_lossy(&bytes)
+}
+
+/// Like [`from_bytes`], but invalid UTF-8 inside string literals (including paragraphs)
+/// is replaced with `U+FFFD`, e.g. for legacy files whose free-text fields have mixed encodings.
+///
+/// Invalid UTF-8 anywhere else is still an error.
+pub fn from_bytes_lossy<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    from_str(&decode_utf8_lossy(bytes)?)
+}
+
+/// Like [`from_bytes_lossy`], but the encoding is detected from a byte order mark (BOM),
+/// so that files saved by e.g. Windows editors load as they are.
+///
+/// UTF-16LE and UTF-16BE are transcoded to UTF-8, where unpaired surrogates are replaced with `U+FFFD`.
+/// Without a BOM, the input is UTF-8. Offsets of [`ErrorKind::InvalidUtf8`] count the BOM.
+pub fn from_slice_lossy_encoding<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    let text = match bytes {
+        [0xFF, 0xFE, rest @ ..] => Cow::Owned(decode_utf16_lossy(rest, u16::from_le_bytes)),
+        [0xFE, 0xFF, rest @ ..] => Cow::Owned(decode_utf16_lossy(rest, u16::from_be_bytes)),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => decode_utf8_lossy(rest).map_err(|mut e| {
+            if let ErrorKind::InvalidUtf8 { offset, .. } = &mut e.kind {
+                *offset += 3;
+            }
+            e
+        })?,
+        _ => decode_utf8_lossy(bytes)?,
+    };
+    from_str(&text)
+}
+
+fn decode_utf16_lossy(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    let mut text: String = char::decode_utf16(units)
+        .map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect();
+    // A dangling byte at the end.
+    if bytes.len() % 2 != 0 {
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+    text
+}
+
+pub(crate) fn decode_utf8(bytes: &[u8]) -> Result<&str> {
+    std::str::from_utf8(bytes).map_err(|e| {
+        let offset = e.valid_up_to();
+        let (line, col) = locate_after(std::str::from_utf8(&bytes[..offset]).unwrap());
+        Error {
+            line,
+            col,
+            kind: invalid_utf8(bytes, offset, e.error_len()),
+            context: Vec::new(),
+        }
+    })
+}
+
+fn decode_utf8_lossy(bytes: &[u8]) -> Result<Cow<'_, str>> {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok(Cow::Borrowed(text));
+    }
+
+    // Positions of replacements in the text, and offsets of the invalid sequences in the bytes.
+    let mut replaced = Vec::new();
+    let mut text = String::with_capacity(bytes.len());
+    let mut offset = 0;
+    while let Err(e) = std::str::from_utf8(&bytes[offset..]) {
+        let valid = &bytes[offset..offset + e.valid_up_to()];
+        text.push_str(std::str::from_utf8(valid).unwrap());
+        replaced.push((text.len(), offset + valid.len(), e.error_len()));
+        text.push(char::REPLACEMENT_CHARACTER);
+        offset += valid.len() + e.error_len().unwrap_or(bytes.len() - offset - valid.len());
+    }
+    text.push_str(std::str::from_utf8(&bytes[offset..]).unwrap());
+
+    // Lexing errors are left to the deserializer.
+    let mut strings = Vec::new();
+    let mut lex = Token::lexer(&text);
+    while let Some(Ok(t)) = lex.next() {
+        if let Token::Literal(Literal::Str(_) | Literal::String(_)) = t {
+            strings.push(lex.span());
+        }
+    }
+
+    for (pos, offset, len) in replaced {
+        if !strings.iter().any(|span| span.contains(&pos)) {
+            let (line, col) = locate_after(&text[..pos]);
+            return Err(Error {
+                line,
+                col,
+                kind: invalid_utf8(bytes, offset, len),
+                context: Vec::new(),
+            });
+        }
+    }
+
+    Ok(Cow::Owned(text))
+}
+
+fn invalid_utf8(bytes: &[u8], offset: usize, len: Option<usize>) -> ErrorKind {
+    let len = len.unwrap_or(bytes.len() - offset).min(4);
+    ErrorKind::InvalidUtf8 {
+        offset,
+        bytes: bytes[offset..offset + len].to_vec(),
+        error: std::str::from_utf8(&bytes[offset..]).unwrap_err(),
+    }
+}
+
+/// Checks whether the document contains no value, only whitespaces and comments.
+///
+/// Returns `false` if the document cannot be tokenized, e.g. has an unclosed comment.
+pub fn is_empty_document(s: &str) -> bool {
+    matches!(Deserializer::from_str(s).peek(), Ok(None))
+}
+
+/// Like [`from_str`], but an empty document (only whitespaces and comments) produces `T::default()`.
+pub fn from_str_or_default<'de, T: serde::Deserialize<'de> + Default>(s: &'de str) -> Result<T> {
+    Ok(from_str_optional(s)?.unwrap_or_default())
+}
+
+/// Like [`from_str`], but an empty document (only whitespaces and comments) produces `None`.
+///
+/// Note that `from_str::<Option<T>>` still requires `?` for `None`, an empty document is an error there.
+pub fn from_str_optional<'de, T: serde::Deserialize<'de>>(s: &'de str) -> Result<Option<T>> {
+    let mut der = Deserializer::from_str(s);
+    if der.peek().map_err(|e| der.locate_error(e))?.is_none() {
+        return Ok(None);
+    }
+
+    let val = T::deserialize(&mut der)?;
+    der.finish()?;
+    Ok(Some(val))
+}
+
+//==================================================================================================
+
+/// Options that relax what the [`Deserializer`] accepts. Everything is strict by default,
+/// except that optional struct names are not checked unless [`check_struct_names`](Self::check_struct_names).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy)]
+pub struct DeserializeConfig {
+    /// Accepts bare `yes`/`on` and `no`/`off` as booleans, when a boolean is expected.
+    ///
+    /// Each use is reported through [`Deserializer::warnings`].
+    pub lenient_bools: bool,
+    /// How variant identifiers are matched against the variants of the expected enum.
+    pub variant_matching: VariantMatching,
+    /// Accepts the index of a variant (in declaration order, not the Rust discriminant) as a unit variant,
+    /// e.g. `2` for the third variant.
+    ///
+    /// Indices count all variants of the enum, but only name unit ones, e.g. `0` is rejected
+    /// for `enum Shape { Circle(f64), Dot }`, whose `Dot` is `1`.
+    pub variant_indices: bool,
+    /// Accepts map entries without value, e.g. `{ Apple, Banana }`, whose values are unit `()`.
+    /// Also accepts braces where a sequence is expected, e.g. for sets.
+    pub set_sugar: bool,
+    /// Accepts dotted keys of fields, e.g. `server.tls.enabled: true` for `server: { tls: { enabled: true } }`,
+    /// where consecutive entries sharing a prefix are merged, e.g. `server.host: "a", server.port: 80`.
+    ///
+    /// A key cannot be both dotted and written otherwise in the same map, nor dotted again after other entries.
+    pub dotted_keys: bool,
+    /// Accepts maps where a sequence of pairs is expected, e.g. `{ a => 1, a => 2 }` as `Vec<(K, V)>`,
+    /// keeping duplicate keys and their order, and sequences of pairs where a map is expected,
+    /// e.g. `[(a, 1), (a, 2)]`.
+    ///
+    /// No duplicate-key policy is applied in either direction, as for maps in braces:
+    /// duplicate keys are up to the type, e.g. the last one wins for `HashMap` and `BTreeMap`.
+    ///
+    /// Braces where a sequence is expected are read as sets instead if [`set_sugar`](Self::set_sugar) is enabled.
+    pub map_pairs: bool,
+    /// Accepts single-quoted strings, e.g. `'like this'`, to ease migration from YAML or TOML.
+    ///
+    /// Exactly one character (or escape) between the primes is still a char literal, e.g. `'a'` or `'\n'`,
+    /// which can be deserialized into a string as well.
+    pub single_quoted_strings: bool,
+    /// The maximum nesting depth of values, [`RECURSION_LIMIT`](crate::limits::RECURSION_LIMIT) by default.
+    pub recursion_limit: usize,
+    /// The maximum length of a bytes literal after decoding, unlimited by default.
+    ///
+    /// Checked before base16/32/64 literals are decoded and while escaped ones are accumulated,
+    /// so untrusted input cannot make them allocate more.
+    pub max_bytes_len: usize,
+    /// The maximum length in bytes (UTF-8) of a string literal after unescaping, unlimited by default.
+    ///
+    /// Checked while escaped strings and paragraphs are accumulated, like [`max_bytes_len`](Self::max_bytes_len).
+    pub max_string_len: usize,
+    /// Keeps `` ` `` lines of paragraphs as they are after the separating space,
+    /// including whitespace-only lines and trailing whitespace, e.g. for indented code blocks.
+    #[cfg(feature = "syntax-paragraph")]
+    pub preserve_paragraph_indent: bool,
+    /// Rejects struct names, e.g. `(Save)`, other than the name of the expected struct
+    /// or an alias registered by [`Deserializer::alias`].
+    pub check_struct_names: bool,
+    /// Accepts the placeholder `_` as the value of a field, e.g. `retries: _`, which is then missing,
+    /// so that it's `None` for an `Option` or its default with `#[serde(default)]`, and an error otherwise.
+    ///
+    /// Only plain fields can be placeholders, not dotted keys.
+    pub default_placeholders: bool,
+}
+
+impl Default for DeserializeConfig {
+    fn default() -> Self {
+        Self::strict()
+    }
+}
+
+impl DeserializeConfig {
+    pub const fn strict() -> Self {
+        Self {
+            lenient_bools: false,
+            variant_matching: VariantMatching::Exact,
+            variant_indices: false,
+            set_sugar: false,
+            dotted_keys: false,
+            map_pairs: false,
+            single_quoted_strings: false,
+            recursion_limit: RECURSION_LIMIT,
+            max_bytes_len: usize::MAX,
+            max_string_len: usize::MAX,
+            #[cfg(feature = "syntax-paragraph")]
+            preserve_paragraph_indent: false,
+            check_struct_names: false,
+            default_placeholders: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VariantMatching {
+    /// `RocketLauncher` only.
+    #[default]
+    Exact,
+    /// Also `rocketlauncher` and `ROCKETLAUNCHER`, and unit variants named in quotes, e.g. `"RocketLauncher"`.
+    CaseInsensitive,
+    /// Also `rocket_launcher` and `ROCKET_LAUNCHER`, underscores are ignored.
+    ///
+    /// Hyphens cannot be written in identifiers, but are ignored in the names of variants as well,
+    /// e.g. `rocket_launcher` matches a variant renamed by `#[serde(rename_all = "kebab-case")]`.
+    /// Unit variants may also be named in quotes, e.g. `"rocket-launcher"` matches `RocketLauncher`.
+    Normalized,
+}
+
+impl VariantMatching {
+    fn matches(self, variant: &str, ident: &str) -> bool {
+        match self {
+            Self::Exact => variant == ident,
+            Self::CaseInsensitive => variant
+                .chars()
+                .flat_map(char::to_lowercase)
+                .eq(ident.chars().flat_map(char::to_lowercase)),
+            Self::Normalized => {
+                let normalize = |s: &str| {
+                    s.chars()
+                        .filter(|ch| !matches!(ch, '_' | '-'))
+                        .flat_map(char::to_lowercase)
+                        .collect::<String>()
+                };
+                normalize(variant) == normalize(ident)
+            }
+        }
+    }
+}
+
 //==================================================================================================
 
 /// The accessible peekable lexer wrapper.
@@ -22,6 +304,8 @@ struct Kexer<'i> {
     lex: Lexer<'i, Token<'i>>,
     peeked: Option<Option<LexerResult<Token<'i>>>>,
     offset: usize,
+    /// Where the last consumed token starts, while the lexer may be a token ahead after a peek.
+    consumed: Mark<'i>,
 }
 
 impl<'i> Kexer<'i> {
@@ -30,6 +314,10 @@ impl<'i> Kexer<'i> {
             lex: Token::lexer(s),
             peeked: None,
             offset: 0,
+            consumed: Mark {
+                line: 0,
+                before: Some(""),
+            },
         }
     }
 
@@ -47,6 +335,7 @@ impl<'i> Iterator for Kexer<'i> {
             None => self.lex.next(),
         };
         self.offset = self.lex.span().end;
+        self.consumed = Mark::of(&self.lex);
         t
     }
 }
@@ -54,10 +343,22 @@ impl<'i> Iterator for Kexer<'i> {
 macro_rules! unwrap_ident {
     ($expr:expr) => {{
         let Token::Ident(name) = $expr else { unreachable!() };
-        SmolStr::new(name)
+        name
     }};
 }
 
+/// Like [`serde::forward_to_deserialize_any`], but to another method of `self`,
+/// so that the expected type is known apart from `deserialize_any`, see [`parse_buffered_variant`].
+macro_rules! forward_to {
+    ($to:ident: $($method:ident($($ty:ty),*))*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $(_: $ty,)* vis: V) -> Result<V::Value> {
+                self.$to(vis)
+            }
+        )*
+    };
+}
+
 //==================================================================================================
 
 /// The KEON deserializer.
@@ -66,17 +367,159 @@ macro_rules! unwrap_ident {
 pub struct Deserializer<'de> {
     kex: Kexer<'de>,
     ttl: usize,
+    cfg: DeserializeConfig,
+    warnings: Vec<Warning>,
+    /// Old struct names and the names they stand for, see [`Deserializer::alias`].
+    aliases: Vec<(SmolStr, SmolStr)>,
+    /// See [`Deserializer::number_parser`].
+    number_parsers: Vec<NumberParser>,
+
+    /// What the next [`deserialize_any`](serde::Deserializer::deserialize_any) is expected to produce.
+    hint: Option<Hint>,
+}
+
+/// See [`Deserializer::number_parser`].
+type NumberParser = Box<dyn Fn(&str) -> Option<Value> + Send + Sync>;
+
+/// Line and column, see [`Error`].
+type Location = (Option<NonZeroU32>, Option<NonZeroU32>);
+
+/// Where a token starts, resolved to a [`Location`] only when an error is located there,
+/// as counting the characters of a long line is not free, see [`Deserializer::mark`].
+#[derive(Debug, Clone, Copy)]
+struct Mark<'de> {
+    line: u32,
+    /// The text before the token on its line, `None` if the token started before the line.
+    before: Option<&'de str>,
+}
+
+impl<'de> Mark<'de> {
+    /// Where the token last read by the lexer starts.
+    fn of(lex: &Lexer<'de, Token<'de>>) -> Self {
+        let InnerExtras { line, line_start, .. } = lex.extras;
+        let token_start = lex.span().start;
+        Mark {
+            line,
+            // Otherwise we encountered unexpected newline.
+            before: (line_start <= token_start).then(|| &lex.source()[line_start..token_start]),
+        }
+    }
+
+    fn resolve(self) -> Location {
+        (
+            Some(NonZeroU32::new(self.line + 1).unwrap()),
+            self.before
+                .map(|text| NonZeroU32::new(text.chars().count() as u32 + 1).unwrap()),
+        )
+    }
+
+    fn error(self, kind: ErrorKind) -> Error {
+        self.locate(Error::new(kind))
+    }
+
+    fn locate(self, e: Error) -> Error {
+        let (line, col) = self.resolve();
+        Error { line, col, ..e }
+    }
+}
+
+/// Line and column right after the last character of `text`.
+pub(crate) fn locate_after(text: &str) -> Location {
+    let last_line = text.rsplit('\n').next().unwrap_or_default();
+    (
+        NonZeroU32::new(text.matches('\n').count() as u32 + 1),
+        NonZeroU32::new(last_line.chars().count() as u32 + 1),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Hint {
+    Enum(&'static [&'static str]),
+    Seq,
+    Tuple(usize),
+    Struct(&'static str),
+    TupleStruct(&'static str, usize),
+    Map,
+    /// Any other type, only [`deserialize_any`](serde::Deserializer::deserialize_any) called directly has no hint.
+    Typed,
+}
+
+impl Hint {
+    fn variants(self) -> Option<&'static [&'static str]> {
+        match self {
+            Hint::Enum(variants) => Some(variants),
+            _ => None,
+        }
+    }
+
+    fn len(self) -> Option<usize> {
+        match self {
+            Hint::Tuple(len) | Hint::TupleStruct(_, len) => Some(len),
+            _ => None,
+        }
+    }
+
+    fn struct_name(self) -> Option<&'static str> {
+        match self {
+            Hint::Struct(name) | Hint::TupleStruct(name, _) => Some(name),
+            _ => None,
+        }
+    }
 }
 
 impl<'de> Deserializer<'de> {
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(source: &'de str) -> Self {
+        Self::with_config(source, DeserializeConfig::strict())
+    }
+
+    pub fn with_config(source: &'de str, cfg: DeserializeConfig) -> Self {
+        let mut kex = Kexer::from_str(source);
+        {
+            let extras = &mut kex.lex.extras;
+            extras.single_quoted_strings = cfg.single_quoted_strings;
+            extras.max_bytes_len = cfg.max_bytes_len;
+            extras.max_string_len = cfg.max_string_len;
+            #[cfg(feature = "syntax-paragraph")]
+            {
+                extras.preserve_paragraph_indent = cfg.preserve_paragraph_indent;
+            }
+        }
+
         Self {
-            kex: Kexer::from_str(source),
-            ttl: RECURSION_LIMIT,
+            kex,
+            ttl: cfg.recursion_limit,
+            cfg,
+            warnings: Vec::new(),
+            aliases: Vec::new(),
+            number_parsers: Vec::new(),
+            hint: None,
         }
     }
 
+    /// Accepts the struct name `old` for the struct named `name`, e.g. `alias("SaveV1", "Save")`
+    /// to load files written with an older type name when [`DeserializeConfig::check_struct_names`].
+    pub fn alias(&mut self, old: &str, name: &str) -> &mut Self {
+        self.aliases.push((SmolStr::new(old), SmolStr::new(name)));
+        self
+    }
+
+    /// Lets `parser` claim numbers followed by a suffix or a fraction, which are rejected otherwise,
+    /// e.g. fixed-point decimals `1.25d`, rationals `1/3` or quantities `3.5m`.
+    ///
+    /// Parsers are tried in the order they were added, the first one returning a value claims the literal,
+    /// and the value is deserialized into the expected type, e.g. `Value::Newtype` for a newtype struct.
+    /// Like any [`Value`], it cannot be deserialized into an enum.
+    pub fn number_parser(&mut self, parser: impl Fn(&str) -> Option<Value> + Send + Sync + 'static) -> &mut Self {
+        self.number_parsers.push(Box::new(parser));
+        self
+    }
+
+    /// Returns the warnings collected so far, only lenient options produce them.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
     /// Returns the offset on source (in bytes) since the last deserialization.
     pub fn offset(&self) -> usize {
         self.kex.offset
@@ -92,19 +535,127 @@ impl<'de> Deserializer<'de> {
     }
 
     fn raise_error<T>(&self, kind: ErrorKind) -> Result<T> {
-        let InnerExtras { line, line_start } = *self.kex.lex.extras.borrow();
-        let token_start = self.kex.lex.span().start;
-        let col = (line_start <= token_start) // otherwise we encountered unexpected newline.
-            .then(|| self.kex.lex.source()[line_start..token_start].chars().count() as u32 + 1);
-
+        let (line, col) = self.locate();
         Err(Error {
-            line: Some(NonZeroU32::new(line + 1).unwrap()),
-            col: col.map(|n| NonZeroU32::new(n).unwrap()),
+            line,
+            col,
             kind,
+            context: Vec::new(),
+        })
+    }
+
+    /// Line and column right after the last character.
+    fn locate_end(&self) -> Location {
+        locate_after(self.kex.lex.source())
+    }
+
+    /// Locates the error at the last token, unless it has been located.
+    ///
+    /// Errors of the visitor are about what it was given, so they are located at the last consumed token
+    /// rather than at a token peeked after it, e.g. at `Foo` rather than `}` in `{ level: Foo }`.
+    fn locate_error(&self, e: Error) -> Error {
+        match e.line {
+            Some(_) => e,
+            None => {
+                let (line, col) = match e.kind {
+                    ErrorKind::Deserialize(_) => self.kex.consumed.resolve(),
+                    _ => self.locate(),
+                };
+                Error { line, col, ..e }
+            }
+        }
+    }
+
+    fn warn(&mut self, kind: WarningKind) {
+        let (line, col) = self.locate();
+        self.warnings.push(Warning { line, col, kind })
+    }
+
+    /// Line and column of the last token, consumed or peeked.
+    fn locate(&self) -> Location {
+        self.mark().resolve()
+    }
+
+    /// Like [`locate`](Self::locate), but only resolved when needed.
+    fn mark(&self) -> Mark<'de> {
+        Mark::of(&self.kex.lex)
+    }
+
+    /// Errors raised by the `seed` itself after the value was parsed, e.g. by validating
+    /// [`adapters`](crate::adapters), are located at the start of the value.
+    fn deserialize_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value> {
+        self.peek()?;
+        let start = self.mark();
+        seed.deserialize(&mut *self).map_err(|e| match e.line {
+            Some(_) => e,
+            None => start.locate(e),
         })
     }
 
-    fn next(&mut self) -> Result<Option<Token>> {
+    /// Where the expected type is known but has no hint of its own, see [`Hint::Typed`].
+    fn deserialize_typed<V: Visitor<'de>>(&mut self, vis: V) -> Result<V::Value> {
+        self.hint = Some(Hint::Typed);
+        serde::Deserializer::deserialize_any(self, vis)
+    }
+
+    /// Resolves an identifier to one of the expected variants according to [`VariantMatching`].
+    fn match_variant(&self, ident: &'de str, variants: &'static [&'static str]) -> &'de str {
+        match variants.contains(&ident) {
+            true => ident,
+            false => variants
+                .iter()
+                .find(|variant| self.cfg.variant_matching.matches(variant, ident))
+                .map_or(ident, |variant| variant),
+        }
+    }
+
+    /// Consumes the comma `,` after an element, and the closing delimiter if it follows (a trailing comma).
+    /// Without the comma, the closing delimiter is required.
+    ///
+    /// Returns whether more elements follow.
+    fn expect_separator(&mut self, close: TokenKind) -> Result<bool> {
+        match self.try_consume_token(TokenKind::Comma)? {
+            Some(_) => Ok(self.try_consume_token(close)?.is_none()),
+            None => {
+                self.expect_consume_token(close, ErrorKind::ExpectedComma)?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Checks the struct name written in `(Name)` against the expected one, see [`DeserializeConfig::check_struct_names`].
+    fn check_struct_name(&self, name: &str, expected: Option<&'static str>) -> Result<()> {
+        match expected {
+            Some(expected) if self.cfg.check_struct_names && name != expected => {
+                match self.aliases.iter().any(|(old, new)| old == name && new == expected) {
+                    true => Ok(()),
+                    false => Error::raise(ErrorKind::UnexpectedStructName {
+                        expected: expected.to_string(),
+                        found: name.to_string(),
+                    }),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Describes the peeked token for error messages, e.g. `` identifier `X` ``, long texts are truncated.
+    fn describe_peeked(&self, tk: TokenKind) -> String {
+        const MAX_CHARS: usize = 24;
+
+        let text = self.kex.lex.slice();
+        let text = match text.char_indices().nth(MAX_CHARS) {
+            Some((i, _)) => format!("`{}...`", &text[..i]),
+            None => format!("`{}`", text),
+        };
+        match tk {
+            TokenKind::Ident => format!("identifier {}", text),
+            TokenKind::Literal => format!("literal {}", text),
+            _ => text,
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<Token<'de>>> {
         match self.kex.next() {
             None => Ok(None),
             Some(res) => match res {
@@ -124,7 +675,7 @@ impl<'de> Deserializer<'de> {
         }
     }
 
-    fn expect_next(&mut self) -> Result<Token> {
+    fn expect_next(&mut self) -> Result<Token<'de>> {
         match self.next()? {
             Some(t) => Ok(t),
             None => Error::raise(ErrorKind::UnexpectedEof),
@@ -138,7 +689,7 @@ impl<'de> Deserializer<'de> {
         }
     }
 
-    fn expect_consume_token(&mut self, token_kind: TokenKind, error_kind: ErrorKind) -> Result<Token> {
+    fn expect_consume_token(&mut self, token_kind: TokenKind, error_kind: ErrorKind) -> Result<Token<'de>> {
         match self.next()? {
             Some(t) => match t.kind() == token_kind {
                 true => Ok(t),
@@ -148,7 +699,7 @@ impl<'de> Deserializer<'de> {
         }
     }
 
-    fn try_consume_token(&mut self, token_kind: TokenKind) -> Result<Option<Token>> {
+    fn try_consume_token(&mut self, token_kind: TokenKind) -> Result<Option<Token<'de>>> {
         match self.peek()? {
             Some(tk) => match tk == token_kind {
                 true => self.next(),
@@ -162,9 +713,15 @@ impl<'de> Deserializer<'de> {
 impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     type Error = Error;
     serde::forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf option unit unit_struct newtype_struct seq tuple
-        tuple_struct map struct enum identifier ignored_any
+        ignored_any
+    }
+    forward_to! {
+        deserialize_typed:
+        deserialize_i8() deserialize_i16() deserialize_i32() deserialize_i64() deserialize_i128()
+        deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64() deserialize_u128()
+        deserialize_f32() deserialize_f64() deserialize_char() deserialize_str() deserialize_string()
+        deserialize_bytes() deserialize_byte_buf() deserialize_option() deserialize_unit()
+        deserialize_identifier()
     }
 
     fn deserialize_any<V: Visitor<'de>>(self, vis: V) -> Result<V::Value> {
@@ -175,28 +732,131 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
 
         self.ttl = ttl;
 
+        let hint = self.hint.take();
+        let variants = hint.and_then(Hint::variants);
+        let len = hint.and_then(Hint::len);
+        let struct_name = hint.and_then(Hint::struct_name);
+        let cfg = self.cfg;
+        let claims_numbers = !self.number_parsers.is_empty();
+        let quoted_variants = cfg.variant_matching != VariantMatching::Exact;
+
         let val = match self.expect_next() {
             Ok(t) => match t {
+                Token::Literal(Literal::UInt(index)) if cfg.variant_indices && variants.is_some() => {
+                    parse_variant_index(self, vis, index, variants.unwrap())
+                }
+                Token::Literal(Literal::Suffixed(text)) if claims_numbers => parse_suffixed(self, vis, text),
+                Token::Literal(Literal::Str(name)) if quoted_variants && variants.is_some() => {
+                    parse_quoted_variant(self, vis, name, variants.unwrap())
+                }
+                Token::Literal(Literal::String(name)) if quoted_variants && variants.is_some() => {
+                    parse_quoted_variant(self, vis, &name, variants.unwrap())
+                }
                 Token::Literal(literal) => parse_literal(literal, vis),
                 Token::Question => parse_option(self, vis),
-                Token::Paren_ => parse_parenthesis(self, vis),
-                Token::Brack_ => parse_seq(self, vis),
+                Token::Paren_ => parse_parenthesis(self, vis, len, struct_name),
+                Token::Brack_ if cfg.map_pairs && hint == Some(Hint::Map) => parse_seq_pairs(self, vis),
+                Token::Brack_ => parse_seq(self, vis, len),
+                Token::Brace_ if cfg.set_sugar && hint == Some(Hint::Seq) => parse_set(self, vis),
+                Token::Brace_ if cfg.map_pairs && hint == Some(Hint::Seq) => parse_map_pairs(self, vis),
                 Token::Brace_ => parse_map(self, vis),
                 Token::Percent => parse_mayary(self, vis),
-                Token::Ident(ident) => {
-                    let name = SmolStr::new(ident);
-                    parse_enum(self, vis, name)
-                }
+                Token::Ident(ident) => match hint {
+                    None => parse_buffered_variant(self, vis, ident),
+                    Some(_) => parse_enum(self, vis, ident, variants),
+                },
                 _ => Error::raise(ErrorKind::UnexpectedToken),
             }
-            .or_else(|e| self.raise_error(e.kind)),
-            Err(e) => self.raise_error(e.kind),
+            .map_err(|e| self.locate_error(e)),
+            Err(e) => match e.kind {
+                ErrorKind::UnexpectedEof if is_empty_document(self.kex.lex.source()) => {
+                    let (line, col) = self.locate_end();
+                    Err(Error {
+                        line,
+                        col,
+                        kind: ErrorKind::EmptyDocument,
+                        context: Vec::new(),
+                    })
+                }
+                _ => self.raise_error(e.kind),
+            },
         };
 
         self.ttl += 1;
 
         val
     }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, vis: V) -> Result<V::Value> {
+        if self.cfg.lenient_bools {
+            let lenient = match self.kex.peek() {
+                Some(Ok(Token::Ident(ident))) => match *ident {
+                    "yes" | "on" => Some(true),
+                    "no" | "off" => Some(false),
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            if let Some(b) = lenient {
+                let ident = unwrap_ident!(self.next()?.unwrap());
+                self.warn(WarningKind::LenientBool(ident.to_string()));
+                return vis.visit_bool(b);
+            }
+        }
+
+        self.deserialize_typed(vis)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        vis: V,
+    ) -> Result<V::Value> {
+        self.hint = Some(Hint::Enum(variants));
+        self.deserialize_any(vis)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, vis: V) -> Result<V::Value> {
+        self.hint = Some(Hint::Seq);
+        self.deserialize_any(vis)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, vis: V) -> Result<V::Value> {
+        self.hint = Some(Hint::Map);
+        self.deserialize_any(vis)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, vis: V) -> Result<V::Value> {
+        self.hint = Some(Hint::Tuple(len));
+        self.deserialize_any(vis)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, name: &'static str, len: usize, vis: V) -> Result<V::Value> {
+        self.hint = Some(Hint::TupleStruct(name, len));
+        self.deserialize_any(vis)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        vis: V,
+    ) -> Result<V::Value> {
+        self.hint = Some(Hint::Struct(name));
+        self.deserialize_any(vis)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &'static str, vis: V) -> Result<V::Value> {
+        self.hint = Some(Hint::Struct(name));
+        self.deserialize_any(vis)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, name: &'static str, vis: V) -> Result<V::Value> {
+        self.hint = Some(Hint::Struct(name));
+        self.deserialize_any(vis)
+    }
 }
 
 fn parse_literal<'de, V: Visitor<'de>>(literal: Literal, vis: V) -> Result<V::Value> {
@@ -210,6 +870,22 @@ fn parse_literal<'de, V: Visitor<'de>>(literal: Literal, vis: V) -> Result<V::Va
         Literal::String(s) => vis.visit_string(s),
         Literal::Bytes(bytes) => vis.visit_bytes(bytes),
         Literal::ByteBuf(buf) => vis.visit_byte_buf(buf),
+        Literal::Suffixed(text) => {
+            #[cfg(feature = "unit-suffixes")]
+            if crate::adapters::is_unit_suffixed(text) {
+                return vis.visit_str(text);
+            }
+            Error::raise(ErrorKind::UnclaimedNumber(text.to_string()))
+        }
+    }
+}
+
+/// A number followed by a suffix or a fraction, claimed by one of [`Deserializer::number_parser`],
+/// or read as a unit otherwise.
+fn parse_suffixed<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, vis: V, text: &str) -> Result<V::Value> {
+    match der.number_parsers.iter().find_map(|parser| parser(text)) {
+        Some(value) => serde::Deserializer::deserialize_any(value, vis),
+        None => parse_literal(Literal::Suffixed(text), vis),
     }
 }
 
@@ -235,14 +911,25 @@ fn parse_option<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, vis: V
 /// - Unary: `% T`, also known as "newtype".
 ///
 /// Usage is like option: `%` and `% Thing`.
+///
+/// Errors raised by the visitor are located at this `%` and explain it, for chains like `% %` that's the second one.
 fn parse_mayary<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, vis: V) -> Result<V::Value> {
-    match der.peek()? {
+    let start = der.mark();
+    let res = match der.peek()? {
         None => parse_nullary(vis),
         Some(tk) => match tk.is_delimiter() {
             true => parse_nullary(vis),
-            false => vis.visit_newtype_struct(der),
+            false => vis.visit_newtype_struct(&mut *der),
         },
-    }
+    };
+
+    res.map_err(|e| match e.line {
+        Some(_) => e,
+        None => start.error(match e.kind {
+            ErrorKind::Deserialize(msg) => ErrorKind::InvalidMayary(msg),
+            kind => kind,
+        }),
+    })
 }
 
 /// Parse the awful nullary.
@@ -260,26 +947,38 @@ fn parse_nullary<'de, V: Visitor<'de>>(vis: V) -> Result<V::Value> {
 ///
 /// - Nullary tuple: `(AwfulNullary)()` or simply `()%`.
 /// - Alt unary tuple: `(CommonNewtype)(T)` or equally `()(T)`.
-fn parse_parenthesis<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, vis: V) -> Result<V::Value> {
+///
+/// The `len` expected by [`deserialize_tuple`](serde::Deserializer::deserialize_tuple) is checked for tuples,
+/// and the `struct_name` expected by `deserialize_*struct` is checked for struct names if configured.
+fn parse_parenthesis<'i, 'de, V: Visitor<'de>>(
+    der: &'i mut Deserializer<'de>,
+    vis: V,
+    len: Option<usize>,
+    struct_name: Option<&'static str>,
+) -> Result<V::Value> {
+    let start = der.mark();
     match der.expect_peek()? {
         TokenKind::_Paren => {
             der.next().ok();
         }
         TokenKind::Ident => {
             let mut name = unwrap_ident!(der.next().unwrap().unwrap());
+            let name_start = der.mark();
             match der.expect_peek()? {
                 TokenKind::_Paren => {
                     der.next().ok();
+                    der.check_struct_name(name, struct_name)
+                        .map_err(|e| name_start.error(e.kind))?;
                 }
                 TokenKind::PathSep => {
                     der.next().ok();
                     name = unwrap_ident!(der.expect_consume_token(TokenKind::Ident, ErrorKind::ExpectedVariant)?);
-                    return parse_tuple_alt(der, vis, name);
+                    return parse_tuple_alt(der, vis, name, start, len);
                 }
-                _ => return parse_tuple_alt(der, vis, name),
+                _ => return parse_tuple_alt(der, vis, name, start, len),
             }
         }
-        _ => return parse_tuple::<_, false>(der, vis),
+        _ => return parse_tuple::<_, false>(der, vis, start, len),
     }
 
     match der.peek()? {
@@ -287,7 +986,7 @@ fn parse_parenthesis<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, v
         Some(tk) => match tk {
             TokenKind::Paren_ => {
                 der.next().ok();
-                parse_tuple::<_, true>(der, vis)
+                parse_tuple::<_, true>(der, vis, start, len)
             }
             TokenKind::Brace_ => {
                 der.next().ok();
@@ -304,7 +1003,7 @@ fn parse_parenthesis<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, v
                 }
             }
             _ if tk.is_delimiter() => vis.visit_unit(),
-            _ => Error::raise(ErrorKind::ExpectedNonUnitStruct),
+            _ => Error::raise(ErrorKind::ExpectedNonUnitStruct(der.describe_peeked(tk))),
         },
     }
 }
@@ -313,11 +1012,15 @@ fn parse_parenthesis<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, v
 ///
 /// - Tuple: `(T,)`, `(T, U, V, ...)`.
 /// - Docile tuple: `()` and `(Name)` are both legal.
+///
+/// The `start` of the whole parenthesized expression is where errors of the visitor are located.
 fn parse_tuple<'i, 'de, V: Visitor<'de>, const DOCILE: bool>(
     der: &'i mut Deserializer<'de>,
     vis: V,
+    start: Mark<'de>,
+    len: Option<usize>,
 ) -> Result<V::Value> {
-    vis.visit_seq(TupleAccessor::new::<DOCILE>(der)?)
+    visit_counted(vis, TupleAccessor::new::<DOCILE>(der)?, start, len)
 }
 
 /// Requires the leading `(` `Enum::Variant` has been consumed, and the `Variant` must be provided in parameter.
@@ -326,16 +1029,24 @@ fn parse_tuple<'i, 'de, V: Visitor<'de>, const DOCILE: bool>(
 fn parse_tuple_alt<'i, 'de, V: Visitor<'de>>(
     der: &'i mut Deserializer<'de>,
     vis: V,
-    variant: SmolStr,
+    variant: &'de str,
+    start: Mark<'de>,
+    len: Option<usize>,
 ) -> Result<V::Value> {
-    vis.visit_seq(TupleAccessor::with_first_variant::<false>(der, variant)?)
+    visit_counted(
+        vis,
+        TupleAccessor::with_first_variant::<false>(der, variant)?,
+        start,
+        len,
+    )
 }
 
 /// Requires the leading bracket `[` has been consumed.
 ///
 /// - Sequence: `[0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89]`.
-fn parse_seq<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, vis: V) -> Result<V::Value> {
-    vis.visit_seq(SeqAccessor::new(der)?)
+fn parse_seq<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, vis: V, len: Option<usize>) -> Result<V::Value> {
+    let start = der.mark();
+    visit_counted(vis, SeqAccessor::new(der)?, start, len)
 }
 
 /// Requires the leading brace `{` has been consumed.
@@ -357,55 +1068,211 @@ fn parse_map<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, vis: V) -
 ///
 /// - Nameness: `Difficulty::Easy`.
 /// - Nameless: `Medium`, `Hard { heart: 1 }`.
-fn parse_enum<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, vis: V, mut name: SmolStr) -> Result<V::Value> {
+fn parse_enum<'i, 'de, V: Visitor<'de>>(
+    der: &'i mut Deserializer<'de>,
+    vis: V,
+    mut name: &'de str,
+    variants: Option<&'static [&'static str]>,
+) -> Result<V::Value> {
     if der.try_consume_token(TokenKind::PathSep)?.is_some() {
         name = unwrap_ident!(der.expect_consume_token(TokenKind::Ident, ErrorKind::ExpectedVariant)?);
     }
 
+    if let Some(variants) = variants {
+        name = der.match_variant(name, variants);
+    }
+
     vis.visit_enum(EnumAccessor::new(der, name))
 }
 
-//==================================================================================================
-
-struct NullaryAccessor;
-impl<'de> SeqAccess<'de> for NullaryAccessor {
-    type Error = Error;
-
-    fn size_hint(&self) -> Option<usize> {
-        Some(0)
+/// The leading identifier must be provided in parameter, and the expected type is unknown.
+///
+/// That's how serde buffers the content of untagged and internally tagged enums, and how [`Value`] is read,
+/// so variants are visited like self-describing formats do, and can be read back as enums from the buffer:
+///
+/// - Unit variant as its name: `Easy` as `"Easy"`.
+/// - Others as a map from the name to its content: `Hard { heart: 1 }` as `{ "Hard" => { "heart" => 1 } }`,
+///   a newtype variant `Speed(2)` or `Speed%2` as `{ "Speed" => 2 }`, nested variants likewise.
+fn parse_buffered_variant<'i, 'de, V: Visitor<'de>>(
+    der: &'i mut Deserializer<'de>,
+    vis: V,
+    mut name: &'de str,
+) -> Result<V::Value> {
+    if der.try_consume_token(TokenKind::PathSep)?.is_some() {
+        name = unwrap_ident!(der.expect_consume_token(TokenKind::Ident, ErrorKind::ExpectedVariant)?);
     }
 
-    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, _seed: T) -> Result<Option<T::Value>> {
-        Ok(None)
+    let content = match der.peek()? {
+        Some(TokenKind::Percent) => {
+            der.next().ok();
+            match der.peek()? {
+                Some(tk) if !tk.is_delimiter() => Value::deserialize(&mut *der),
+                _ => Ok(Value::Seq(Vec::new())),
+            }
+        }
+        Some(TokenKind::Paren_) => {
+            der.next().ok();
+            TupleAccessor::new::<true>(der)
+                .and_then(|acc| Vec::<Value>::deserialize(SeqAccessDeserializer::new(acc)))
+                .map(|mut seq| match seq.len() {
+                    1 => seq.pop().unwrap(),
+                    _ => Value::Seq(seq),
+                })
+        }
+        Some(TokenKind::Brace_) => Value::deserialize(&mut *der),
+        _ => return vis.visit_borrowed_str(name),
     }
+    .map_err(|e| e.within(|| ErrorContext::Variant(name.to_string())))?;
+
+    vis.visit_map(BufferedVariantAccessor {
+        name: Some(name),
+        content: Some(content),
+    })
 }
 
-struct TupleAccessor<'i, 'de> {
+/// An unsigned integer where an enum is expected, requires [`DeserializeConfig::variant_indices`].
+///
+/// - Unit variant by index: `2` for the third variant, which must be a unit variant.
+fn parse_variant_index<'i, 'de, V: Visitor<'de>>(
     der: &'i mut Deserializer<'de>,
-    yielding: bool,
-    first_variant: Option<SmolStr>,
-
-    /// Once this value equals to `1`, it's expected a comma `,` before closing.
-    ///
-    /// This value will be increased after each `next_element_seed` call.
-    ctr: u32,
-}
-impl<'i, 'de> TupleAccessor<'i, 'de> {
-    /// Requires the leading parenthesis `(` has been consumed.
-    fn new<const DOCILE: bool>(der: &'i mut Deserializer<'de>) -> Result<Self> {
-        Self::_build::<DOCILE>(der, None)
+    vis: V,
+    index: u64,
+    variants: &'static [&'static str],
+) -> Result<V::Value> {
+    match variants.get(index as usize) {
+        Some(variant) => vis.visit_enum(UnitAccessor(EnumAccessor::new(der, variant))),
+        None => Err(serde::de::Error::invalid_value(
+            Unexpected::Unsigned(index),
+            &format!("variant index 0 <= i < {}", variants.len()).as_str(),
+        )),
     }
+}
 
-    /// Requires the leading `(` `Enum::Variant` has been consumed, and the `Variant` must be provided in parameter.
-    fn with_first_variant<const DOCILE: bool>(der: &'i mut Deserializer<'de>, first_variant: SmolStr) -> Result<Self> {
-        Self::_build::<DOCILE>(der, Some(first_variant))
+/// A unit variant by its name in quotes, e.g. `"rocket-launcher"`, if [`VariantMatching`] is not `Exact`.
+fn parse_quoted_variant<'i, 'de, V: Visitor<'de>>(
+    der: &'i mut Deserializer<'de>,
+    vis: V,
+    name: &str,
+    variants: &'static [&'static str],
+) -> Result<V::Value> {
+    let matching = der.cfg.variant_matching;
+    match variants.iter().find(|variant| matching.matches(variant, name)) {
+        Some(variant) => vis.visit_enum(UnitAccessor(EnumAccessor::new(der, variant))),
+        None => Err(serde::de::Error::unknown_variant(name, variants)),
     }
+}
 
-    fn _build<const DOCILE: bool>(der: &'i mut Deserializer<'de>, first_variant: Option<SmolStr>) -> Result<Self> {
-        Ok(Self {
+/// Requires the leading brace `{` has been consumed, and [`DeserializeConfig::set_sugar`].
+///
+/// - Set-like: `{ Apple, Banana, "Cherry" }`, where a sequence is expected.
+fn parse_set<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, vis: V) -> Result<V::Value> {
+    vis.visit_seq(SetAccessor::new(der)?)
+}
+
+/// Requires the leading brace `{` has been consumed, and [`DeserializeConfig::map_pairs`].
+///
+/// - Pairs: `{ a => 1, a => 2 }`, where a sequence is expected.
+fn parse_map_pairs<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, vis: V) -> Result<V::Value> {
+    vis.visit_seq(MapPairsAccessor {
+        map: MapAccessor::new(der)?,
+    })
+}
+
+/// Requires the leading bracket `[` has been consumed, and [`DeserializeConfig::map_pairs`].
+///
+/// - Map of pairs: `[(a, 1), (a, 2)]`, where a map is expected.
+fn parse_seq_pairs<'i, 'de, V: Visitor<'de>>(der: &'i mut Deserializer<'de>, vis: V) -> Result<V::Value> {
+    vis.visit_map(SeqPairsAccessor::new(der)?)
+}
+
+/// Errors raised by the visitor itself, e.g. for a sequence of the wrong length,
+/// are located at the start of the sequence.
+///
+/// If the expected `len` is known, a mismatch is reported with the number of elements found,
+/// counting the remaining ones if the visitor stopped early.
+fn visit_counted<'de, V: Visitor<'de>, A: CountedAccess<'de>>(
+    vis: V,
+    mut acc: A,
+    start: Mark<'de>,
+    len: Option<usize>,
+) -> Result<V::Value> {
+    let res = vis.visit_seq(&mut acc);
+    let mismatch = |found| match len {
+        Some(expected) if expected != found => Some(ErrorKind::InvalidLength { expected, found }),
+        _ => None,
+    };
+
+    match res {
+        Ok(val) => match len.is_some() && acc.is_yielding() {
+            false => Ok(val),
+            true => {
+                while acc.next_element::<IgnoredAny>()?.is_some() {}
+                match mismatch(acc.count()) {
+                    Some(kind) => Err(start.error(kind)),
+                    None => Ok(val),
+                }
+            }
+        },
+        Err(e) if e.line.is_none() => match mismatch(acc.count()).filter(|_| !acc.is_yielding()) {
+            Some(kind) => Err(start.error(kind)),
+            None => Err(start.locate(e)),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+//==================================================================================================
+
+/// A sequence accessor that counts the elements yielded, see [`visit_counted`].
+///
+/// Errors raised by the accessor itself are located before returning,
+/// so that the remaining unlocated errors can only come from the visitor.
+trait CountedAccess<'de>: SeqAccess<'de, Error = Error> {
+    fn is_yielding(&self) -> bool;
+    fn count(&self) -> usize;
+}
+
+struct NullaryAccessor;
+impl<'de> SeqAccess<'de> for NullaryAccessor {
+    type Error = Error;
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, _seed: T) -> Result<Option<T::Value>> {
+        Ok(None)
+    }
+}
+
+struct TupleAccessor<'i, 'de> {
+    der: &'i mut Deserializer<'de>,
+    yielding: bool,
+    first_variant: Option<&'de str>,
+
+    /// Once this value equals to `1`, it's expected a comma `,` before closing.
+    ///
+    /// This value will be increased after each `next_element_seed` call.
+    ctr: u32,
+    count: usize,
+}
+impl<'i, 'de> TupleAccessor<'i, 'de> {
+    /// Requires the leading parenthesis `(` has been consumed.
+    fn new<const DOCILE: bool>(der: &'i mut Deserializer<'de>) -> Result<Self> {
+        Self::_build::<DOCILE>(der, None)
+    }
+
+    /// Requires the leading `(` `Enum::Variant` has been consumed, and the `Variant` must be provided in parameter.
+    fn with_first_variant<const DOCILE: bool>(der: &'i mut Deserializer<'de>, first_variant: &'de str) -> Result<Self> {
+        Self::_build::<DOCILE>(der, Some(first_variant))
+    }
+
+    fn _build<const DOCILE: bool>(der: &'i mut Deserializer<'de>, first_variant: Option<&'de str>) -> Result<Self> {
+        Ok(Self {
+            yielding: first_variant.is_some() || der.try_consume_token(TokenKind::_Paren)?.is_none(),
             first_variant,
-            yielding: der.try_consume_token(TokenKind::_Paren)?.is_none(),
             ctr: DOCILE.into(),
+            count: 0,
             der,
         })
     }
@@ -414,33 +1281,39 @@ impl<'de> SeqAccess<'de> for TupleAccessor<'_, 'de> {
     type Error = Error;
 
     fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self._next_element_seed(seed).map_err(|e| self.der.locate_error(e))
+    }
+}
+impl<'de> CountedAccess<'de> for TupleAccessor<'_, 'de> {
+    fn is_yielding(&self) -> bool {
+        self.yielding
+    }
+    fn count(&self) -> usize {
+        self.count
+    }
+}
+impl<'de> TupleAccessor<'_, 'de> {
+    fn _next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
         if !self.yielding {
             return Ok(None);
         }
 
         let val = match self.first_variant.take() {
-            None => seed.deserialize(&mut *self.der)?,
-            Some(variant) => {
-                seed.deserialize(EnumAccessDeserializer::new(EnumAccessor::new(&mut *self.der, variant)))?
-            }
-        };
+            None => self.der.deserialize_seed(seed),
+            Some(variant) => seed.deserialize(VariantDeserializer::new(&mut *self.der, variant)),
+        }
+        .map_err(|e| e.within(|| ErrorContext::Element(self.count)))?;
 
         self.ctr += 1;
+        self.count += 1;
 
-        match self.der.try_consume_token(TokenKind::Comma)? {
-            Some(_) => self.yielding = self.der.try_consume_token(TokenKind::_Paren)?.is_none(),
-            None => {
-                let tk = self.der.expect_peek()?;
-                match tk {
-                    TokenKind::_Paren if self.ctr != 1 => {
-                        self.der.next().ok();
-                        self.yielding = false;
-                    }
-                    _ => Error::raise(ErrorKind::ExpectedComma)?,
-                }
-            }
+        // A unary tuple requires the comma, otherwise it's a parenthesized value.
+        if self.ctr == 1 && self.der.peek()? == Some(TokenKind::_Paren) {
+            Error::raise(ErrorKind::ExpectedComma)?
         }
 
+        self.yielding = self.der.expect_separator(TokenKind::_Paren)?;
+
         Ok(Some(val))
     }
 }
@@ -448,92 +1321,261 @@ impl<'de> SeqAccess<'de> for TupleAccessor<'_, 'de> {
 struct SeqAccessor<'i, 'de> {
     der: &'i mut Deserializer<'de>,
     yielding: bool,
+    count: usize,
 }
 impl<'i, 'de> SeqAccessor<'i, 'de> {
     /// Requires the leading bracket `[` has been consumed.
     fn new(der: &'i mut Deserializer<'de>) -> Result<Self> {
         Ok(Self {
             yielding: der.try_consume_token(TokenKind::_Brack)?.is_none(),
+            count: 0,
             der,
         })
     }
-}
-impl<'de> SeqAccess<'de> for SeqAccessor<'_, 'de> {
-    type Error = Error;
 
-    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+    fn _next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
         if !self.yielding {
             return Ok(None);
         }
 
-        let val = seed.deserialize(&mut *self.der)?;
+        let val = self
+            .der
+            .deserialize_seed(seed)
+            .map_err(|e| e.within(|| ErrorContext::Element(self.count)))?;
+        self.count += 1;
 
-        match self.der.try_consume_token(TokenKind::Comma)? {
-            Some(_) => self.yielding = self.der.try_consume_token(TokenKind::_Brack)?.is_none(),
-            None => {
-                self.der
-                    .expect_consume_token(TokenKind::_Brack, ErrorKind::ExpectedComma)?;
-                self.yielding = false;
-            }
-        }
+        self.yielding = self.der.expect_separator(TokenKind::_Brack)?;
 
         Ok(Some(val))
     }
 }
+impl<'de> SeqAccess<'de> for SeqAccessor<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self._next_element_seed(seed).map_err(|e| self.der.locate_error(e))
+    }
+}
+impl<'de> CountedAccess<'de> for SeqAccessor<'_, 'de> {
+    fn is_yielding(&self) -> bool {
+        self.yielding
+    }
+    fn count(&self) -> usize {
+        self.count
+    }
+}
 
 struct MapAccessor<'i, 'de> {
     der: &'i mut Deserializer<'de>,
     yielding: bool,
+
+    /// The current entry has no value, see [`DeserializeConfig::set_sugar`].
+    valueless: bool,
+    /// The key of the current entry if it's a field, e.g. `server.tls.enabled`, see [`DeserializeConfig::dotted_keys`].
+    path: Vec<&'de str>,
+    /// How far the next entry was read by the nested maps of a dotted key, to find where they end.
+    ahead: Option<Ahead<'de>>,
+    keys: DottedKeys<'de>,
+    /// The index of the current entry, see [`ErrorContext::Entry`].
+    count: usize,
+}
+
+/// How far the next entry of a map was read, see [`DottedMapAccessor`].
+#[derive(Debug, Clone, Copy)]
+enum Ahead<'de> {
+    /// Its key, into [`MapAccessor::path`], including the colon.
+    Path,
+    /// Its leading identifier, which is not a field, e.g. `Enum` of `Enum::Variant => value`.
+    Ident(&'de str),
+    /// The comma before it, it doesn't start with an identifier.
+    Comma,
+    /// The closing brace, there's no next entry.
+    End,
 }
+
+/// The fields of a map, or of the nested map of a dotted key, checked for conflicts with dotted keys.
+#[derive(Debug, Default)]
+struct DottedKeys<'de> {
+    fields: Vec<&'de str>,
+    groups: Vec<&'de str>,
+}
+impl<'de> DottedKeys<'de> {
+    /// Adds `path[depth]`, which is dotted if the path goes on.
+    fn insert(&mut self, path: &[&'de str], depth: usize) -> Result<()> {
+        let key = path[depth];
+        let dotted = path.len() > depth + 1;
+        if self.groups.contains(&key) || dotted && self.fields.contains(&key) {
+            return Error::raise(ErrorKind::ConflictingDottedKey(path[..=depth].join(".")));
+        }
+        match dotted {
+            true => self.groups.push(key),
+            false => self.fields.push(key),
+        }
+        Ok(())
+    }
+}
+
 impl<'i, 'de> MapAccessor<'i, 'de> {
     /// Requires the leading brace `{` has been consumed.
     fn new(der: &'i mut Deserializer<'de>) -> Result<Self> {
         Ok(Self {
             yielding: der.try_consume_token(TokenKind::_Brace)?.is_none(),
+            valueless: false,
+            path: Vec::new(),
+            ahead: None,
+            keys: DottedKeys::default(),
+            count: 0,
             der,
         })
     }
+
+    fn context(&self) -> ErrorContext {
+        match self.path.is_empty() {
+            true => ErrorContext::Entry(self.count),
+            false => ErrorContext::Field(self.path.join(".")),
+        }
+    }
+
+    /// Consumes the fat arrow `=>`, or nothing if the entry has no value.
+    fn expect_fat_arrow(&mut self) -> Result<()> {
+        if self.der.cfg.set_sugar && matches!(self.der.peek()?, Some(TokenKind::Comma | TokenKind::_Brace)) {
+            self.valueless = true;
+            return Ok(());
+        }
+
+        self.der
+            .expect_consume_token(TokenKind::FatArrow, ErrorKind::ExpectedFatArrow)?;
+
+        Ok(())
+    }
+
+    /// Reads the key of a field after its leading identifier, up to and including the colon,
+    /// returns `false` if it's not followed by a colon or a dot.
+    fn read_path(&mut self, first: &'de str) -> Result<bool> {
+        self.path.clear();
+        self.path.push(first);
+        loop {
+            match self.der.peek()? {
+                Some(TokenKind::Colon) => {
+                    self.der.next()?;
+                    return Ok(true);
+                }
+                Some(TokenKind::Dot) if self.der.cfg.dotted_keys => {
+                    self.der.next()?;
+                    let ident = self
+                        .der
+                        .expect_consume_token(TokenKind::Ident, ErrorKind::ExpectedDottedKey)?;
+                    self.path.push(unwrap_ident!(ident));
+                }
+                // As before there was a dot token.
+                Some(TokenKind::Dot) => return Error::raise(ErrorKind::UnexpectedToken),
+                _ if self.path.len() > 1 => return Error::raise(ErrorKind::ExpectedDottedKey),
+                _ => return Ok(false),
+            }
+        }
+    }
+
+    /// Skips the next fields whose values are placeholders `_`, reading the key of the entry after them ahead,
+    /// see [`DeserializeConfig::default_placeholders`].
+    fn skip_placeholders(&mut self) -> Result<()> {
+        while self.yielding {
+            let ahead = match self.ahead.take() {
+                Some(Ahead::End) | None => match self.der.try_consume_token(TokenKind::Ident)? {
+                    None => Ahead::Comma,
+                    Some(ident) => match self.read_path(unwrap_ident!(ident))? {
+                        true => Ahead::Path,
+                        false => Ahead::Ident(self.path[0]),
+                    },
+                },
+                Some(ahead) => ahead,
+            };
+            self.ahead = Some(ahead);
+
+            let placeholder = matches!(ahead, Ahead::Path)
+                && self.path.len() == 1
+                && matches!(self.der.kex.peek(), Some(Ok(Token::Ident("_"))));
+            if !placeholder {
+                return Ok(());
+            }
+            self.der.next()?;
+            self.ahead = None;
+            self.count += 1;
+            self.yielding = self.der.expect_separator(TokenKind::_Brace)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the separator after an entry and as much of the next entry as [`Ahead`] tells.
+    fn read_ahead(&mut self) -> Result<Ahead<'de>> {
+        if !self.der.expect_separator(TokenKind::_Brace)? {
+            return Ok(Ahead::End);
+        }
+        Ok(match self.der.try_consume_token(TokenKind::Ident)? {
+            None => Ahead::Comma,
+            Some(ident) => match self.read_path(unwrap_ident!(ident))? {
+                true => Ahead::Path,
+                false => Ahead::Ident(self.path[0]),
+            },
+        })
+    }
 }
 impl<'de> MapAccess<'de> for MapAccessor<'_, 'de> {
     type Error = Error;
 
     fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if self.der.cfg.default_placeholders {
+            self.skip_placeholders()?;
+        }
         if !self.yielding {
             return Ok(None);
         }
 
+        // The next entry may have been read by the nested maps of a dotted key already.
+        let (field, ident) = match self.ahead.take() {
+            Some(Ahead::Path) => (true, None),
+            Some(Ahead::Ident(name)) => (false, Some(name)),
+            Some(Ahead::Comma) => (false, None),
+            Some(Ahead::End) | None => match self.der.try_consume_token(TokenKind::Ident)? {
+                Some(ident) => {
+                    let name = unwrap_ident!(ident);
+                    (self.read_path(name)?, Some(name))
+                }
+                None => (false, None),
+            },
+        };
+        if field {
+            /* Field: or dotted.field: */
+            if self.der.cfg.dotted_keys {
+                self.keys.insert(&self.path, 0)?;
+            }
+            return seed
+                .deserialize(BorrowedStrDeserializer::<Error>::new(self.path[0]))
+                .map(Some);
+        }
+        self.path.clear();
+
         let val;
 
-        match self.der.try_consume_token(TokenKind::Ident)? {
+        match ident {
             None => {
                 /* Arbitrary => */
-                val = seed.deserialize(&mut *self.der)?;
+                val = self.der.deserialize_seed(seed)?;
 
-                self.der
-                    .expect_consume_token(TokenKind::FatArrow, ErrorKind::ExpectedFatArrow)?;
+                self.expect_fat_arrow()?;
             }
-            Some(ident) => {
-                /* Field or Enum::Variant */
-                let mut name = unwrap_ident!(ident);
-                match self.der.try_consume_token(TokenKind::Colon)? {
-                    Some(_) => {
-                        /* Field: */
-                        val = seed.deserialize(StrDeserializer::<Error>::new(&name))?;
-                    }
-                    None => {
-                        /* Enum::Variant => */
-                        if self.der.try_consume_token(TokenKind::PathSep)?.is_some() {
-                            name = unwrap_ident!(self
-                                .der
-                                .expect_consume_token(TokenKind::Ident, ErrorKind::ExpectedVariant)?);
-                        }
-
-                        val = seed.deserialize(EnumAccessDeserializer::new(EnumAccessor::new(&mut *self.der, name)))?;
-
-                        self.der
-                            .expect_consume_token(TokenKind::FatArrow, ErrorKind::ExpectedFatArrow)?;
-                    }
+            Some(mut name) => {
+                /* Enum::Variant => */
+                if self.der.try_consume_token(TokenKind::PathSep)?.is_some() {
+                    name = unwrap_ident!(self
+                        .der
+                        .expect_consume_token(TokenKind::Ident, ErrorKind::ExpectedVariant)?);
                 }
+
+                let mut der = VariantDeserializer::new(&mut *self.der, name);
+                der.bare = der.der.cfg.set_sugar;
+                val = seed.deserialize(der)?;
+
+                self.expect_fat_arrow()?;
             }
         }
 
@@ -541,29 +1583,336 @@ impl<'de> MapAccess<'de> for MapAccessor<'_, 'de> {
     }
 
     fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
-        let val = seed.deserialize(&mut *self.der)?;
+        let val = match core::mem::take(&mut self.valueless) {
+            true => seed.deserialize(UnitDeserializer::<Error>::new()),
+            false if self.path.len() > 1 => seed.deserialize(DottedMapAccessor::new(self, 1)),
+            false => self.der.deserialize_seed(seed),
+        }
+        .map_err(|e| e.within(|| self.context()))?;
+        self.count += 1;
 
-        match self.der.try_consume_token(TokenKind::Comma)? {
-            Some(_) => self.yielding = self.der.try_consume_token(TokenKind::_Brace)?.is_none(),
-            None => {
-                self.der
-                    .expect_consume_token(TokenKind::_Brace, ErrorKind::ExpectedComma)?;
-                self.yielding = false;
+        self.yielding = match self.ahead {
+            Some(Ahead::End) => false,
+            Some(_) => true,
+            None => self.der.expect_separator(TokenKind::_Brace)?,
+        };
+
+        Ok(val)
+    }
+}
+
+/// The nested map of a dotted key, e.g. `{ tls: { enabled: true } }` of `server.tls.enabled: true`,
+/// which goes on while the next entries share the prefix, see [`DeserializeConfig::dotted_keys`].
+struct DottedMapAccessor<'a, 'i, 'de> {
+    map: &'a mut MapAccessor<'i, 'de>,
+    /// The index of the key of this map in the path.
+    depth: usize,
+    prefix: Vec<&'de str>,
+    keys: DottedKeys<'de>,
+    started: bool,
+}
+impl<'a, 'i, 'de> DottedMapAccessor<'a, 'i, 'de> {
+    fn new(map: &'a mut MapAccessor<'i, 'de>, depth: usize) -> Self {
+        Self {
+            prefix: map.path[..depth].to_vec(),
+            map,
+            depth,
+            keys: DottedKeys::default(),
+            started: false,
+        }
+    }
+}
+impl<'de> MapAccess<'de> for DottedMapAccessor<'_, '_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if self.started {
+            if self.map.ahead.is_none() {
+                self.map.ahead = Some(self.map.read_ahead()?);
+            }
+            let path = &self.map.path;
+            match self.map.ahead {
+                Some(Ahead::Path) if path.len() > self.depth && path[..self.depth] == self.prefix[..] => {
+                    self.map.ahead = None
+                }
+                _ => return Ok(None),
             }
         }
+        self.started = true;
+
+        self.keys.insert(&self.map.path, self.depth)?;
+        seed.deserialize(BorrowedStrDeserializer::<Error>::new(self.map.path[self.depth]))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        match self.map.path.len() > self.depth + 1 {
+            true => seed.deserialize(DottedMapAccessor::new(self.map, self.depth + 1)),
+            false => self.map.der.deserialize_seed(seed),
+        }
+    }
+}
+impl<'de> serde::Deserializer<'de> for DottedMapAccessor<'_, '_, 'de> {
+    type Error = Error;
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, vis: V) -> Result<V::Value> {
+        vis.visit_map(self)
+    }
+    fn deserialize_option<V: Visitor<'de>>(self, vis: V) -> Result<V::Value> {
+        vis.visit_some(self)
+    }
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _: &'static str, vis: V) -> Result<V::Value> {
+        vis.visit_newtype_struct(self)
+    }
+}
+
+struct SetAccessor<'i, 'de> {
+    der: &'i mut Deserializer<'de>,
+    yielding: bool,
+}
+impl<'i, 'de> SetAccessor<'i, 'de> {
+    /// Requires the leading brace `{` has been consumed.
+    fn new(der: &'i mut Deserializer<'de>) -> Result<Self> {
+        Ok(Self {
+            yielding: der.try_consume_token(TokenKind::_Brace)?.is_none(),
+            der,
+        })
+    }
+}
+impl<'de> SeqAccess<'de> for SetAccessor<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if !self.yielding {
+            return Ok(None);
+        }
+
+        let val = match self.der.try_consume_token(TokenKind::Ident)? {
+            None => self.der.deserialize_seed(seed)?,
+            Some(ident) => {
+                let mut name = unwrap_ident!(ident);
+                if self.der.try_consume_token(TokenKind::PathSep)?.is_some() {
+                    name = unwrap_ident!(self
+                        .der
+                        .expect_consume_token(TokenKind::Ident, ErrorKind::ExpectedVariant)?);
+                }
+
+                let mut der = VariantDeserializer::new(&mut *self.der, name);
+                der.bare = true;
+                seed.deserialize(der)?
+            }
+        };
+
+        self.yielding = self.der.expect_separator(TokenKind::_Brace)?;
+
+        Ok(Some(val))
+    }
+}
+
+/// Each entry of a map as a pair, see [`parse_map_pairs`].
+struct MapPairsAccessor<'i, 'de> {
+    map: MapAccessor<'i, 'de>,
+}
+impl<'de> SeqAccess<'de> for MapPairsAccessor<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.map.yielding {
+            true => seed.deserialize(PairDeserializer { map: &mut self.map }).map(Some),
+            false => Ok(None),
+        }
+    }
+}
+
+/// The key and value of the current map entry as a sequence of two.
+struct PairDeserializer<'a, 'i, 'de> {
+    map: &'a mut MapAccessor<'i, 'de>,
+}
+impl<'de> serde::Deserializer<'de> for PairDeserializer<'_, '_, 'de> {
+    type Error = Error;
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, vis: V) -> Result<V::Value> {
+        vis.visit_seq(PairAccessor { map: self.map, ctr: 0 })
+    }
+}
+
+struct PairAccessor<'a, 'i, 'de> {
+    map: &'a mut MapAccessor<'i, 'de>,
+    ctr: usize,
+}
+impl<'de> SeqAccess<'de> for PairAccessor<'_, '_, 'de> {
+    type Error = Error;
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(2 - self.ctr)
+    }
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self.ctr += 1;
+        match self.ctr {
+            1 => self.map.next_key_seed(seed),
+            2 => self.map.next_value_seed(seed).map(Some),
+            _ => {
+                self.ctr = 2;
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Each pair `(key, value)` of a sequence as an entry, see [`parse_seq_pairs`].
+struct SeqPairsAccessor<'i, 'de> {
+    der: &'i mut Deserializer<'de>,
+    yielding: bool,
+}
+impl<'i, 'de> SeqPairsAccessor<'i, 'de> {
+    /// Requires the leading bracket `[` has been consumed.
+    fn new(der: &'i mut Deserializer<'de>) -> Result<Self> {
+        Ok(Self {
+            yielding: der.try_consume_token(TokenKind::_Brack)?.is_none(),
+            der,
+        })
+    }
+}
+impl<'de> MapAccess<'de> for SeqPairsAccessor<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if !self.yielding {
+            return Ok(None);
+        }
+
+        self.der
+            .expect_consume_token(TokenKind::Paren_, ErrorKind::ExpectedPair)?;
+        let key = self.der.deserialize_seed(seed)?;
+        self.der
+            .expect_consume_token(TokenKind::Comma, ErrorKind::ExpectedComma)?;
+
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let val = self.der.deserialize_seed(seed)?;
+
+        if self.der.expect_separator(TokenKind::_Paren)? {
+            Error::raise(ErrorKind::ExpectedPair)?
+        }
+        self.yielding = self.der.expect_separator(TokenKind::_Brack)?;
 
         Ok(val)
     }
 }
 
+/// A map with the single entry of a variant, see [`parse_buffered_variant`].
+struct BufferedVariantAccessor<'de> {
+    name: Option<&'de str>,
+    content: Option<Value>,
+}
+impl<'de> MapAccess<'de> for BufferedVariantAccessor<'de> {
+    type Error = Error;
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.name.is_some() as usize)
+    }
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.name.take() {
+            Some(name) => seed.deserialize(BorrowedStrDeserializer::<Error>::new(name)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(self.content.take().expect("contract violation"))
+    }
+}
+
+/// Like [`EnumAccessDeserializer`](serde::de::value::EnumAccessDeserializer),
+/// but the variant can be resolved when the expected variants are known.
+struct VariantDeserializer<'i, 'de> {
+    der: &'i mut Deserializer<'de>,
+    variant: &'de str,
+
+    /// The identifier may also be a string, see [`DeserializeConfig::set_sugar`].
+    bare: bool,
+}
+impl<'i, 'de> VariantDeserializer<'i, 'de> {
+    /// Requires the leading `Enum::Variant` has been consumed, and the `Variant` must be provided in parameter.
+    fn new(der: &'i mut Deserializer<'de>, variant: &'de str) -> Self {
+        Self {
+            der,
+            variant,
+            bare: false,
+        }
+    }
+
+    fn visit_enum<V: Visitor<'de>>(self, vis: V) -> Result<V::Value> {
+        vis.visit_enum(EnumAccessor::new(self.der, self.variant))
+    }
+}
+impl<'de> serde::Deserializer<'de> for VariantDeserializer<'_, 'de> {
+    type Error = Error;
+    serde::forward_to_deserialize_any! {
+        ignored_any
+    }
+    forward_to! {
+        visit_enum:
+        deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32() deserialize_i64() deserialize_i128()
+        deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64() deserialize_u128()
+        deserialize_f32() deserialize_f64() deserialize_char() deserialize_bytes() deserialize_byte_buf()
+        deserialize_option() deserialize_unit() deserialize_unit_struct(&'static str)
+        deserialize_newtype_struct(&'static str) deserialize_seq() deserialize_tuple(usize)
+        deserialize_tuple_struct(&'static str, usize) deserialize_map()
+        deserialize_struct(&'static str, &'static [&'static str]) deserialize_identifier()
+    }
+
+    /// See [`parse_buffered_variant`].
+    fn deserialize_any<V: Visitor<'de>>(self, vis: V) -> Result<V::Value> {
+        parse_buffered_variant(self.der, vis, self.variant)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, vis: V) -> Result<V::Value> {
+        match self.bare {
+            true => vis.visit_borrowed_str(self.variant),
+            false => self.visit_enum(vis),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, vis: V) -> Result<V::Value> {
+        self.deserialize_str(vis)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        vis: V,
+    ) -> Result<V::Value> {
+        let variant = self.der.match_variant(self.variant, variants);
+        vis.visit_enum(EnumAccessor::new(self.der, variant))
+    }
+}
+
 struct EnumAccessor<'i, 'de> {
     der: &'i mut Deserializer<'de>,
-    variant: SmolStr,
+    variant: &'de str,
+    ident: Mark<'de>,
 }
 impl<'i, 'de> EnumAccessor<'i, 'de> {
     /// Requires the leading `Enum::Variant` has been consumed, and the `Variant` must be provided in parameter.
-    fn new(der: &'i mut Deserializer<'de>, variant: SmolStr) -> Self {
-        Self { der, variant }
+    fn new(der: &'i mut Deserializer<'de>, variant: &'de str) -> Self {
+        let ident = der.kex.consumed;
+        Self { der, variant, ident }
     }
 }
 impl<'i, 'de> EnumAccess<'de> for EnumAccessor<'i, 'de> {
@@ -572,18 +1921,61 @@ impl<'i, 'de> EnumAccess<'de> for EnumAccessor<'i, 'de> {
 
     fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
         Ok((
-            seed.deserialize(StrDeserializer::<Error>::new(&self.variant))?,
-            VariantAccessor::new(self.der),
+            seed.deserialize(BorrowedStrDeserializer::<Error>::new(self.variant))?,
+            VariantAccessor::new(self.der, self.variant, self.ident),
         ))
     }
 }
 
+/// A variant by its index or quoted name, which name unit variants only,
+/// e.g. `1` is not `Circle(1.0)` as in `1(1.0)`.
+struct UnitAccessor<'i, 'de>(EnumAccessor<'i, 'de>);
+impl<'i, 'de> EnumAccess<'de> for UnitAccessor<'i, 'de> {
+    type Error = Error;
+    type Variant = UnitVariantAccessor<'i, 'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
+        let (val, variant) = self.0.variant_seed(seed)?;
+        Ok((val, UnitVariantAccessor(variant)))
+    }
+}
+
+struct UnitVariantAccessor<'i, 'de>(VariantAccessor<'i, 'de>);
+impl<'de> VariantAccess<'de> for UnitVariantAccessor<'_, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        self.0.unit_variant()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, _: T) -> Result<T::Value> {
+        Err(self.0.ident.error(ErrorKind::ExpectedUnitVariant))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _: usize, _: V) -> Result<V::Value> {
+        Err(self.0.ident.error(ErrorKind::ExpectedUnitVariant))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _: &'static [&'static str], _: V) -> Result<V::Value> {
+        Err(self.0.ident.error(ErrorKind::ExpectedUnitVariant))
+    }
+}
+
+/// What follows the variant is checked against its kind only after a peek,
+/// errors of the kind are located at the variant itself, e.g. at `Right` rather than `}` in `{ side: Right }`.
 struct VariantAccessor<'i, 'de> {
     der: &'i mut Deserializer<'de>,
+    variant: &'de str,
+    ident: Mark<'de>,
 }
 impl<'i, 'de> VariantAccessor<'i, 'de> {
-    fn new(der: &'i mut Deserializer<'de>) -> Self {
-        Self { der }
+    fn new(der: &'i mut Deserializer<'de>, variant: &'de str, ident: Mark<'de>) -> Self {
+        Self { der, variant, ident }
+    }
+
+    fn context(&self) -> impl FnOnce() -> ErrorContext + 'de {
+        let variant = self.variant;
+        move || ErrorContext::Variant(variant.to_string())
     }
 }
 impl<'de> VariantAccess<'de> for VariantAccessor<'_, 'de> {
@@ -593,7 +1985,7 @@ impl<'de> VariantAccess<'de> for VariantAccessor<'_, 'de> {
     fn unit_variant(self) -> Result<()> {
         if let Some(tk) = self.der.peek()? {
             if !tk.is_delimiter() {
-                Error::raise(ErrorKind::ExpectedUnitVariant)?
+                Err(self.ident.error(ErrorKind::ExpectedUnitVariant))?
             }
         }
 
@@ -601,31 +1993,37 @@ impl<'de> VariantAccess<'de> for VariantAccessor<'_, 'de> {
     }
 
     fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        let context = self.context();
         match self.der.expect_next()? {
-            Token::Percent => seed.deserialize(&mut *self.der),
+            Token::Percent => self.der.deserialize_seed(seed).map_err(|e| e.within(context)),
             Token::Paren_ => {
-                let val = seed.deserialize(&mut *self.der)?;
+                let val = self.der.deserialize_seed(seed).map_err(|e| e.within(context))?;
                 self.der.try_consume_token(TokenKind::Comma)?;
                 self.der
                     .expect_consume_token(TokenKind::_Paren, ErrorKind::ExpectedNewtypeVariant)?;
                 Ok(val)
             }
-            _ => Error::raise(ErrorKind::ExpectedNewtypeVariant),
+            _ => Err(self.ident.error(ErrorKind::ExpectedNewtypeVariant)),
         }
     }
 
-    fn tuple_variant<V: Visitor<'de>>(self, _: usize, vis: V) -> Result<V::Value> {
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, vis: V) -> Result<V::Value> {
+        let context = self.context();
         match self.der.expect_next()? {
             Token::Percent => parse_nullary(vis),
-            Token::Paren_ => parse_tuple::<_, true>(self.der, vis),
-            _ => Error::raise(ErrorKind::ExpectedTupleVariant),
+            Token::Paren_ => {
+                let start = self.der.mark();
+                parse_tuple::<_, true>(self.der, vis, start, Some(len)).map_err(|e| e.within(context))
+            }
+            _ => Err(self.ident.error(ErrorKind::ExpectedTupleVariant)),
         }
     }
 
     fn struct_variant<V: Visitor<'de>>(self, _: &'static [&'static str], vis: V) -> Result<V::Value> {
-        self.der
-            .expect_consume_token(TokenKind::Brace_, ErrorKind::ExpectedStructVariant)?;
-
-        parse_map(self.der, vis)
+        let context = self.context();
+        match self.der.expect_next()? {
+            Token::Brace_ => parse_map(self.der, vis).map_err(|e| e.within(context)),
+            _ => Err(self.ident.error(ErrorKind::ExpectedStructVariant)),
+        }
     }
 }
@@ -0,0 +1,65 @@
+//! Tools walking the tokens of a document, e.g. to see how it is read when it doesn't parse as expected.
+
+use super::{de::locate_after, lexer::*, Error, Result};
+use logos::Logos;
+use std::{fmt::Write, ops::Range};
+
+/// A range of bytes in the source.
+pub type Span = Range<usize>;
+
+/// Lists each token of `source` on its own line, with where it starts, its byte span, and what it is,
+/// e.g. `:1:9 8..14 literal String("Alex")`.
+///
+/// Literals are shown as they are decoded, so escapes, paragraphs and numbers can be checked.
+/// Whitespaces and comments are skipped, and the listing stops at the first error, which is the last line.
+pub fn dump_tokens(source: &str) -> String {
+    let mut lex = Token::lexer(source);
+    let mut out = String::new();
+
+    while let Some(res) = lex.next() {
+        let span = lex.span();
+        let (line, col) = locate_after(&source[..span.start]);
+        write!(out, ":{}:{} {}..{} ", line.unwrap(), col.unwrap(), span.start, span.end).unwrap();
+
+        match res {
+            Ok(Token::Ident(ident)) => writeln!(out, "identifier `{}`", ident),
+            Ok(Token::Literal(literal)) => writeln!(out, "literal {:?}", literal),
+            Ok(_) => writeln!(out, "`{}`", lex.slice()),
+            Err(kind) => {
+                writeln!(out, "error: {}", kind).unwrap();
+                break;
+            }
+        }
+        .unwrap();
+    }
+
+    out
+}
+
+/// Lists each string literal of `source` with its byte span, in order, e.g. for localization pipelines
+/// to pull translatable text out of content files without knowing their types.
+///
+/// Strings are decoded, whichever way they are written, and include strings as map keys.
+/// Identifiers, chars and bytes are not strings. Fails at the first token that cannot be read.
+pub fn extract_strings(source: &str) -> Result<Vec<(Span, String)>> {
+    let mut lex = Token::lexer(source);
+    let mut strings = Vec::new();
+
+    while let Some(res) = lex.next() {
+        match res {
+            Ok(Token::Literal(Literal::Str(s))) => strings.push((lex.span(), s.to_string())),
+            Ok(Token::Literal(Literal::String(s))) => strings.push((lex.span(), s)),
+            Ok(_) => (),
+            Err(kind) => {
+                let (line, col) = locate_after(&source[..lex.span().start]);
+                return Err(Error {
+                    line,
+                    col,
+                    ..Error::new(kind)
+                });
+            }
+        }
+    }
+
+    Ok(strings)
+}
@@ -0,0 +1,55 @@
+//! Reading and writing KEON through the trait objects of [`erased_serde`],
+//! e.g. for plugins that cannot name the concrete types of serde.
+//!
+//! Requires the feature `erased`.
+
+use crate::{Deserializer, Serializer};
+use std::io::Write;
+
+/// Owns a [`Deserializer`] and lends it as a `dyn erased_serde::Deserializer`.
+///
+/// Errors go through [`erased_serde::Error`], which keeps their message and location as text only.
+pub struct ErasedDeserializer<'de> {
+    der: Deserializer<'de>,
+}
+
+impl<'de> ErasedDeserializer<'de> {
+    pub fn new(der: Deserializer<'de>) -> Self {
+        Self { der }
+    }
+
+    /// Lends the deserializer, to be coerced into `&mut dyn erased_serde::Deserializer<'de>`,
+    /// e.g. for [`erased_serde::deserialize`]. Each one reads the next value.
+    pub fn erase(&mut self) -> impl erased_serde::Deserializer<'de> + '_ {
+        <dyn erased_serde::Deserializer>::erase(&mut self.der)
+    }
+
+    /// Gives the deserializer back, e.g. to [`finish`](Deserializer::finish) it.
+    pub fn into_inner(self) -> Deserializer<'de> {
+        self.der
+    }
+}
+
+/// Owns a [`Serializer`] and lends it as a `dyn erased_serde::Serializer`.
+///
+/// Errors go through [`erased_serde::Error`], which keeps their message and location as text only.
+pub struct ErasedSerializer<W: Write> {
+    ser: Serializer<W>,
+}
+
+impl<W: Write> ErasedSerializer<W> {
+    pub fn new(ser: Serializer<W>) -> Self {
+        Self { ser }
+    }
+
+    /// Lends the serializer, to be coerced into `&mut dyn erased_serde::Serializer`,
+    /// e.g. for [`erased_serde::Serialize::erased_serialize`]. Each one writes a value.
+    pub fn erase(&mut self) -> impl erased_serde::Serializer + '_ {
+        <dyn erased_serde::Serializer>::erase(&mut self.ser)
+    }
+
+    /// Gives the serializer back, e.g. for its [`source_map`](Serializer::source_map).
+    pub fn into_inner(self) -> Serializer<W> {
+        self.ser
+    }
+}
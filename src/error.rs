@@ -1,12 +1,17 @@
-use std::{fmt, io, num::NonZeroU32};
+use crate::limits::ERROR_CONTEXT_DEPTH;
+use std::{fmt, io, num::NonZeroU32, sync::Arc};
 
 pub type Result<T> = core::result::Result<T, Error>;
 
+/// Formatted with `{:#}`, the [`context`](Self::context) follows on lines of its own,
+/// e.g. `while parsing element 3` and `while parsing value of field `inventory``.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Error {
     pub line: Option<NonZeroU32>,
     pub col: Option<NonZeroU32>,
     pub kind: ErrorKind,
+    /// See [`context`](Self::context).
+    pub(crate) context: Vec<ErrorContext>,
 }
 impl Error {
     pub(crate) fn new(kind: ErrorKind) -> Self {
@@ -14,13 +19,38 @@ impl Error {
             line: None,
             col: None,
             kind,
+            context: Vec::new(),
         }
     }
     pub(crate) fn raise<T>(kind: ErrorKind) -> Result<T> {
         Err(Self::new(kind))
     }
+
+    /// What was being parsed while deserializing, innermost first,
+    /// up to [`ERROR_CONTEXT_DEPTH`](crate::limits::ERROR_CONTEXT_DEPTH) of them.
+    pub fn context(&self) -> &[ErrorContext] {
+        &self.context
+    }
+
+    /// Adds what was being parsed around the error, unless there is enough context already.
+    pub(crate) fn within(mut self, context: impl FnOnce() -> ErrorContext) -> Self {
+        if self.context.len() < ERROR_CONTEXT_DEPTH {
+            self.context.push(context());
+        }
+        self
+    }
+}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ErrorKind::InvalidNumber(e) => Some(e),
+            ErrorKind::InvalidBytesEncoding(e) => Some(e),
+            ErrorKind::InvalidUtf8 { error, .. } => Some(error),
+            ErrorKind::Io(e) => Some(e.get_ref()),
+            _ => None,
+        }
+    }
 }
-impl std::error::Error for Error {}
 impl serde::ser::Error for Error {
     fn custom<T: fmt::Display>(msg: T) -> Self {
         Error::new(ErrorKind::Serialize(msg.to_string()))
@@ -33,7 +63,12 @@ impl serde::de::Error for Error {
 }
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Error { line, col, kind } = self;
+        let Error {
+            line,
+            col,
+            kind,
+            context,
+        } = self;
         if let Some(n) = line {
             write!(f, ":{}", n)?;
             match col {
@@ -41,12 +76,72 @@ impl fmt::Display for Error {
                 None => write!(f, ":-1 ")?,
             }
         }
-        write!(f, "{}", kind)
+        write!(f, "{}", kind)?;
+        if f.alternate() {
+            for context in context {
+                write!(f, "\n    while parsing {}", context)?;
+            }
+        }
+        Ok(())
     }
 }
 impl From<io::Error> for Error {
     fn from(e: io::Error) -> Self {
-        Error::new(ErrorKind::Io(e.to_string()))
+        match e.get_ref().is_some_and(|inner| inner.is::<LocatedIo>()) {
+            true => {
+                let LocatedIo { line, col, error } = *e.into_inner().unwrap().downcast().unwrap();
+                Error {
+                    line: NonZeroU32::new(line as u32 + 1),
+                    col: NonZeroU32::new(col as u32 + 1),
+                    kind: ErrorKind::Io(IoError(Arc::new(error))),
+                    context: Vec::new(),
+                }
+            }
+            false => Error::new(ErrorKind::Io(IoError(Arc::new(e)))),
+        }
+    }
+}
+
+/// An error of the underlying reader or writer, also the [`source`](std::error::Error::source) of [`Error`].
+#[derive(Debug, Clone)]
+pub struct IoError(Arc<io::Error>);
+impl IoError {
+    pub fn kind(&self) -> io::ErrorKind {
+        self.0.kind()
+    }
+    pub fn get_ref(&self) -> &io::Error {
+        &self.0
+    }
+}
+/// Compares the kinds and messages, as [`io::Error`] cannot be compared.
+impl PartialEq for IoError {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind() == other.kind() && self.0.to_string() == other.0.to_string()
+    }
+}
+impl Eq for IoError {}
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// An error of the writer with where in the output it happened, unwrapped again by `From<io::Error>`.
+#[derive(Debug)]
+pub(crate) struct LocatedIo {
+    line: usize,
+    col: usize,
+    error: io::Error,
+}
+impl LocatedIo {
+    pub(crate) fn wrap(error: io::Error, line: usize, col: usize) -> io::Error {
+        io::Error::new(error.kind(), LocatedIo { line, col, error })
+    }
+}
+impl std::error::Error for LocatedIo {}
+impl fmt::Display for LocatedIo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
     }
 }
 
@@ -54,6 +149,8 @@ impl From<io::Error> for Error {
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub enum ErrorKind {
     UnexpectedEof,
+    /// The document contains only whitespaces and comments, see [`is_empty_document`](crate::is_empty_document).
+    EmptyDocument,
     #[default]
     UnexpectedToken,
     UnexpectedNewline,
@@ -61,62 +158,207 @@ pub enum ErrorKind {
     UnexpectedUnicodeEscape,
     UnbalancedLiteralClose,
     InvalidNumber(lexical_core::Error),
+    /// The bits of a NaN with payload are not a NaN, e.g. `NaN:0x3ff0000000000000`,
+    /// see [`SerializeConfig::nan_payloads`](crate::SerializeConfig::nan_payloads).
+    InvalidNanPayload,
+    /// A number followed by a suffix or a fraction, e.g. `3.5m`, that no
+    /// [`number_parser`](crate::Deserializer::number_parser) claimed.
+    UnclaimedNumber(String),
     InvalidCharacterTooLess,
     InvalidCharacterTooMany,
     InvalidBytesEncoding(data_encoding::DecodeError),
+    /// The input is not UTF-8, with the byte offset and up to 4 bytes of the invalid sequence.
+    ///
+    /// The `error` is that of [`str::from_utf8`] on the input from the offset on,
+    /// its [`error_len`](std::str::Utf8Error::error_len) is `None` if the input ends in the middle of a character.
+    InvalidUtf8 {
+        offset: usize,
+        bytes: Vec<u8>,
+        error: std::str::Utf8Error,
+    },
     InvalidEscape,
     InvalidAsciiEscape,
     InvalidUnicodeEscape,
 
     ExpectedComma,
     ExpectedFatArrow,
-    ExpectedNonUnitStruct,
+    /// A struct name `(Name)` followed by something other than `(`, `{`, `%` or a delimiter,
+    /// with the token found, e.g. `` literal `5` ``.
+    ExpectedNonUnitStruct(String),
+    /// A pair `(key, value)` in a sequence read as a map, see [`map_pairs`](crate::DeserializeConfig::map_pairs).
+    ExpectedPair,
+    /// A dotted key not followed by an identifier after a dot, or by another dot or a colon,
+    /// see [`dotted_keys`](crate::DeserializeConfig::dotted_keys).
+    ExpectedDottedKey,
+    /// A key both dotted and written on its own in the same map, or dotted again after other entries,
+    /// with the key as far as it's dotted, e.g. `server.tls`.
+    ConflictingDottedKey(String),
     ExpectedVariant,
     ExpectedUnitVariant,
     ExpectedNewtypeVariant,
     ExpectedTupleVariant,
     ExpectedStructVariant,
     ExpectedEof,
+    /// A struct name other than the expected one, see [`check_struct_names`](crate::DeserializeConfig::check_struct_names).
+    UnexpectedStructName {
+        expected: String,
+        found: String,
+    },
+    /// The visitor rejected a `%` value, see [`Deserialize`](ErrorKind::Deserialize).
+    InvalidMayary(String),
+
+    /// A sequence or tuple has a different number of elements than expected, e.g. for `[T; N]`.
+    InvalidLength {
+        expected: usize,
+        found: usize,
+    },
 
-    Io(String),
+    /// An error of the reader or writer. While serializing, it's located at where the output stopped.
+    Io(IoError),
     Serialize(String),
     Deserialize(String),
+    /// A path of [`settings::Store`](crate::settings::Store) that no value is written at, which is given.
+    UnknownSetting(String),
 
     ExceededRecursionLimit,
+    /// A bytes literal longer than [`DeserializeConfig::max_bytes_len`](crate::DeserializeConfig::max_bytes_len),
+    /// which is given.
+    ExceededBytesLimit(usize),
+    /// A string literal longer than [`DeserializeConfig::max_string_len`](crate::DeserializeConfig::max_string_len),
+    /// which is given.
+    ExceededStringLimit(usize),
 }
 impl fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use ErrorKind::*;
         match self {
             UnexpectedEof => write!(f, "unexpected EOF"),
+            EmptyDocument => write!(f, "document contains no value (only comments/whitespace)"),
             UnexpectedToken => write!(f, "unexpected token"),
             UnexpectedNewline => write!(f, "this literal does not allow break, use `\\n` instead"),
             UnexpectedNonAscii => write!(f, "unexpected non ascii in byte string"),
             UnexpectedUnicodeEscape => write!(f, "unexpected unicode escape in byte string"),
             UnbalancedLiteralClose => write!(f, "unbalanced literal close"),
             InvalidNumber(e) => write!(f, "{}", e),
+            InvalidNanPayload => write!(f, "NaN payload must have all exponent bits set and some mantissa bits"),
+            UnclaimedNumber(text) => write!(f, "number `{}` has a suffix, but no number parser claimed it", text),
             InvalidCharacterTooLess => write!(f, "character literal must contain one codepoint"),
             InvalidCharacterTooMany => write!(f, "character literal may only contain one codepoint"),
             InvalidBytesEncoding(e) => write!(f, "{}", e),
+            InvalidUtf8 { offset, bytes, .. } => {
+                write!(f, "invalid UTF-8 sequence `")?;
+                for (i, b) in bytes.iter().enumerate() {
+                    write!(f, "{}{:02X}", if i == 0 { "" } else { " " }, b)?;
+                }
+                write!(f, "` at byte offset {}", offset)
+            }
             InvalidEscape => write!(f, "invalid escape"),
             InvalidAsciiEscape => write!(f, "ASCII hex escape code must be at most 0x7F"),
             InvalidUnicodeEscape => write!(f, "Unicode escape code muse be at most 10FFFF"),
 
             ExpectedComma => write!(f, "expected comma"),
             ExpectedFatArrow => write!(f, "expected fat arrow"),
-            ExpectedNonUnitStruct => write!(f, "expected non-unit struct (newtype, tuple or map)"),
+            ExpectedNonUnitStruct(found) => write!(
+                f,
+                "expected `(`, `{{` or `%` to continue the struct name, or a delimiter to end it as a unit struct, found {}",
+                found
+            ),
+            ExpectedPair => write!(f, "expected a pair `(key, value)`"),
+            ExpectedDottedKey => write!(f, "expected `.` and an identifier or `:` after dotted key"),
+            ConflictingDottedKey(key) => write!(
+                f,
+                "dotted key `{}` conflicts with an earlier entry, its entries must be consecutive and not written otherwise",
+                key
+            ),
             ExpectedVariant => write!(f, "expected variant (an identifier)"),
             ExpectedUnitVariant => write!(f, "expected unit variant"),
             ExpectedNewtypeVariant => write!(f, "expected newtype variant"),
             ExpectedTupleVariant => write!(f, "expected tuple variant"),
             ExpectedStructVariant => write!(f, "expected struct variant"),
+            UnexpectedStructName { expected, found } => {
+                write!(f, "expected struct name `{}`, found `{}`", expected, found)
+            }
             ExpectedEof => write!(f, "expected EOF"),
+            InvalidMayary(e) => write!(
+                f,
+                "(deserialize) {}; note that `%` is a newtype wrapper with zero or one item: \
+                 `%` alone is nullary, `% T` wraps `T` and `% %` wraps a nullary",
+                e
+            ),
+
+            InvalidLength { expected, found } => write!(f, "expected {} elements, found {}", expected, found),
 
             Io(e) => write!(f, "(IO) {}", e),
             Serialize(e) => write!(f, "(serialize) {}", e),
             Deserialize(e) => write!(f, "(deserialize) {}", e),
+            UnknownSetting(path) => write!(f, "no setting at `{}`", path),
 
             ExceededRecursionLimit => write!(f, "exceeded recursion limit"),
+            ExceededBytesLimit(limit) => write!(f, "bytes literal exceeds the limit of {} bytes", limit),
+            ExceededStringLimit(limit) => write!(f, "string literal exceeds the limit of {} bytes", limit),
+        }
+    }
+}
+
+/// What was being parsed where an error happened, see [`Error::context`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorContext {
+    /// The value of a field, with its key, dotted if it's a dotted key, e.g. `server.port`.
+    Field(String),
+    /// The value of an entry of a map, by index from 0, where the key is not a field.
+    Entry(usize),
+    /// An element of a sequence or tuple, by index from 0.
+    Element(usize),
+    /// The content of a variant, e.g. `{ by: 1 }` of `Right { by: 1 }`.
+    Variant(String),
+}
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ErrorContext::*;
+        match self {
+            Field(key) => write!(f, "value of field `{}`", key),
+            Entry(index) => write!(f, "value of entry {}", index),
+            Element(index) => write!(f, "element {}", index),
+            Variant(name) => write!(f, "content of variant `{}`", name),
+        }
+    }
+}
+
+//==================================================================================================
+
+/// A non-fatal diagnostic collected by the deserializer, see [`Deserializer::warnings`](crate::Deserializer::warnings).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub line: Option<NonZeroU32>,
+    pub col: Option<NonZeroU32>,
+    pub kind: WarningKind,
+}
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Warning { line, col, kind } = self;
+        if let Some(n) = line {
+            write!(f, ":{}", n)?;
+            match col {
+                Some(m) => write!(f, ":{} ", m)?,
+                None => write!(f, ":-1 ")?,
+            }
+        }
+        write!(f, "{}", kind)
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WarningKind {
+    /// A bare `yes`, `no`, `on` or `off` was accepted as a boolean.
+    LenientBool(String),
+}
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use WarningKind::*;
+        match self {
+            LenientBool(s) => write!(f, "`{}` was accepted as a boolean, use `true` or `false` instead", s),
         }
     }
 }
@@ -0,0 +1,89 @@
+//! Adapters to be used with `#[serde(with = "...")]`, writing a single field in a specific literal form
+//! regardless of [`SerializeConfig`](crate::SerializeConfig).
+//!
+//! Each form applies to all the integers or strings inside the field, e.g. every element of a `Vec<u8>`.
+//! Deserialization is unchanged, as any form reads back the same. Other serializers see a newtype struct.
+
+use crate::ser::{BytesFlavor, Radix};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A literal form requested by one of the adapters, recognized by the serializer by the newtype name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Form {
+    Radix(Radix),
+    #[cfg(feature = "syntax-paragraph")]
+    Paragraph,
+    #[cfg(feature = "syntax-raw-strings")]
+    RawString,
+    /// Requested by the adapters in [`crate::bytes`].
+    Bytes(BytesFlavor),
+}
+
+const PREFIX: &str = "$keon::private::fmt::";
+
+impl Form {
+    pub(crate) fn from_name(name: &str) -> Option<Form> {
+        if let Some(flavor) = crate::bytes::flavor_from_name(name) {
+            return Some(Form::Bytes(flavor));
+        }
+        match name.strip_prefix(PREFIX)? {
+            "Binary" => Some(Form::Radix(Radix::Binary)),
+            "Octal" => Some(Form::Radix(Radix::Octal)),
+            "Hex" => Some(Form::Radix(Radix::Hexadecimal)),
+            #[cfg(feature = "syntax-paragraph")]
+            "Paragraph" => Some(Form::Paragraph),
+            #[cfg(feature = "syntax-raw-strings")]
+            "RawString" => Some(Form::RawString),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! form_adapter {
+    ($(#[$attr:meta])* $module:ident => $name:literal) => {
+        $(#[$attr])*
+        pub mod $module {
+            use super::*;
+
+            pub fn serialize<T: ?Sized + Serialize, S: Serializer>(v: &T, ser: S) -> Result<S::Ok, S::Error> {
+                ser.serialize_newtype_struct(concat!("$keon::private::fmt::", $name), v)
+            }
+
+            pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(der: D) -> Result<T, D::Error> {
+                T::deserialize(der)
+            }
+        }
+    };
+}
+
+form_adapter! {
+    /// Writes integers in binary, e.g. `0b1010` or `-0b1`.
+    binary => "Binary"
+}
+
+form_adapter! {
+    /// Writes integers in octal, e.g. `0o755`.
+    octal => "Octal"
+}
+
+form_adapter! {
+    /// Writes integers in hexadecimal, e.g. `0xff` or `-0x10`.
+    hex => "Hex"
+}
+
+form_adapter! {
+    /// Writes strings as paragraphs where pretty, even if they are short and have no newlines,
+    /// wrapped at [`SerializeConfig::wrap_width`](crate::SerializeConfig::wrap_width) if any.
+    ///
+    /// Strings a paragraph cannot represent, and strings where minimized, are quoted as usual.
+    #[cfg(feature = "syntax-paragraph")]
+    paragraph => "Paragraph"
+}
+
+form_adapter! {
+    /// Writes strings as raw strings, even if they are longer than escaped, e.g. `` `"no \escapes"` ``.
+    ///
+    /// Strings with control characters are quoted as usual.
+    #[cfg(feature = "syntax-raw-strings")]
+    raw_string => "RawString"
+}
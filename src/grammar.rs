@@ -0,0 +1,11 @@
+//! The grammar of KEON documents.
+
+/// The grammar in [EBNF](https://www.iso.org/standard/26153.html), e.g. to generate railroad diagrams
+/// or to write highlighters for editors.
+///
+/// It describes what [`from_str`](crate::from_str) accepts as a [`Value`](crate::Value) with the default config
+/// and features. Forms left out by disabled `syntax-*` features and extensions enabled by
+/// [`DeserializeConfig`](crate::DeserializeConfig) are noted in its comments.
+pub fn ebnf() -> &'static str {
+    include_str!("grammar.ebnf")
+}
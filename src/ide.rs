@@ -0,0 +1,511 @@
+//! Support for editors, answering what may be written at the cursor of a document being edited,
+//! guided by a schema from [`schema_for`](crate::schema::schema_for).
+//!
+//! Requires the feature `schemars`.
+
+use super::{lexer::*, limits::RECURSION_LIMIT};
+use logos::Logos;
+use schemars::Schema;
+
+/// Something that may be written at the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    /// The text to insert, e.g. `name`, `Circle`, `true` or `[]`.
+    pub label: String,
+    pub kind: CompletionKind,
+    /// The description of the field or variant in the schema, from its doc comment.
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    /// A field of a struct not written yet, at the start of an entry.
+    Field,
+    /// A variant of an enum, where a value is expected.
+    Variant,
+    /// The start of a literal or a structure where a value is expected,
+    /// e.g. `?`, `false`, `0`, `""`, `''`, `b""`, `()`, `[]` or `{}`.
+    Literal,
+}
+
+/// Lists what may be written at `offset`, a byte offset into `source`, for a document of `schema`.
+///
+/// The document is read up to the cursor only, so what follows may be incomplete or invalid.
+/// A word being typed at the cursor, e.g. `na` of `{ na }`, filters the completions by prefix.
+/// Inside literals and comments, or after a syntax error, nothing is suggested.
+/// Syntax to opt into with [`DeserializeConfig`](crate::DeserializeConfig), e.g. dotted keys, is always read.
+pub fn completions(source: &str, offset: usize, schema: &Schema) -> Vec<Completion> {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &source[..offset];
+    let word = before.len()
+        - before
+            .chars()
+            .rev()
+            .take_while(|&ch| unicode_ident::is_xid_continue(ch))
+            .map(char::len_utf8)
+            .sum::<usize>();
+    let (before, word) = before.split_at(word);
+
+    let mut lex = Token::lexer(before);
+    let mut tokens = Vec::new();
+    let mut end = 0;
+    while let Some(res) = lex.next() {
+        match res {
+            Ok(token) => tokens.push(token),
+            Err(_) => return vec![],
+        }
+        end = lex.span().end;
+    }
+    // Comments are skipped, an unterminated line comment is only told by the text after the last token.
+    if before[end..].rsplit('\n').next().unwrap_or_default().contains("//") {
+        return vec![];
+    }
+
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        path: vec![],
+        ttl: RECURSION_LIMIT,
+    };
+    let (expect, path) = match parser.value() {
+        Err(Stop::Cursor(expect, path)) => (expect, path),
+        Ok(()) | Err(Stop::Invalid) => return vec![],
+    };
+
+    let mut alts = Vec::new();
+    alternatives(schema, schema, None, 0, &mut alts);
+    for segment in &path {
+        let children: Vec<_> = alts.iter().filter_map(|alt| child(alt, segment)).collect();
+        alts.clear();
+        for child in children {
+            alternatives(schema, child, None, 0, &mut alts);
+        }
+    }
+
+    let mut completions = Vec::new();
+    for alt in &alts {
+        match &expect {
+            Expect::Key(written) => field_completions(alt, written, &mut completions),
+            Expect::Value => value_completions(alt, true, &mut completions),
+            Expect::Variant => value_completions(alt, false, &mut completions),
+            Expect::Nothing => (),
+        }
+    }
+
+    let mut seen = Vec::new();
+    completions.retain(|completion: &Completion| {
+        let new = completion.label.starts_with(word) && !seen.contains(&(completion.label.clone(), completion.kind));
+        seen.push((completion.label.clone(), completion.kind));
+        new
+    });
+    completions
+}
+
+//==================================================================================================
+
+/// A step from a value to one inside it.
+#[derive(Debug, Clone)]
+enum Segment<'s> {
+    /// A field of a struct or an entry of a map, `None` for keys other than strings.
+    Field(Option<String>),
+    /// An element of a sequence.
+    Index(usize),
+    /// An element in parentheses, of a tuple, or the value of a newtype variant if it's the only one.
+    Element(usize),
+    Variant(&'s str),
+    /// The key of a map entry, e.g. `1` of `1 => "one"`.
+    Key,
+}
+
+/// What the cursor is expected to be followed by.
+#[derive(Debug)]
+enum Expect {
+    /// The start of an entry, with the keys written before it.
+    Key(Vec<String>),
+    Value,
+    /// The variant after `Enum::`.
+    Variant,
+    /// A delimiter, or something the schema cannot tell.
+    Nothing,
+}
+
+enum Stop<'s> {
+    /// The tokens ran out where the cursor is.
+    Cursor(Expect, Vec<Segment<'s>>),
+    Invalid,
+}
+
+/// Reads the grammar like the deserializer, without a type to guide it, up to the cursor.
+///
+/// This is a second parser of the grammar, kept in step with the deserializer by `tests/ide.rs`,
+/// over a corpus that `tests/grammar.rs` requires to take every choice of the grammar.
+/// A change of the grammar thus needs documents of it, and this parser to agree on them.
+struct Parser<'s> {
+    tokens: Vec<Token<'s>>,
+    pos: usize,
+    path: Vec<Segment<'s>>,
+    /// The values that may still be nested, counted like the deserializer does.
+    ttl: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn peek(&self, n: usize) -> Option<&Token<'s>> {
+        self.tokens.get(self.pos + n)
+    }
+    fn peek_kind(&self) -> Option<TokenKind> {
+        self.peek(0).map(Token::kind)
+    }
+    fn next_kind(&mut self) -> Option<TokenKind> {
+        let kind = self.peek_kind();
+        self.pos += kind.is_some() as usize;
+        kind
+    }
+
+    fn cursor(&self, expect: Expect) -> Stop<'s> {
+        Stop::Cursor(expect, self.path.clone())
+    }
+
+    fn nested(
+        &mut self,
+        segment: Segment<'s>,
+        f: impl FnOnce(&mut Self) -> Result<(), Stop<'s>>,
+    ) -> Result<(), Stop<'s>> {
+        self.path.push(segment);
+        f(self)?;
+        self.path.pop();
+        Ok(())
+    }
+
+    /// Gives up like the deserializer beyond its recursion limit, which also bounds the stack of this parser.
+    fn value(&mut self) -> Result<(), Stop<'s>> {
+        self.ttl = self.ttl.checked_sub(1).ok_or(Stop::Invalid)?;
+        let res = self.value_unbounded();
+        self.ttl += 1;
+        res
+    }
+
+    fn value_unbounded(&mut self) -> Result<(), Stop<'s>> {
+        let ident = match self.peek(0) {
+            Some(Token::Ident(ident)) => Some(*ident),
+            _ => None,
+        };
+        match self.next_kind() {
+            None => Err(self.cursor(Expect::Value)),
+            Some(TokenKind::Literal) => Ok(()),
+            Some(TokenKind::Question | TokenKind::Percent) => match self.peek_kind() {
+                Some(kind) if kind.is_delimiter() => Ok(()),
+                _ => self.value(),
+            },
+            Some(TokenKind::Paren_) => match (self.peek_kind(), self.peek(1).map(Token::kind)) {
+                (Some(TokenKind::Ident), Some(TokenKind::_Paren)) => {
+                    self.pos += 2;
+                    self.struct_body(true)
+                }
+                (Some(TokenKind::_Paren), _) => {
+                    self.pos += 1;
+                    self.struct_body(false)
+                }
+                _ => self.tuple(),
+            },
+            Some(TokenKind::Brack_) => self.elements(TokenKind::_Brack).map(drop),
+            Some(TokenKind::Brace_) => self.map(),
+            Some(TokenKind::Ident) => self.variant(ident.unwrap_or_default()),
+            Some(_) => Err(Stop::Invalid),
+        }
+    }
+
+    /// After `(Name)` or `()`, where only the former is usually followed by the body.
+    fn struct_body(&mut self, named: bool) -> Result<(), Stop<'s>> {
+        match self.peek_kind() {
+            None if named => Err(self.cursor(Expect::Value)),
+            None => Err(self.cursor(Expect::Nothing)),
+            Some(TokenKind::Paren_) => {
+                self.pos += 1;
+                self.elements(TokenKind::_Paren).map(drop)
+            }
+            Some(TokenKind::Brace_ | TokenKind::Percent) => self.value(),
+            Some(_) => Ok(()),
+        }
+    }
+
+    fn variant(&mut self, mut name: &'s str) -> Result<(), Stop<'s>> {
+        if self.peek_kind() == Some(TokenKind::PathSep) {
+            self.pos += 1;
+            name = match self.peek(0) {
+                Some(Token::Ident(variant)) => *variant,
+                None => return Err(self.cursor(Expect::Variant)),
+                Some(_) => return Err(Stop::Invalid),
+            };
+            self.pos += 1;
+        }
+
+        match self.peek_kind() {
+            None => Err(self.cursor(Expect::Nothing)),
+            Some(TokenKind::Percent | TokenKind::Brace_) => self.nested(Segment::Variant(name), Self::value),
+            Some(TokenKind::Paren_) => {
+                self.pos += 1;
+                self.nested(Segment::Variant(name), |p| p.elements(TokenKind::_Paren).map(drop))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// After `(` of a tuple, where a single element needs a trailing comma, e.g. `(1,)`,
+    /// otherwise it would be a struct name, e.g. `(Name)`.
+    fn tuple(&mut self) -> Result<(), Stop<'s>> {
+        match self.elements(TokenKind::_Paren)? {
+            0 => Err(Stop::Invalid),
+            _ => Ok(()),
+        }
+    }
+
+    /// After `[` or `(`, until `close`. Returns the number of commas.
+    fn elements(&mut self, close: TokenKind) -> Result<usize, Stop<'s>> {
+        for index in 0.. {
+            if self.peek_kind() == Some(close) {
+                self.pos += 1;
+                return Ok(index);
+            }
+            let segment = match close {
+                TokenKind::_Paren => Segment::Element(index),
+                _ => Segment::Index(index),
+            };
+            self.nested(segment, Self::value)?;
+            match self.next_kind() {
+                None => return Err(self.cursor(Expect::Nothing)),
+                Some(TokenKind::Comma) => continue,
+                Some(kind) if kind == close => return Ok(index),
+                Some(_) => return Err(Stop::Invalid),
+            }
+        }
+        unreachable!()
+    }
+
+    /// A dotted key and its value, e.g. `server.tls.enabled: true`,
+    /// see [`DeserializeConfig::dotted_keys`](crate::DeserializeConfig::dotted_keys).
+    fn dotted_field(&mut self) -> Result<(), Stop<'s>> {
+        let depth = self.path.len();
+        while let Some(Token::Ident(key)) = self.peek(0) {
+            self.path.push(Segment::Field(Some(key.to_string())));
+            self.pos += 1;
+            match self.next_kind() {
+                Some(TokenKind::Dot) => continue,
+                Some(TokenKind::Colon) => {
+                    self.value()?;
+                    self.path.truncate(depth);
+                    return Ok(());
+                }
+                None => return Err(self.cursor(Expect::Nothing)),
+                Some(_) => return Err(Stop::Invalid),
+            }
+        }
+        match self.peek(0) {
+            // The fields written before in the same nested map are not known.
+            None => Err(self.cursor(Expect::Key(vec![]))),
+            Some(_) => Err(Stop::Invalid),
+        }
+    }
+
+    /// After `{`.
+    fn map(&mut self) -> Result<(), Stop<'s>> {
+        let mut keys = Vec::new();
+        loop {
+            match (self.peek(0), self.peek(1).map(Token::kind)) {
+                (None, _) => return Err(Stop::Cursor(Expect::Key(keys), self.path.clone())),
+                (Some(Token::_Brace), _) => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                (Some(Token::Ident(key)), Some(TokenKind::Colon)) => {
+                    let key = key.to_string();
+                    self.pos += 2;
+                    keys.push(key.clone());
+                    self.nested(Segment::Field(Some(key)), Self::value)?;
+                }
+                (Some(Token::Ident(key)), Some(TokenKind::Dot)) => {
+                    keys.push(key.to_string());
+                    self.dotted_field()?;
+                }
+                (Some(token), _) => {
+                    let key = match token {
+                        Token::Literal(Literal::Str(key)) => Some(key.to_string()),
+                        Token::Literal(Literal::String(key)) => Some(key.clone()),
+                        _ => None,
+                    };
+                    self.nested(Segment::Key, Self::value)?;
+                    if self.peek_kind() == Some(TokenKind::FatArrow) {
+                        self.pos += 1;
+                        keys.extend(key.clone());
+                        self.nested(Segment::Field(key), Self::value)?;
+                    }
+                }
+            }
+
+            match self.next_kind() {
+                None => return Err(self.cursor(Expect::Nothing)),
+                Some(TokenKind::Comma) => continue,
+                Some(TokenKind::_Brace) => return Ok(()),
+                Some(_) => return Err(Stop::Invalid),
+            }
+        }
+    }
+}
+
+//==================================================================================================
+
+/// Deep enough for any sensible schema, but stops recursive references that lead nowhere.
+const MAX_DEPTH: usize = 64;
+
+/// A schema that a value may match, with the keyword of the union it's an alternative of, if any.
+struct Alt<'s> {
+    schema: &'s Schema,
+    union: Option<&'static str>,
+}
+
+impl<'s> Alt<'s> {
+    fn get(&self, key: &str) -> Option<&'s Schema> {
+        self.schema.get(key).and_then(|sub| sub.try_into().ok())
+    }
+
+    fn description(schema: &Schema) -> Option<String> {
+        schema
+            .get("description")
+            .and_then(|desc| desc.as_str())
+            .map(str::to_owned)
+    }
+
+    /// The name of a variant with data, written as `{ "Variant": ... }` in JSON.
+    fn variant(&self) -> Option<&'s str> {
+        let props = self.schema.get("properties")?.as_object()?;
+        match self.schema.get("required")?.as_array()?.as_slice() {
+            [name] if self.union == Some("oneOf") && props.len() == 1 => name.as_str(),
+            _ => None,
+        }
+    }
+
+    fn types(&self) -> Vec<&'s str> {
+        match self.schema.get("type") {
+            Some(ty) if ty.is_string() => ty.as_str().into_iter().collect(),
+            Some(ty) => ty
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|ty| ty.as_str())
+                .collect(),
+            None => vec![],
+        }
+    }
+}
+
+/// Follows references and unions down to the schemas a value may match.
+fn alternatives<'s>(
+    root: &'s Schema,
+    schema: &'s Schema,
+    union: Option<&'static str>,
+    depth: usize,
+    out: &mut Vec<Alt<'s>>,
+) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+
+    let target = schema
+        .get("$ref")
+        .and_then(|reference| reference.as_str()?.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer)?.try_into().ok());
+    if let Some(target) = target {
+        return alternatives(root, target, union, depth + 1, out);
+    }
+
+    let mut branched = false;
+    for key in ["anyOf", "oneOf", "allOf"] {
+        let subs = schema.get(key).and_then(|subs| subs.as_array());
+        for sub in subs.into_iter().flatten().filter_map(|sub| sub.try_into().ok()) {
+            branched = true;
+            alternatives(root, sub, Some(key), depth + 1, out);
+        }
+    }
+    if !branched {
+        out.push(Alt { schema, union });
+    }
+}
+
+fn child<'s>(alt: &Alt<'s>, segment: &Segment) -> Option<&'s Schema> {
+    let prop = |name: &str| alt.get("properties")?.get(name)?.try_into().ok();
+    let item = |index: usize| alt.schema.get("prefixItems")?.get(index)?.try_into().ok();
+    match (alt.variant(), segment) {
+        (Some(variant), Segment::Variant(name)) if variant == *name => prop(name),
+        (Some(_), _) | (None, Segment::Variant(_) | Segment::Key) => None,
+        (None, Segment::Field(Some(name))) => prop(name).or_else(|| alt.get("additionalProperties")),
+        (None, Segment::Field(None)) => alt.get("additionalProperties"),
+        (None, Segment::Index(index)) => item(*index).or_else(|| alt.get("items")),
+        (None, Segment::Element(index)) => match alt.schema.get("prefixItems") {
+            Some(_) => item(*index),
+            None if *index == 0 => Some(alt.schema),
+            None => None,
+        },
+    }
+}
+
+fn field_completions(alt: &Alt, written: &[String], out: &mut Vec<Completion>) {
+    if alt.variant().is_some() {
+        return;
+    }
+    let props = alt.schema.get("properties").and_then(|props| props.as_object());
+    for (name, prop) in props.into_iter().flatten() {
+        if !written.contains(name) {
+            out.push(Completion {
+                label: name.clone(),
+                kind: CompletionKind::Field,
+                detail: prop.try_into().ok().and_then(Alt::description),
+            });
+        }
+    }
+}
+
+fn value_completions(alt: &Alt, literals: bool, out: &mut Vec<Completion>) {
+    let variant = |label: &str| Completion {
+        label: label.to_owned(),
+        kind: CompletionKind::Variant,
+        detail: Alt::description(alt.schema),
+    };
+    if let Some(name) = alt.variant() {
+        return out.push(variant(name));
+    }
+    let names = alt.schema.get("enum").and_then(|names| names.as_array());
+    for name in names.into_iter().flatten().chain(alt.schema.get("const")) {
+        if let Some(name) = name.as_str() {
+            out.push(variant(name));
+        }
+    }
+    if !literals || names.is_some() || alt.schema.get("const").is_some() {
+        return;
+    }
+
+    let types = alt.types();
+    let keon = |key: &str| alt.schema.get(key).is_some_and(|flag| flag == true);
+    for ty in &types {
+        let labels: &[&str] = match *ty {
+            "null" if types.len() > 1 || alt.union == Some("anyOf") => &["?"],
+            "null" => &["()"],
+            "boolean" => &["true", "false"],
+            "integer" => &["0"],
+            "number" => &["0.0"],
+            "string" if keon("x-keon-char") => &["''"],
+            "string" => &["\"\""],
+            "array" if keon("x-keon-bytes") => &["b\"\""],
+            "array" if alt.schema.get("prefixItems").is_some() => &["()"],
+            "array" => &["[]"],
+            "object" => &["{}"],
+            _ => &[],
+        };
+        out.extend(labels.iter().map(|label| Completion {
+            label: label.to_string(),
+            kind: CompletionKind::Literal,
+            detail: None,
+        }));
+    }
+}
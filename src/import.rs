@@ -0,0 +1,428 @@
+//! Converting tabular exports into KEON, e.g. into fixture files:
+//!
+//! ```
+//! use keon::import::{from_csv, CellType, SchemaHint};
+//!
+//! let csv = "id,name,zip\n1,Alice,01234\n2,\"Bob, Jr.\",\n";
+//! let rows = from_csv(csv.as_bytes(), &SchemaHint::new().column("zip", CellType::String)).unwrap();
+//! assert_eq!(
+//!     rows.to_string().unwrap(),
+//!     r#"[{"id"=>1,"name"=>"Alice","zip"=>"01234"},{"id"=>2,"name"=>"Bob, Jr.","zip"=>""}]"#,
+//! );
+//! ```
+//!
+//! Rows are read one by one, so that large exports can be written without holding them all, see [`csv_rows`]
+//! and [`Serializer::collect_seq_streaming`](crate::Serializer::collect_seq_streaming).
+
+use crate::{value::Seq, Error, ErrorContext, ErrorKind, Number, Result, Value};
+use serde::de::{Error as _, Unexpected};
+use std::{
+    io::{BufRead, BufReader, Read},
+    num::NonZeroU32,
+};
+
+/// How the cells of a column are read, see [`SchemaHint`].
+///
+/// Empty cells are empty strings where strings are read, see [`SchemaHint::optional`] for missing values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CellType {
+    /// Booleans, integers and floats as written in KEON, otherwise strings.
+    /// Integers with leading zeros, e.g. `007`, are strings as well.
+    #[default]
+    Infer,
+    String,
+    Bool,
+    Integer,
+    Float,
+}
+
+/// The types of the columns of a CSV export by their names, and its delimiter, see [`from_csv`].
+#[derive(Debug, Clone)]
+pub struct SchemaHint {
+    /// With whether the column is optional.
+    columns: Vec<(String, CellType, bool)>,
+    default: CellType,
+    delimiter: char,
+}
+
+impl Default for SchemaHint {
+    fn default() -> Self {
+        Self {
+            columns: Vec::new(),
+            default: CellType::Infer,
+            delimiter: ',',
+        }
+    }
+}
+
+impl SchemaHint {
+    /// Every column inferred, delimited by commas.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn column(mut self, name: impl Into<String>, typ: CellType) -> Self {
+        let name = name.into();
+        match self.columns.iter_mut().find(|(column, ..)| *column == name) {
+            Some(column) => column.1 = typ,
+            None => self.columns.push((name, typ, false)),
+        }
+        self
+    }
+
+    /// Reads the column as options for `Option` fields, which KEON writes as `? value`,
+    /// with empty cells as `?`.
+    pub fn optional(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        match self.columns.iter_mut().find(|(column, ..)| *column == name) {
+            Some(column) => column.2 = true,
+            None => self.columns.push((name, self.default, true)),
+        }
+        self
+    }
+
+    /// The type of the columns not given, [`CellType::Infer`] by default.
+    pub fn default_type(mut self, typ: CellType) -> Self {
+        self.default = typ;
+        self
+    }
+
+    /// E.g. `;` or `\t`, `,` by default.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    fn typ(&self, name: &str) -> (CellType, bool) {
+        match self.columns.iter().find(|(column, ..)| column == name) {
+            Some(&(_, typ, optional)) => (typ, optional),
+            None => (self.default, false),
+        }
+    }
+}
+
+/// Reads a CSV export with a header as a sequence of maps from the names of the columns to the cells,
+/// as described by RFC 4180: cells may be quoted, with `""` for a quote, and span several lines if so.
+///
+/// As a [`Value::Map`] is ordered by its keys, the cells are in order of the names of their columns,
+/// not as the columns are in the CSV, see [`CsvRows::header`] for that. Names of columns must be unique.
+///
+/// Errors are located at the cell in the CSV, within the element of the row from 0 and the field of the column.
+pub fn from_csv<R: Read>(reader: R, hint: &SchemaHint) -> Result<Value> {
+    csv_rows(reader, hint)?.collect::<Result<Seq>>().map(Value::Seq)
+}
+
+/// Reads the header of a CSV export, and then its rows one by one, see [`from_csv`].
+pub fn csv_rows<R: Read>(reader: R, hint: &SchemaHint) -> Result<CsvRows<BufReader<R>>> {
+    let mut rows = CsvRows {
+        reader: BufReader::new(reader),
+        hint: hint.clone(),
+        header: Vec::new(),
+        line: 0,
+        row: 0,
+    };
+    for cell in rows.read_record()?.unwrap_or_default() {
+        if rows.header.contains(&cell.text) {
+            let e = Error::custom(format_args!("duplicate column `{}`", cell.text));
+            return Err(located(e.kind, cell.line, Some(cell.col)));
+        }
+        rows.header.push(cell.text);
+    }
+    Ok(rows)
+}
+
+/// The rows of a CSV export, see [`csv_rows`].
+#[derive(Debug)]
+pub struct CsvRows<R> {
+    reader: R,
+    hint: SchemaHint,
+    header: Vec<String>,
+    /// The number of lines read.
+    line: usize,
+    /// The number of rows read, after the header.
+    row: usize,
+}
+
+struct Cell {
+    line: usize,
+    col: usize,
+    text: String,
+}
+
+impl<R: BufRead> Iterator for CsvRows<R> {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Result<Value>> {
+        let record = match self.read_record() {
+            Ok(record) => record?,
+            Err(e) => return Some(Err(e)),
+        };
+        let row = self.row;
+        self.row += 1;
+
+        if record.len() != self.header.len() {
+            let e = located(
+                ErrorKind::InvalidLength {
+                    expected: self.header.len(),
+                    found: record.len(),
+                },
+                record[0].line,
+                None,
+            );
+            return Some(Err(e.within(|| ErrorContext::Element(row))));
+        }
+
+        let mut map = crate::value::Map::new();
+        for (name, cell) in self.header.iter().zip(record) {
+            match parse_cell(&cell.text, self.hint.typ(name)) {
+                Ok(value) => {
+                    map.insert(Value::from(name.as_str()), value);
+                }
+                Err(mut e) => {
+                    e.line = NonZeroU32::new(cell.line as u32);
+                    e.col = NonZeroU32::new(cell.col as u32);
+                    let e = e
+                        .within(|| ErrorContext::Field(name.clone()))
+                        .within(|| ErrorContext::Element(row));
+                    return Some(Err(e));
+                }
+            }
+        }
+        Some(Ok(Value::Map(map)))
+    }
+}
+
+impl<R> CsvRows<R> {
+    /// The names of the columns, in their order in the CSV.
+    pub fn header(&self) -> &[String] {
+        &self.header
+    }
+}
+
+impl<R: BufRead> CsvRows<R> {
+    /// The cells of the next record, skipping blank lines before it.
+    fn read_record(&mut self) -> Result<Option<Vec<Cell>>> {
+        let mut text = String::new();
+        loop {
+            text.clear();
+            if self.reader.read_line(&mut text)? == 0 {
+                return Ok(None);
+            }
+            self.line += 1;
+            if !text.trim_end_matches(['\r', '\n']).is_empty() {
+                break;
+            }
+        }
+
+        let delimiter = self.hint.delimiter;
+        let mut cells = Vec::new();
+        let mut cell = Cell {
+            line: self.line,
+            col: 1,
+            text: String::new(),
+        };
+        let mut col = 1;
+        let mut at_start = true;
+        let mut quoted = false;
+        let mut closed = false;
+        loop {
+            let mut chars = text.chars().peekable();
+            while let Some(ch) = chars.next() {
+                match ch {
+                    '"' if quoted => match chars.peek() {
+                        Some('"') => {
+                            chars.next();
+                            col += 1;
+                            cell.text.push('"');
+                        }
+                        _ => (quoted, closed) = (false, true),
+                    },
+                    '"' if at_start => quoted = true,
+                    _ if quoted => cell.text.push(ch),
+                    _ if ch == delimiter => {
+                        col += 1;
+                        let next = Cell {
+                            line: self.line,
+                            col,
+                            text: String::new(),
+                        };
+                        cells.push(std::mem::replace(&mut cell, next));
+                        (at_start, closed) = (true, false);
+                        continue;
+                    }
+                    '\r' | '\n' => (),
+                    _ if closed => return Err(located(ErrorKind::UnexpectedToken, self.line, Some(col))),
+                    _ => cell.text.push(ch),
+                }
+                at_start = false;
+                col += 1;
+            }
+
+            if !quoted {
+                break;
+            }
+            text.clear();
+            if self.reader.read_line(&mut text)? == 0 {
+                return Err(located(ErrorKind::UnexpectedEof, self.line, Some(col)));
+            }
+            self.line += 1;
+            col = 1;
+        }
+        cells.push(cell);
+        Ok(Some(cells))
+    }
+}
+
+fn parse_cell(text: &str, (typ, optional): (CellType, bool)) -> Result<Value> {
+    let invalid = |expected: &str| Error::invalid_value(Unexpected::Str(text), &expected);
+    if optional {
+        return Ok(match text.is_empty() {
+            true => Value::Opt(None),
+            false => Value::Opt(Some(Box::new(parse_cell(text, (typ, false))?))),
+        });
+    }
+    Ok(match typ {
+        CellType::String => Value::from(text),
+        CellType::Infer if text.is_empty() => Value::from(""),
+        CellType::Infer => infer_cell(text),
+        CellType::Bool => match text {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => return Err(invalid("a boolean")),
+        },
+        CellType::Integer => integer(text).ok_or_else(|| invalid("an integer"))?,
+        CellType::Float => match text.parse::<f64>() {
+            Ok(f) => Value::Number(Number::Float(f)),
+            Err(_) => return Err(invalid("a float")),
+        },
+    })
+}
+
+fn infer_cell(text: &str) -> Value {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    let leading_zero = digits.len() > 1 && digits.starts_with('0') && digits.as_bytes()[1].is_ascii_digit();
+    match text {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ if leading_zero || !digits.starts_with(|ch: char| ch.is_ascii_digit()) => Value::from(text),
+        _ => match (integer(text), text.parse::<f64>()) {
+            (Some(value), _) => value,
+            (None, Ok(f)) => Value::Number(Number::Float(f)),
+            (None, Err(_)) => Value::from(text),
+        },
+    }
+}
+
+/// Negative integers are `Int`s, others are `UInt`s, as deserialized. `-0` is the `UInt` `0` like `0`.
+fn integer(text: &str) -> Option<Value> {
+    match text.parse::<u64>() {
+        Ok(u) => Some(Value::Number(Number::UInt(u))),
+        Err(_) => text.parse::<i64>().ok().map(|i| match i {
+            0 => Value::Number(Number::UInt(0)),
+            _ => Value::Number(Number::Int(i)),
+        }),
+    }
+}
+
+fn located(kind: ErrorKind, line: usize, col: Option<usize>) -> Error {
+    Error {
+        line: NonZeroU32::new(line as u32),
+        col: col.and_then(|col| NonZeroU32::new(col as u32)),
+        kind,
+        context: Vec::new(),
+    }
+}
+
+/// Reads newline-delimited JSON as a sequence of its values, skipping blank lines, with `null`s as `?`.
+///
+/// Errors are located at the line and column in the JSON, within the element from 0. Requires the feature `ndjson`.
+#[cfg(feature = "ndjson")]
+pub fn from_ndjson<R: Read>(reader: R) -> Result<Value> {
+    from_ndjson_with(reader, &SchemaHint::new())
+}
+
+/// Like [`from_ndjson`], with the members of objects named as [optional](SchemaHint::optional) columns as options,
+/// e.g. `? 9.5` for `"score": 9.5`. The types of the columns do not apply, values are as typed in JSON.
+#[cfg(feature = "ndjson")]
+pub fn from_ndjson_with<R: Read>(reader: R, hint: &SchemaHint) -> Result<Value> {
+    ndjson_rows(reader, hint).collect::<Result<Seq>>().map(Value::Seq)
+}
+
+/// Reads the values of newline-delimited JSON one by one, see [`from_ndjson_with`].
+#[cfg(feature = "ndjson")]
+pub fn ndjson_rows<R: Read>(reader: R, hint: &SchemaHint) -> NdjsonRows<BufReader<R>> {
+    NdjsonRows {
+        reader: BufReader::new(reader),
+        hint: hint.clone(),
+        line: 0,
+        row: 0,
+    }
+}
+
+/// The values of newline-delimited JSON, see [`ndjson_rows`].
+#[cfg(feature = "ndjson")]
+#[derive(Debug)]
+pub struct NdjsonRows<R> {
+    reader: R,
+    hint: SchemaHint,
+    line: usize,
+    row: usize,
+}
+
+#[cfg(feature = "ndjson")]
+impl<R: BufRead> Iterator for NdjsonRows<R> {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Result<Value>> {
+        let mut text = String::new();
+        loop {
+            text.clear();
+            match self.reader.read_line(&mut text) {
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(e) => return Some(Err(e.into())),
+            }
+            if !text.trim().is_empty() {
+                break;
+            }
+        }
+
+        let row = self.row;
+        self.row += 1;
+        let value = match serde_json::from_str::<Value>(&text) {
+            Ok(value) => nulls_as_none(value),
+            Err(e) => {
+                let message = e.to_string();
+                let suffix = format!(" at line {} column {}", e.line(), e.column());
+                let message = message.strip_suffix(&suffix).unwrap_or(&message);
+                let e = located(ErrorKind::Deserialize(message.into()), self.line, Some(e.column()));
+                return Some(Err(e.within(|| ErrorContext::Element(row))));
+            }
+        };
+        Some(Ok(match value {
+            Value::Map(map) => Value::Map(
+                map.into_iter()
+                    .map(|(key, value)| match (&key, value) {
+                        (Value::String(name), value @ Value::Opt(_)) if self.hint.typ(name).1 => (key, value),
+                        (Value::String(name), value) if self.hint.typ(name).1 => {
+                            (key, Value::Opt(Some(Box::new(value))))
+                        }
+                        (_, value) => (key, value),
+                    })
+                    .collect(),
+            ),
+            value => value,
+        }))
+    }
+}
+
+/// JSON has `null`s only, which are read as units.
+#[cfg(feature = "ndjson")]
+fn nulls_as_none(value: Value) -> Value {
+    match value {
+        Value::Unit => Value::Opt(None),
+        Value::Seq(seq) => Value::Seq(seq.into_iter().map(nulls_as_none).collect()),
+        Value::Map(map) => Value::Map(map.into_iter().map(|(k, v)| (k, nulls_as_none(v))).collect()),
+        value => value,
+    }
+}
@@ -4,19 +4,27 @@
 #![allow(clippy::upper_case_acronyms)]
 
 use super::{value::*, *};
+#[cfg(feature = "syntax-basexx")]
 use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD, HEXUPPER_PERMISSIVE};
 use lexical_core::{
     parse_with_options, NumberFormatBuilder, ParseFloatOptions, ParseFloatOptionsBuilder, ParseIntegerOptions,
     ParseIntegerOptionsBuilder,
 };
 use logos::{FilterResult, Lexer, Logos, Skip};
-use std::{cell::RefCell, cmp::Ordering, num::NonZeroU8, rc::Rc};
+#[cfg(feature = "syntax-raw-strings")]
+use std::cmp::Ordering;
+use std::num::NonZeroU8;
 
 pub(crate) type LexerResult<T> = core::result::Result<T, ErrorKind>;
 
 //==================================================================================================
 
 const NUMBER_FMT: u128 = NumberFormatBuilder::rebuild(lexical_core::format::RUST_STRING)
+    .digit_separator(NonZeroU8::new(b'_'))
+    .leading_digit_separator(true) // only possible after base prefixes and exponent signs, see `Token`.
+    .internal_digit_separator(true)
+    .trailing_digit_separator(true)
+    .consecutive_digit_separator(true)
     .no_special(false)
     .case_sensitive_special(true)
     .case_sensitive_base_prefix(true)
@@ -59,6 +67,7 @@ pub(crate) enum TokenKind {
     Comma,   PathSep,
     Colon,   FatArrow,
     Percent, Question,
+    Dot,
     Paren_, _Paren,
     Brack_, _Brack,
     Brace_, _Brace,
@@ -88,6 +97,7 @@ impl Token<'_> {
             Token::PathSep => TokenKind::PathSep,
             Token::Question => TokenKind::Question,
             Token::FatArrow => TokenKind::FatArrow,
+            Token::Dot => TokenKind::Dot,
 
             Token::Paren_ => TokenKind::Paren_,
             Token::_Paren => TokenKind::_Paren,
@@ -101,12 +111,36 @@ impl Token<'_> {
 
 //==================================================================================================
 
-type Extras = Rc<RefCell<InnerExtras>>;
+type Extras = InnerExtras;
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) struct InnerExtras {
     pub(crate) line: u32,
     pub(crate) line_start: usize,
+
+    /// See [`DeserializeConfig::single_quoted_strings`](crate::DeserializeConfig::single_quoted_strings).
+    pub(crate) single_quoted_strings: bool,
+    /// See [`DeserializeConfig::max_bytes_len`](crate::DeserializeConfig::max_bytes_len).
+    pub(crate) max_bytes_len: usize,
+    /// See [`DeserializeConfig::max_string_len`](crate::DeserializeConfig::max_string_len).
+    pub(crate) max_string_len: usize,
+    /// See [`DeserializeConfig::preserve_paragraph_indent`](crate::DeserializeConfig::preserve_paragraph_indent).
+    #[cfg(feature = "syntax-paragraph")]
+    pub(crate) preserve_paragraph_indent: bool,
+}
+
+impl Default for InnerExtras {
+    fn default() -> Self {
+        Self {
+            line: 0,
+            line_start: 0,
+            single_quoted_strings: false,
+            max_bytes_len: usize::MAX,
+            max_string_len: usize::MAX,
+            #[cfg(feature = "syntax-paragraph")]
+            preserve_paragraph_indent: false,
+        }
+    }
 }
 
 #[rustfmt::skip]
@@ -131,15 +165,19 @@ pub(crate) enum Token<'src> {
     // dec_alt =   r"_*([0-9]_*)+"  # Allows start with underscore.
     // float   =  fr"-?({dec}((\.{dec})?[Ee][+-]?{dec_alt}|\.({dec})?)|inf|NaN)"
     #[regex(r"-?(([0-9]_*)+((\.([0-9]_*)+)?[Ee][+-]?_*([0-9]_*)+|\.(([0-9]_*)+)?)|inf|NaN)", cb::floating)]
+    // hex_float = fr"-?0x_*{hex}(\.({hex})?)?[Pp][+-]?{dec_alt}"  # The binary exponent is required.
+    #[regex(r"-?0x_*([0-9A-Fa-f]_*)+(\.(([0-9A-Fa-f]_*)+)?)?[Pp][+-]?_*([0-9]_*)+", cb::hex_floating)]
+    // A number followed by a suffix or a fraction, e.g. `3.5m` or `1/3`, is left to number parsers.
+    #[regex(r"-?[0-9][0-9A-Za-z_.]*(/[0-9][0-9A-Za-z_.]*)?", |lex| Literal::Suffixed(lex.slice()), priority = 0)]
     #[regex(   "\'",       cb::char)]
     #[regex(   "\"",       cb::string)]
-    #[regex( "`+\"", |lex| cb::raw_string(lex, lex.slice().len() - 1))]
+    #[cfg_attr(feature = "syntax-raw-strings", regex( "`+\"", |lex| cb::raw_string(lex, lex.slice().len() - 1)))]
     #[regex(  "b\"",       cb::bytes)]
-    #[regex("b`+\"", |lex| cb::raw_bytes(lex, lex.slice().len() - 2))]
-    #[regex("b16\"", |lex| cb::bytes_encoding(lex, BaseXX::Base16))]
-    #[regex("b32\"", |lex| cb::bytes_encoding(lex, BaseXX::Base32))]
-    #[regex("b64\"", |lex| cb::bytes_encoding(lex, BaseXX::Base64))]
-    #[regex(  r"\|[^\n]*", cb::paragraph)]
+    #[cfg_attr(feature = "syntax-raw-strings", regex("b`+\"", |lex| cb::raw_bytes(lex, lex.slice().len() - 2)))]
+    #[cfg_attr(feature = "syntax-basexx", regex("b16\"", |lex| cb::bytes_encoding(lex, BaseXX::Base16)))]
+    #[cfg_attr(feature = "syntax-basexx", regex("b32\"", |lex| cb::bytes_encoding(lex, BaseXX::Base32)))]
+    #[cfg_attr(feature = "syntax-basexx", regex("b64\"", |lex| cb::bytes_encoding(lex, BaseXX::Base64)))]
+    #[cfg_attr(feature = "syntax-paragraph", regex(r"\|[^\n]*", cb::paragraph))]
     Literal(Literal<'src>),
 
     #[token(",")] Comma,
@@ -148,6 +186,8 @@ pub(crate) enum Token<'src> {
     #[token("?")] Question,
     #[token("::")] PathSep,
     #[token("=>")] FatArrow,
+    /// Only in dotted keys, see [`DeserializeConfig::dotted_keys`](crate::DeserializeConfig::dotted_keys).
+    #[token(".")] Dot,
 
     #[token("(")] Paren_,
     #[token(")")] _Paren,
@@ -179,7 +219,8 @@ pub(crate) enum TokenEscape {
     #[token("'")] Prime,
     #[regex("\"`*", |lex| lex.slice().len() - 1)] Quote(usize),
 
-    #[regex(r#"[^'"\\\n]+"#,           priority = 0)] NoEscapeUtf8,
+    // Disjoint, overlapping runs overflow the stack of debug builds on long literals.
+    #[regex(r#"[^\x00-\x7F]+"#,           priority = 0)] NoEscapeUtf8,
     #[regex(r#"[\x00-\x7F--'"\\\n]+"#, priority = 1)] NoEscapeAscii,
 
     #[regex(r#"\\."#, callback = |_| raise(ErrorKind::InvalidEscape), priority = 2)]
@@ -188,17 +229,19 @@ pub(crate) enum TokenEscape {
     #[regex(r#"\\u\{([0-9A-Fa-f]_*)+\}"#,                             priority = 5)] EscapeUnicode,
 }
 
+#[cfg(feature = "syntax-raw-strings")]
 #[rustfmt::skip]
 #[derive(Debug, Logos)]
 #[logos(error = ErrorKind, extras = Extras)]
 enum TokenNoEscape {
     #[regex("\"`*", |lex| lex.slice().len() - 1)] Quote(usize),
-    #[regex(r#"[^"]+"#,            priority = 0)] NoEscapeUtf8,
+    #[regex(r#"[^\x00-\x7F]+"#,   priority = 0)] NoEscapeUtf8,
     #[regex(r#"[\x00-\x7F--"]+"#,  priority = 1)] NoEscapeAscii,
 
     #[token("\n", |lex| { cb::newline(lex); Skip })] UNINHABITED,
 }
 
+#[cfg(feature = "syntax-paragraph")]
 #[rustfmt::skip]
 #[derive(Debug, Logos)]
 #[logos(error = ErrorKind, extras = Extras)]
@@ -216,26 +259,49 @@ pub(crate) enum Literal<'i> {
     UInt(u64),
     Float(f64),
     Char(char),
+    #[cfg_attr(
+        not(any(feature = "syntax-paragraph", feature = "syntax-raw-strings")),
+        allow(dead_code)
+    )]
     Str(&'i str),
     String(String),
+    #[cfg_attr(not(feature = "syntax-raw-strings"), allow(dead_code))]
     Bytes(&'i [u8]),
     ByteBuf(ByteBuf),
+    /// See [`Deserializer::number_parser`](crate::Deserializer::number_parser).
+    Suffixed(&'i str),
 }
 
 #[derive(Debug)] #[rustfmt::skip]
 pub(crate) enum Radix { Dec, Bin, Oct, Hex }
 
+#[cfg(feature = "syntax-basexx")]
 #[derive(Debug)] #[rustfmt::skip]
 pub(crate) enum BaseXX { Base16, Base32, Base64 }
 
-fn switch<'i, Token1, Token2>(lex: &Lexer<'i, Token1>) -> Lexer<'i, Token2>
+/// Lexes the rest of the current token with `Token2`, which starts with a copy of the extras,
+/// and hands them back afterwards, e.g. the lines counted.
+fn switch<'i, Token1, Token2, R>(
+    lex: &mut Lexer<'i, Token1>,
+    f: impl FnOnce(&mut Lexer<'i, Token1>, &mut Lexer<'i, Token2>) -> R,
+) -> R
 where
     Token1: Logos<'i, Extras = Extras, Source = str>,
     Token2: Logos<'i, Extras = Extras, Source = str>,
 {
-    let mut tks = Token2::lexer_with_extras(lex.source(), lex.extras.clone());
+    let mut tks = Token2::lexer_with_extras(lex.source(), lex.extras);
     tks.bump(lex.span().end);
-    tks
+    let r = f(lex, &mut tks);
+    lex.extras = tks.extras;
+    r
+}
+
+/// Whether `text` is read as a single identifier, so that it can be written as a key or a name.
+pub(crate) fn is_ident(text: &str) -> bool {
+    let mut chs = text.chars();
+    chs.next()
+        .is_some_and(|start| unicode_ident::is_xid_start(start) || start == '_')
+        && chs.all(unicode_ident::is_xid_continue)
 }
 
 mod cb {
@@ -245,9 +311,8 @@ mod cb {
     where
         T: Logos<'i, Extras = Extras>,
     {
-        let mut extras = lex.extras.borrow_mut();
-        extras.line += 1;
-        extras.line_start = lex.span().end;
+        lex.extras.line += 1;
+        lex.extras.line_start = lex.span().end;
     }
 
     pub(crate) fn line_comment<'i>(lex: &mut Lexer<'i, Token<'i>>) -> FilterResult<(), ErrorKind> {
@@ -261,29 +326,30 @@ mod cb {
     }
 
     pub(crate) fn block_comment<'i>(lex: &mut Lexer<'i, Token<'i>>) -> FilterResult<(), ErrorKind> {
-        let mut tks = switch::<_, TokenComment>(lex);
-        let mut ctr = 1;
-        let mut cursor = lex.span().end;
-
-        while let Some(t) = match tks.next().transpose() {
-            Ok(opt) => opt,
-            Err(ek) => return FilterResult::Error(ek),
-        } {
-            lex.bump(tks.span().end - cursor);
-            cursor = tks.span().end;
-
-            match t {
-                TokenComment::Block_ => ctr += 1,
-                TokenComment::_Block => ctr -= 1,
-                TokenComment::UNINHABITED => unreachable!(),
-            }
+        switch(lex, |lex, tks: &mut Lexer<'i, TokenComment>| {
+            let mut ctr = 1;
+            let mut cursor = lex.span().end;
+
+            while let Some(t) = match tks.next().transpose() {
+                Ok(opt) => opt,
+                Err(ek) => return FilterResult::Error(ek),
+            } {
+                lex.bump(tks.span().end - cursor);
+                cursor = tks.span().end;
+
+                match t {
+                    TokenComment::Block_ => ctr += 1,
+                    TokenComment::_Block => ctr -= 1,
+                    TokenComment::UNINHABITED => unreachable!(),
+                }
 
-            if ctr == 0 {
-                return FilterResult::Skip;
+                if ctr == 0 {
+                    return FilterResult::Skip;
+                }
             }
-        }
 
-        FilterResult::Error(ErrorKind::UnexpectedEof)
+            FilterResult::Error(ErrorKind::UnexpectedEof)
+        })
     }
 
     pub(crate) fn ident<'i>(lex: &mut Lexer<'i, Token<'i>>, slice: &'i str) -> LexerResult<&'i str> {
@@ -330,219 +396,439 @@ mod cb {
         })
     }
 
-    pub(crate) fn floating<'i>(lex: &Lexer<'i, Token<'i>>) -> LexerResult<Literal<'i>> {
+    pub(crate) fn floating<'i>(lex: &mut Lexer<'i, Token<'i>>) -> LexerResult<Literal<'i>> {
+        if lex.slice() == "NaN" {
+            if let Some(f) = nan_payload(lex) {
+                return f;
+            }
+        }
+
         Ok(Literal::Float(
             parse_with_options::<_, NUMBER_FMT>(lex.slice().as_bytes(), PARSE_OPTS_FLOAT)
                 .map_err(ErrorKind::InvalidNumber)?,
         ))
     }
 
-    pub(crate) fn char<'i>(lex: &mut Lexer<'i, Token<'i>>) -> LexerResult<Literal<'i>> {
-        let mut tks = switch::<_, TokenEscape>(lex);
-
-        if let Some(t) = tks.next().transpose()? {
-            lex.bump(tks.slice().len());
-            let ch = match t {
-                TokenEscape::Newline => Err(ErrorKind::UnexpectedNewline)?,
-                TokenEscape::Prime => Err(ErrorKind::InvalidCharacterTooLess)?,
-                TokenEscape::Quote(n) => match n {
-                    0 => '"',
-                    _ => Err(ErrorKind::InvalidCharacterTooMany)?,
-                },
-                TokenEscape::NoEscapeUtf8 | TokenEscape::NoEscapeAscii => {
-                    let mut chs = tks.slice().chars();
-                    let ch = chs.next().unwrap();
-                    if chs.next().is_some() {
-                        Err(ErrorKind::InvalidCharacterTooMany)?
-                    }
-                    ch
+    /// Like `NaN:0x7ff8000000000001` after `NaN`, with the bits of `f64` or of `f32` by the number of digits,
+    /// see `SerializeConfig::nan_payloads`. Anything else after `NaN` is left to the next token.
+    fn nan_payload<'i>(lex: &mut Lexer<'i, Token<'i>>) -> Option<LexerResult<Literal<'i>>> {
+        let rest = lex.remainder().strip_prefix(":0x")?;
+        let digits = &rest[..rest.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(rest.len())];
+        let bits = u64::from_str_radix(digits, 16).ok()?;
+        let f = match digits.len() {
+            8 => f32::from_bits(bits as u32) as f64,
+            16 => f64::from_bits(bits),
+            _ => return None,
+        };
+        lex.bump(3 + digits.len());
+        Some(match f.is_nan() {
+            true => Ok(Literal::Float(f)),
+            false => Err(ErrorKind::InvalidNanPayload),
+        })
+    }
+
+    /// Like `0x1.8p3`, the value is the hexadecimal mantissa multiplied by 2 to the power of the decimal exponent.
+    ///
+    /// Parsed by hand with correct rounding, the syntax has been checked by the regex.
+    pub(crate) fn hex_floating<'i>(lex: &Lexer<'i, Token<'i>>) -> LexerResult<Literal<'i>> {
+        let s = lex.slice();
+        let (neg, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let (mantissa, exponent) = s[2..].split_once(['p', 'P']).unwrap();
+
+        // Value is `m * 2^e`, digits beyond 120 bits only matter for rounding.
+        let (mut m, mut e, mut sticky) = (0u128, 0i64, false);
+        let mut fraction = false;
+        for ch in mantissa.chars() {
+            let d = match ch {
+                '.' => {
+                    fraction = true;
+                    continue;
                 }
-                TokenEscape::EscapeByte => Err(ErrorKind::InvalidAsciiEscape)?,
-                TokenEscape::EscapeAscii => esc::ascii(&tks),
-                TokenEscape::EscapeUnicode => esc::unicode(&tks)?,
+                '_' => continue,
+                _ => ch.to_digit(16).unwrap() as u128,
             };
+            match m >> 120 == 0 {
+                true => {
+                    m = m << 4 | d;
+                    e -= fraction as i64 * 4;
+                }
+                false => {
+                    sticky |= d != 0;
+                    e += !fraction as i64 * 4;
+                }
+            }
+        }
+
+        let magnitude = exponent
+            .bytes()
+            .filter(u8::is_ascii_digit)
+            .fold(0i64, |acc, b| (acc * 10 + (b - b'0') as i64).min(1 << 20));
+        e += match exponent.starts_with('-') {
+            true => -magnitude,
+            false => magnitude,
+        };
+
+        let f = round_to_f64(m, e, sticky);
+        Ok(Literal::Float(if neg { -f } else { f }))
+    }
+
+    /// Rounds `m * 2^e` to the nearest `f64`, ties to even, `sticky` means some lower bits were non-zero.
+    fn round_to_f64(m: u128, e: i64, sticky: bool) -> f64 {
+        if m == 0 {
+            return 0.0;
+        }
+
+        let n = 128 - m.leading_zeros() as i64;
+        let exp = n - 1 + e;
+        if exp > 1023 {
+            return f64::INFINITY;
+        }
 
-            if let Some(TokenEscape::Prime) = tks.next().transpose()? {
-                lex.bump(1);
-                return Ok(Literal::Char(ch));
+        // 53 bits of precision, less for subnormals.
+        let keep = match exp >= -1022 {
+            true => 53,
+            false => exp + 1075,
+        };
+        let shift = n - keep;
+        let mant = match shift {
+            ..=0 => m << -shift,
+            1..=128 => {
+                let mant = m.checked_shr(shift as u32).unwrap_or(0);
+                let rem = m & (u128::MAX >> (128 - shift));
+                let half = 1u128 << (shift - 1);
+                match rem > half || (rem == half && (sticky || mant & 1 == 1)) {
+                    true => mant + 1,
+                    false => mant,
+                }
             }
+            _ => 0,
+        };
+        if mant == 0 {
+            return 0.0;
+        }
+
+        // Exact, unless it overflows into infinity.
+        let k = e + shift;
+        let scale = match k >= -1022 {
+            true => f64::from_bits(((k + 1023) as u64) << 52),
+            false => f64::from_bits(1 << (k + 1074)),
+        };
+        mant as f64 * scale
+    }
+
+    pub(crate) fn char<'i>(lex: &mut Lexer<'i, Token<'i>>) -> LexerResult<Literal<'i>> {
+        if lex.extras.single_quoted_strings {
+            return single_quoted(lex);
         }
 
-        Err(ErrorKind::UnexpectedEof)
+        switch(lex, |lex, tks: &mut Lexer<'i, TokenEscape>| {
+            if let Some(t) = tks.next().transpose()? {
+                lex.bump(tks.slice().len());
+                let ch = match t {
+                    TokenEscape::Newline => Err(ErrorKind::UnexpectedNewline)?,
+                    TokenEscape::Prime => Err(ErrorKind::InvalidCharacterTooLess)?,
+                    TokenEscape::Quote(n) => match n {
+                        0 => '"',
+                        _ => Err(ErrorKind::InvalidCharacterTooMany)?,
+                    },
+                    TokenEscape::NoEscapeUtf8 | TokenEscape::NoEscapeAscii => {
+                        let mut chs = tks.slice().chars();
+                        let ch = chs.next().unwrap();
+                        if chs.next().is_some() {
+                            Err(ErrorKind::InvalidCharacterTooMany)?
+                        }
+                        ch
+                    }
+                    TokenEscape::EscapeByte => Err(ErrorKind::InvalidAsciiEscape)?,
+                    TokenEscape::EscapeAscii => esc::ascii(tks),
+                    TokenEscape::EscapeUnicode => esc::unicode(tks)?,
+                };
+
+                match tks.next().transpose()? {
+                    Some(TokenEscape::Prime) => {
+                        lex.bump(1);
+                        return Ok(Literal::Char(ch));
+                    }
+                    Some(TokenEscape::Newline) | None => (),
+                    Some(_) => Err(ErrorKind::InvalidCharacterTooMany)?,
+                }
+            }
+
+            Err(ErrorKind::UnexpectedEof)
+        })
+    }
+
+    /// A char literal if there is exactly one character (or escape) between the primes,
+    /// otherwise a string literal, in which `"` needs no escape.
+    pub(crate) fn single_quoted<'i>(lex: &mut Lexer<'i, Token<'i>>) -> LexerResult<Literal<'i>> {
+        switch(lex, |lex, tks: &mut Lexer<'i, TokenEscape>| {
+            let mut s = String::new();
+
+            while let Some(t) = tks.next().transpose()? {
+                lex.bump(tks.slice().len());
+                match t {
+                    TokenEscape::Newline => Err(ErrorKind::UnexpectedNewline)?,
+                    TokenEscape::Prime => {
+                        let mut chs = s.chars();
+                        return Ok(match (chs.next(), chs.next()) {
+                            (Some(ch), None) => Literal::Char(ch),
+                            _ => Literal::String(s),
+                        });
+                    }
+                    TokenEscape::Quote(_) | TokenEscape::NoEscapeUtf8 | TokenEscape::NoEscapeAscii => {
+                        s.push_str(tks.slice())
+                    }
+                    TokenEscape::EscapeByte => Err(ErrorKind::InvalidAsciiEscape)?,
+                    TokenEscape::EscapeAscii => s.push(esc::ascii(tks)),
+                    TokenEscape::EscapeUnicode => s.push(esc::unicode(tks)?),
+                }
+                check_string_len(lex, s.len())?;
+            }
+
+            Err(ErrorKind::UnexpectedEof)
+        })
     }
 
     // IMPROVE: Is it possible to borrow a "normal string without escape"?
     pub(crate) fn string<'i>(lex: &mut Lexer<'i, Token<'i>>) -> LexerResult<Literal<'i>> {
-        let mut tks = switch::<_, TokenEscape>(lex);
-        let mut s = String::new();
-
-        while let Some(t) = tks.next().transpose()? {
-            lex.bump(tks.slice().len());
-            match t {
-                TokenEscape::Newline => Err(ErrorKind::UnexpectedNewline)?,
-                TokenEscape::Prime => s.push('\''),
-                TokenEscape::Quote(n) => match n {
-                    0 => return Ok(Literal::String(s)),
-                    _ => Err(ErrorKind::UnbalancedLiteralClose)?,
-                },
-                TokenEscape::NoEscapeUtf8 | TokenEscape::NoEscapeAscii => s.push_str(tks.slice()),
-                TokenEscape::EscapeByte => Err(ErrorKind::InvalidAsciiEscape)?,
-                TokenEscape::EscapeAscii => s.push(esc::ascii(&tks)),
-                TokenEscape::EscapeUnicode => s.push(esc::unicode(&tks)?),
+        switch(lex, |lex, tks: &mut Lexer<'i, TokenEscape>| {
+            let mut s = String::new();
+
+            while let Some(t) = tks.next().transpose()? {
+                lex.bump(tks.slice().len());
+                match t {
+                    TokenEscape::Newline => Err(ErrorKind::UnexpectedNewline)?,
+                    TokenEscape::Prime => s.push('\''),
+                    TokenEscape::Quote(n) => match n {
+                        0 => return Ok(Literal::String(s)),
+                        _ => Err(ErrorKind::UnbalancedLiteralClose)?,
+                    },
+                    TokenEscape::NoEscapeUtf8 | TokenEscape::NoEscapeAscii => s.push_str(tks.slice()),
+                    TokenEscape::EscapeByte => Err(ErrorKind::InvalidAsciiEscape)?,
+                    TokenEscape::EscapeAscii => s.push(esc::ascii(tks)),
+                    TokenEscape::EscapeUnicode => s.push(esc::unicode(tks)?),
+                }
+                check_string_len(lex, s.len())?;
             }
-        }
 
-        Err(ErrorKind::UnexpectedEof)
+            Err(ErrorKind::UnexpectedEof)
+        })
     }
 
+    #[cfg(feature = "syntax-raw-strings")]
     pub(crate) fn raw_string<'i>(lex: &mut Lexer<'i, Token<'i>>, n_backtick: usize) -> LexerResult<Literal<'i>> {
         let j = lex.remainder();
-        let mut tks = switch::<_, TokenNoEscape>(lex);
-        let mut len = 0;
-
-        while let Some(t) = tks.next().transpose()? {
-            len += tks.slice().len();
-
-            if let TokenNoEscape::Quote(n) = t {
-                match n.cmp(&n_backtick) {
-                    Ordering::Less => continue,
-                    Ordering::Equal => {
-                        lex.bump(len);
-                        return Ok(Literal::Str(&j[..len - tks.slice().len()]));
+        switch(lex, |lex, tks: &mut Lexer<'i, TokenNoEscape>| {
+            let mut len = 0;
+
+            while let Some(t) = tks.next().transpose()? {
+                len += tks.slice().len();
+
+                if let TokenNoEscape::Quote(n) = t {
+                    match n.cmp(&n_backtick) {
+                        Ordering::Less => continue,
+                        Ordering::Equal => {
+                            check_string_len(lex, len - tks.slice().len())?;
+                            lex.bump(len);
+                            return Ok(Literal::Str(&j[..len - tks.slice().len()]));
+                        }
+                        Ordering::Greater => Err(ErrorKind::UnbalancedLiteralClose)?,
                     }
-                    Ordering::Greater => Err(ErrorKind::UnbalancedLiteralClose)?,
                 }
             }
+
+            Err(ErrorKind::UnexpectedEof)
+        })
+    }
+
+    /// Errors if `len` exceeds [`DeserializeConfig::max_bytes_len`](crate::DeserializeConfig::max_bytes_len).
+    fn check_bytes_len<'i, T: Logos<'i, Extras = Extras>>(lex: &Lexer<'i, T>, len: usize) -> LexerResult<()> {
+        let limit = lex.extras.max_bytes_len;
+        match len > limit {
+            true => Err(ErrorKind::ExceededBytesLimit(limit)),
+            false => Ok(()),
         }
+    }
 
-        Err(ErrorKind::UnexpectedEof)
+    /// Errors if `len` exceeds [`DeserializeConfig::max_string_len`](crate::DeserializeConfig::max_string_len).
+    fn check_string_len<'i, T: Logos<'i, Extras = Extras>>(lex: &Lexer<'i, T>, len: usize) -> LexerResult<()> {
+        let limit = lex.extras.max_string_len;
+        match len > limit {
+            true => Err(ErrorKind::ExceededStringLimit(limit)),
+            false => Ok(()),
+        }
     }
 
     // IMPROVE: Is it possible to borrow a "normal bytes without escape"?
     pub(crate) fn bytes<'i>(lex: &mut Lexer<'i, Token<'i>>) -> LexerResult<Literal<'i>> {
-        let mut tks = switch::<_, TokenEscape>(lex);
-        let mut buf = ByteBuf::new();
-
-        while let Some(t) = tks.next().transpose()? {
-            lex.bump(tks.slice().len());
-            match t {
-                TokenEscape::Newline => Err(ErrorKind::UnexpectedNewline)?,
-                TokenEscape::Prime => buf.push(b'\''),
-                TokenEscape::Quote(n) => match n {
-                    0 => return Ok(Literal::ByteBuf(buf)),
-                    _ => Err(ErrorKind::UnbalancedLiteralClose)?,
-                },
-                TokenEscape::NoEscapeUtf8 => Err(ErrorKind::UnexpectedNonAscii)?,
-                TokenEscape::NoEscapeAscii => buf.extend_from_slice(tks.slice().as_bytes()),
-                TokenEscape::EscapeByte => buf.push(esc::byte(&tks)),
-                TokenEscape::EscapeAscii => buf.push(esc::ascii(&tks) as u8),
-                TokenEscape::EscapeUnicode => Err(ErrorKind::UnexpectedUnicodeEscape)?,
+        switch(lex, |lex, tks: &mut Lexer<'i, TokenEscape>| {
+            let mut buf = ByteBuf::new();
+
+            while let Some(t) = tks.next().transpose()? {
+                lex.bump(tks.slice().len());
+                match t {
+                    TokenEscape::Newline => Err(ErrorKind::UnexpectedNewline)?,
+                    TokenEscape::Prime => buf.push(b'\''),
+                    TokenEscape::Quote(n) => match n {
+                        0 => return Ok(Literal::ByteBuf(buf)),
+                        _ => Err(ErrorKind::UnbalancedLiteralClose)?,
+                    },
+                    TokenEscape::NoEscapeUtf8 => Err(ErrorKind::UnexpectedNonAscii)?,
+                    TokenEscape::NoEscapeAscii => buf.extend_from_slice(tks.slice().as_bytes()),
+                    TokenEscape::EscapeByte => buf.push(esc::byte(tks)),
+                    TokenEscape::EscapeAscii => buf.push(esc::ascii(tks) as u8),
+                    TokenEscape::EscapeUnicode => Err(ErrorKind::UnexpectedUnicodeEscape)?,
+                }
+                check_bytes_len(lex, buf.len())?;
             }
-        }
 
-        Err(ErrorKind::UnexpectedEof)
+            Err(ErrorKind::UnexpectedEof)
+        })
     }
 
+    #[cfg(feature = "syntax-raw-strings")]
     pub(crate) fn raw_bytes<'i>(lex: &mut Lexer<'i, Token<'i>>, n_backtick: usize) -> LexerResult<Literal<'i>> {
         let j = lex.remainder();
-        let mut tks = switch::<_, TokenNoEscape>(lex);
-        let mut len = 0;
-
-        while let Some(t) = tks.next().transpose()? {
-            len += tks.slice().len();
-
-            match t {
-                TokenNoEscape::Quote(n) => match n.cmp(&n_backtick) {
-                    Ordering::Less => continue,
-                    Ordering::Equal => {
-                        lex.bump(len);
-                        return Ok(Literal::Bytes(j[..len - tks.slice().len()].as_bytes()));
-                    }
-                    Ordering::Greater => Err(ErrorKind::UnbalancedLiteralClose)?,
-                },
-                TokenNoEscape::NoEscapeUtf8 => Err(ErrorKind::UnexpectedNonAscii)?,
-                TokenNoEscape::NoEscapeAscii => continue,
-                TokenNoEscape::UNINHABITED => unreachable!(),
+        switch(lex, |lex, tks: &mut Lexer<'i, TokenNoEscape>| {
+            let mut len = 0;
+
+            while let Some(t) = tks.next().transpose()? {
+                len += tks.slice().len();
+
+                match t {
+                    TokenNoEscape::Quote(n) => match n.cmp(&n_backtick) {
+                        Ordering::Less => continue,
+                        Ordering::Equal => {
+                            check_bytes_len(lex, len - tks.slice().len())?;
+                            lex.bump(len);
+                            return Ok(Literal::Bytes(&j.as_bytes()[..len - tks.slice().len()]));
+                        }
+                        Ordering::Greater => Err(ErrorKind::UnbalancedLiteralClose)?,
+                    },
+                    TokenNoEscape::NoEscapeUtf8 => Err(ErrorKind::UnexpectedNonAscii)?,
+                    TokenNoEscape::NoEscapeAscii => continue,
+                    TokenNoEscape::UNINHABITED => unreachable!(),
+                }
             }
-        }
 
-        Err(ErrorKind::UnexpectedEof)
+            Err(ErrorKind::UnexpectedEof)
+        })
     }
 
+    #[cfg(feature = "syntax-basexx")]
     pub(crate) fn bytes_encoding<'i>(lex: &mut Lexer<'i, Token<'i>>, flavor: BaseXX) -> LexerResult<Literal<'i>> {
         let j = lex.remainder();
         match j.find('"') {
             Some(n) => {
                 lex.bump(n + 1);
-                let content = j[..n].as_bytes();
+                let content = &j.as_bytes()[..n];
+                // Long encoded bytes may be wrapped across lines, see `SerializeConfig::bytes_wrap_width`.
+                let compact: Vec<u8>;
+                let content = match content.iter().any(u8::is_ascii_whitespace) {
+                    true => {
+                        compact = content.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect();
+                        &compact
+                    }
+                    false => content,
+                };
                 let base_err = |e| ErrorKind::InvalidBytesEncoding(e);
-                Ok(Literal::ByteBuf(match flavor {
-                    BaseXX::Base16 => HEXUPPER_PERMISSIVE.decode(content).map_err(base_err)?,
-                    BaseXX::Base32 => BASE32_NOPAD.decode(content).map_err(base_err)?,
-                    BaseXX::Base64 => BASE64URL_NOPAD.decode(content).map_err(base_err)?,
-                }))
+                let encoding = match flavor {
+                    BaseXX::Base16 => &HEXUPPER_PERMISSIVE,
+                    BaseXX::Base32 => &BASE32_NOPAD,
+                    BaseXX::Base64 => &BASE64URL_NOPAD,
+                };
+                check_bytes_len(lex, encoding.decode_len(content.len()).map_err(base_err)?)?;
+                Ok(Literal::ByteBuf(encoding.decode(content).map_err(base_err)?))
             }
             None => Err(ErrorKind::UnexpectedEof)?,
         }
     }
 
+    /// Each line is read by these rules:
+    ///
+    /// - Whitespace before the start sign is indentation of the document, and ignored.
+    /// - One space after the start sign separates it from the content, and is removed.
+    ///   Anything else is content, including more spaces, a tab instead of the space, and start signs.
+    /// - Trailing whitespace, including `\r` of CRLF, is removed.
+    ///   Unless [`preserve_paragraph_indent`](crate::DeserializeConfig::preserve_paragraph_indent)
+    ///   on `` ` `` lines, where only `\r` is.
+    #[cfg(feature = "syntax-paragraph")]
     pub(crate) fn paragraph<'i>(lex: &mut Lexer<'i, Token<'i>>) -> LexerResult<Literal<'i>> {
-        fn trim(mut s: &str) -> &str {
-            s = &s.trim()[1..];
+        fn trim(s: &str, verbatim: bool) -> &str {
+            let s = &s.trim_start()[1..];
+            let s = match verbatim {
+                true => s.strip_suffix('\r').unwrap_or(s),
+                false => s.trim_end(),
+            };
             s.strip_prefix('\x20').unwrap_or(s)
         }
 
-        let first = trim(lex.slice());
-        let mut tks = switch::<_, TokenParagraph>(lex);
-        let mut newlined = false;
-
-        Ok(match tks.next().transpose()? {
-            Some(TokenParagraph::Leave) | None => Literal::Str(first),
-            Some(mut t) => {
-                let mut s = String::from(first);
-                loop {
-                    match t {
-                        TokenParagraph::Leave => break,
-                        t => {
-                            lex.extras.borrow_mut().line += 1;
-                            lex.bump(tks.slice().len());
-                            let line = trim(tks.slice());
-
-                            match t {
-                                TokenParagraph::Leave => unreachable!(),
-                                TokenParagraph::AsIsNewLine => {
-                                    newlined = line.is_empty();
-                                    s.push('\n');
-                                    s.push_str(line);
-                                }
-                                TokenParagraph::JoinedLine | TokenParagraph::SpaceJoinedLine => match line.is_empty() {
-                                    true => {
-                                        if !newlined {
-                                            newlined = true;
-                                            s.push('\n');
-                                        }
+        let verbatim = lex.extras.preserve_paragraph_indent;
+        let first = trim(lex.slice(), false);
+        check_string_len(lex, first.len())?;
+        switch(lex, |lex, tks: &mut Lexer<'i, TokenParagraph>| {
+            let mut newlined = false;
+            let mut lines = 0;
+
+            Ok(match tks.next().transpose()? {
+                Some(TokenParagraph::Leave) | None => Literal::Str(first),
+                Some(mut t) => {
+                    let mut s = String::from(first);
+                    loop {
+                        match t {
+                            TokenParagraph::Leave => break,
+                            t => {
+                                tks.extras.line += 1;
+                                lines += 1;
+                                lex.bump(tks.slice().len());
+                                let line = trim(tks.slice(), verbatim && matches!(t, TokenParagraph::AsIsNewLine));
+
+                                match t {
+                                    TokenParagraph::Leave => unreachable!(),
+                                    TokenParagraph::AsIsNewLine => {
+                                        newlined = line.is_empty();
+                                        s.push('\n');
+                                        s.push_str(line);
                                     }
-                                    false => {
-                                        if let TokenParagraph::SpaceJoinedLine = t {
-                                            if !newlined {
-                                                s.push('\x20');
+                                    TokenParagraph::JoinedLine | TokenParagraph::SpaceJoinedLine => {
+                                        match line.is_empty() {
+                                            true => {
+                                                if !newlined {
+                                                    newlined = true;
+                                                    s.push('\n');
+                                                }
+                                            }
+                                            false => {
+                                                if let TokenParagraph::SpaceJoinedLine = t {
+                                                    if !newlined {
+                                                        s.push('\x20');
+                                                    }
+                                                }
+                                                newlined = false;
+                                                s.push_str(line);
                                             }
                                         }
-                                        newlined = false;
-                                        s.push_str(line);
                                     }
-                                },
+                                }
+                                // Located at the first line.
+                                if let Err(ek) = check_string_len(lex, s.len()) {
+                                    tks.extras.line -= lines;
+                                    return Err(ek);
+                                }
                             }
                         }
-                    }
 
-                    match tks.next().transpose()? {
-                        Some(t2) => t = t2,
-                        None => break,
+                        match tks.next().transpose()? {
+                            Some(t2) => t = t2,
+                            None => break,
+                        }
                     }
+                    Literal::String(s)
                 }
-                Literal::String(s)
-            }
+            })
         })
     }
 }
@@ -37,16 +37,48 @@
 
 #![doc = include_str!("../CRATES.IO-README.md")]
 
+mod const_str;
 mod lexer;
 
+pub mod adapters;
+pub mod build;
+pub mod bytes;
+#[cfg(feature = "clap")]
+pub mod cli;
 pub mod de;
+pub mod debug;
+#[cfg(feature = "erased")]
+pub mod erased;
 pub mod error;
+pub mod fmt;
+pub mod grammar;
+#[cfg(feature = "schemars")]
+pub mod ide;
+pub mod import;
+pub mod limits;
+#[cfg(feature = "schemars")]
+pub mod schema;
 pub mod ser;
+pub mod settings;
 pub mod value;
+#[cfg(feature = "notify")]
+pub mod watch;
 
-pub use de::{from_str, Deserializer};
-pub use error::{Error, ErrorKind, Result};
-pub use ser::{to_string, to_string_pretty, to_writer, to_writer_pretty, BytesFlavor, SerializeConfig, Serializer};
-pub use value::{Number, Value};
+pub use de::{
+    from_bytes, from_bytes_lossy, from_reader, from_reader_lossy, from_slice_lossy_encoding, from_str,
+    from_str_optional, from_str_or_default, is_empty_document, DeserializeConfig, Deserializer, VariantMatching,
+};
+pub use debug::extract_strings;
+#[cfg(feature = "erased")]
+pub use erased::{ErasedDeserializer, ErasedSerializer};
+pub use error::{Error, ErrorContext, ErrorKind, IoError, Result, Warning, WarningKind};
+pub use ser::{
+    to_string, to_string_pretty, to_string_with, to_string_with_source_map, to_vec, to_vec_pretty, to_writer,
+    to_writer_pretty, to_writer_with, BytesFlavor, EnumPaths, Expand, FieldPath, Newline, PathSegment, Radix,
+    SerializeConfig, SerializeConfigBuilder, Serializer, SourceMap,
+};
+pub use value::{semantically_equal, Number, Value};
+#[cfg(feature = "notify")]
+pub use watch::watch;
 
-const RECURSION_LIMIT: usize = 128;
+use limits::{INDENT_WIDTH, RECURSION_LIMIT, WRITE_BUFFER_SIZE};
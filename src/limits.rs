@@ -0,0 +1,24 @@
+//! Fixed limits and sizes of this crate, to reason about which documents are accepted.
+
+/// The default maximum nesting depth of values, both when serializing and deserializing.
+///
+/// Exceeding it raises [`ErrorKind::ExceededRecursionLimit`](crate::ErrorKind::ExceededRecursionLimit),
+/// another limit can be given with [`SerializeConfig::recursion_limit`](crate::SerializeConfig::recursion_limit)
+/// and [`DeserializeConfig::recursion_limit`](crate::DeserializeConfig::recursion_limit).
+pub const RECURSION_LIMIT: usize = 128;
+
+/// The maximum number of contexts of an error, the innermost ones, see [`Error::context`](crate::Error::context).
+pub const ERROR_CONTEXT_DEPTH: usize = 8;
+
+/// The number of tokens the deserializer looks ahead.
+///
+/// The whole source is borrowed as a `str`, so a single token, e.g. a long number literal, is never truncated.
+pub const LOOKAHEAD_TOKENS: usize = 1;
+
+/// The default number of spaces per indentation level in pretty outputs,
+/// see [`SerializeConfig::indent_width`](crate::SerializeConfig::indent_width).
+pub const INDENT_WIDTH: usize = 4;
+
+/// The number of bytes buffered by [`to_writer`](crate::to_writer) and alike before writing them into the writer,
+/// see [`Serializer::with_buffer`](crate::Serializer::with_buffer).
+pub const WRITE_BUFFER_SIZE: usize = 8 * 1024;
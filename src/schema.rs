@@ -0,0 +1,128 @@
+//! JSON Schemas of the documents a Rust type accepts, for editors to validate and complete KEON files.
+//!
+//! The schema describes the document as if it were read as a [`Value`](crate::Value) and converted to JSON:
+//! `?` and `()` are `null`, `%` is transparent, chars are strings of one character, bytes are arrays of integers,
+//! and variants are strings for unit variants or single-entry objects `{ "Variant": ... }` otherwise.
+//!
+//! Where JSON loses a distinction that KEON keeps, [`schema_for`] adds keywords that tools aware of KEON can use:
+//!
+//! | Keyword           | On                                    | Written as              |
+//! | ----------------- | ------------------------------------- | ----------------------- |
+//! | `x-keon-option`   | schemas allowing `null` besides more  | `?` or `? value`        |
+//! | `x-keon-char`     | strings of exactly one character      | `'c'`                   |
+//! | `x-keon-bytes`    | schemas from [`bytes`]                | `b"..."`                |
+//! | `x-keon-variants` | enums, the paths of all variants      | `Enum::Variant`         |
+//!
+//! Requires the feature `schemars`, types derive [`schemars::JsonSchema`] besides serde's traits.
+
+use schemars::{
+    generate::SchemaSettings,
+    transform::{transform_subschemas, Transform},
+    JsonSchema, Schema, SchemaGenerator,
+};
+
+/// The schema of the documents `T` deserializes from, in JSON Schema draft 2020-12
+/// with the conventions of KEON applied, see the [module documentation](self).
+pub fn schema_for<T: ?Sized + JsonSchema>() -> Schema {
+    let mut schema = SchemaSettings::draft2020_12()
+        .for_deserialize()
+        .with_transform(KeonConventions)
+        .into_generator()
+        .into_root_schema_for::<T>();
+
+    if let Some(name) = schema.get("title").and_then(|title| title.as_str()).map(str::to_owned) {
+        annotate_variants(&name, &mut schema);
+    }
+    if let Some(defs) = schema.get_mut("$defs").and_then(|defs| defs.as_object_mut()) {
+        for (name, def) in defs {
+            if let Ok(def) = <&mut Schema>::try_from(def) {
+                annotate_variants(name, def);
+            }
+        }
+    }
+
+    schema
+}
+
+/// Marks options, chars and their subschemas, for use with other [`SchemaSettings`].
+///
+/// Variant paths need the name of the enum, which only [`schema_for`] knows.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeonConventions;
+
+impl Transform for KeonConventions {
+    fn transform(&mut self, schema: &mut Schema) {
+        if is_option(schema) {
+            schema.insert("x-keon-option".into(), true.into());
+        }
+        if is_char(schema) {
+            schema.insert("x-keon-char".into(), true.into());
+        }
+        transform_subschemas(self, schema);
+    }
+}
+
+/// The schema of bytes, used as `#[schemars(schema_with = "keon::schema::bytes")]`
+/// on fields that are serialized with `serde_bytes` or similar.
+pub fn bytes(_gen: &mut SchemaGenerator) -> Schema {
+    schemars::json_schema!({
+        "type": "array",
+        "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+        "x-keon-bytes": true,
+    })
+}
+
+/// `"type": ["integer", "null"]` or `"anyOf": [..., { "type": "null" }]`, but not `"type": "null"` alone.
+fn is_option(schema: &Schema) -> bool {
+    let by_type = match schema.get("type").and_then(|ty| ty.as_array()) {
+        Some(types) => types.len() > 1 && types.iter().any(|ty| ty == "null"),
+        None => false,
+    };
+    let by_any_of = match schema.get("anyOf").and_then(|any_of| any_of.as_array()) {
+        Some(schemas) => schemas.len() > 1 && schemas.iter().any(|s| s.get("type").is_some_and(|ty| ty == "null")),
+        None => false,
+    };
+    by_type || by_any_of
+}
+
+fn is_char(schema: &Schema) -> bool {
+    schema.get("type").is_some_and(|ty| ty == "string")
+        && schema.get("minLength").is_some_and(|len| len == 1)
+        && schema.get("maxLength").is_some_and(|len| len == 1)
+}
+
+/// Adds `x-keon-variants` if the schema is an externally tagged enum, that is, all alternatives
+/// are either string constants or objects with a single required property.
+fn annotate_variants(name: &str, schema: &mut Schema) {
+    let variants = match schema.get("oneOf").and_then(|one_of| one_of.as_array()) {
+        Some(alternatives) => alternatives.iter().try_fold(Vec::new(), |mut variants, alt| {
+            if let Some(names) = alt.get("enum").and_then(|names| names.as_array()) {
+                for variant in names {
+                    variants.push(variant.as_str()?);
+                }
+            } else if let Some(variant) = alt.get("const") {
+                variants.push(variant.as_str()?);
+            } else {
+                match alt.get("required").and_then(|required| required.as_array())?.as_slice() {
+                    [variant] if alt.get("additionalProperties").is_some_and(|add| add == false) => {
+                        variants.push(variant.as_str()?)
+                    }
+                    _ => return None,
+                }
+            }
+            Some(variants)
+        }),
+        None => schema
+            .get("enum")
+            .and_then(|names| names.as_array())
+            .and_then(|names| names.iter().map(|variant| variant.as_str()).collect()),
+    };
+
+    if let Some(variants) = variants.filter(|variants| !variants.is_empty()) {
+        let paths: Vec<String> = variants
+            .into_iter()
+            .map(|variant| format!("{}::{}", name, variant))
+            .collect();
+        schema.insert("x-keon-variants".into(), paths.into());
+    }
+}
@@ -1,39 +1,81 @@
 use super::*;
+use crate::{fmt::Form, lexer::is_ident};
+#[cfg(feature = "syntax-basexx")]
 use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD, HEXUPPER_PERMISSIVE};
 use lexical_core::BUFFER_SIZE;
 use serde::{
     ser::{
-        SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple, SerializeTupleStruct,
-        SerializeTupleVariant,
+        Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+        SerializeTupleStruct, SerializeTupleVariant,
     },
     Serialize,
 };
-use std::io::Write;
+use std::{collections::HashMap, io::Write, ops::Range};
 
 /// Conveniently serialize `value` to a String in the minimal way.
 pub fn to_string<T: ?Sized + Serialize>(value: &T) -> Result<String> {
-    let mut buf = Vec::new();
-    to_writer(&mut buf, value)?;
+    let buf = to_vec(value)?;
     unsafe { Ok(String::from_utf8_unchecked(buf)) }
 }
 
 /// Conveniently serialize `value` to a String in a pretty way.
 pub fn to_string_pretty<T: ?Sized + Serialize>(value: &T) -> Result<String> {
+    let buf = to_vec_pretty(value)?;
+    unsafe { Ok(String::from_utf8_unchecked(buf)) }
+}
+
+/// Conveniently serialize `value` to a String with a custom config, see [`SerializeConfig::builder`].
+pub fn to_string_with<T: ?Sized + Serialize>(value: &T, cfg: &SerializeConfig) -> Result<String> {
     let mut buf = Vec::new();
-    to_writer_pretty(&mut buf, value)?;
+    to_writer_with(&mut buf, value, cfg)?;
     unsafe { Ok(String::from_utf8_unchecked(buf)) }
 }
 
+/// Conveniently serialize `value` to a String with a custom config, and where its values were written,
+/// see [`Serializer::with_source_map`].
+pub fn to_string_with_source_map<T: ?Sized + Serialize>(
+    value: &T,
+    cfg: &SerializeConfig,
+) -> Result<(String, SourceMap)> {
+    let mut ser = Serializer::new(Vec::new(), *cfg).with_source_map();
+    value.serialize(&mut ser)?;
+    let source_map = ser.spans.take().unwrap_or_default();
+    unsafe { Ok((String::from_utf8_unchecked(ser.dst.dst), source_map)) }
+}
+
+/// Conveniently serialize `value` to UTF-8 bytes in the minimal way.
+pub fn to_vec<T: ?Sized + Serialize>(value: &T) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    to_writer(&mut buf, value)?;
+    Ok(buf)
+}
+
+/// Conveniently serialize `value` to UTF-8 bytes in a pretty way.
+pub fn to_vec_pretty<T: ?Sized + Serialize>(value: &T) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    to_writer_pretty(&mut buf, value)?;
+    Ok(buf)
+}
+
 /// Conveniently serialize `value` into `writer` in the minimal way.
 pub fn to_writer<W: Write, T: ?Sized + Serialize>(writer: W, value: &T) -> Result<()> {
-    let mut ser = Serializer::new(writer, SerializeConfig::minimal());
-    value.serialize(&mut ser)
+    let mut ser = Serializer::new(writer, SerializeConfig::minimal()).with_buffer(WRITE_BUFFER_SIZE);
+    value.serialize(&mut ser)?;
+    ser.into_inner().map(drop)
 }
 
 /// Conveniently serialize `value` into `writer` in a pretty way.
 pub fn to_writer_pretty<W: Write, T: ?Sized + Serialize>(writer: W, value: &T) -> Result<()> {
-    let mut ser = Serializer::new(writer, SerializeConfig::comfort());
-    value.serialize(&mut ser)
+    let mut ser = Serializer::new(writer, SerializeConfig::comfort()).with_buffer(WRITE_BUFFER_SIZE);
+    value.serialize(&mut ser)?;
+    ser.into_inner().map(drop)
+}
+
+/// Conveniently serialize `value` into `writer` with a custom config, see [`SerializeConfig::builder`].
+pub fn to_writer_with<W: Write, T: ?Sized + Serialize>(writer: W, value: &T, cfg: &SerializeConfig) -> Result<()> {
+    let mut ser = Serializer::new(writer, *cfg).with_buffer(WRITE_BUFFER_SIZE);
+    value.serialize(&mut ser)?;
+    ser.into_inner().map(drop)
 }
 
 //==================================================================================================
@@ -41,34 +83,480 @@ pub fn to_writer_pretty<W: Write, T: ?Sized + Serialize>(writer: W, value: &T) -
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy)]
 pub struct SerializeConfig {
-    pub minimize_after_depth: u8,
+    /// At which depths values are pretty, minimized elsewhere.
+    pub expand: Expand,
     pub bytes_flavor: BytesFlavor,
+    /// Writes unit variants as their indices (in declaration order) where minimized,
+    /// read them back with [`DeserializeConfig::variant_indices`](crate::DeserializeConfig::variant_indices).
+    ///
+    /// Enums with other kinds of variants are not told apart, their unit variants are written as indices as well,
+    /// which count all variants, e.g. `Dot` is `1` in `enum Shape { Circle(f64), Dot }`.
+    pub unit_variant_indices: bool,
+    /// Omits `=> ()` of map entries, e.g. `{ Apple, Banana }`,
+    /// read them back with [`DeserializeConfig::set_sugar`](crate::DeserializeConfig::set_sugar).
+    ///
+    /// Sets are sequences to Serde, which are written as such, unless marked with [`adapters::set`](crate::adapters::set).
+    pub set_sugar: bool,
+    /// Writes the entries of maps in order of their keys as written, byte by byte,
+    /// e.g. for a `HashMap` to be written the same each time. Entries with equal keys keep their order.
+    pub sort_keys: bool,
+    /// Omits fields of structs and struct variants whose values are `None`, rather than writing `field: ?`,
+    /// e.g. for configs with many options rarely set. Serde reads missing fields of `Option`s back as `None`.
+    pub skip_none_fields: bool,
+    /// Writes keys of maps that are identifiers like fields, e.g. `{ alpha: 1, "two words" => 2 }`
+    /// rather than `{ "alpha" => 1, "two words" => 2 }`, for `HashMap<String, T>` that read like structs:
+    /// strings that are identifiers, and unit variants without their enum paths, e.g. `{ Up: 'W' }`.
+    /// Keys are read back as strings or variants, as expected, either way.
+    pub ident_keys: bool,
+    /// Writes fields whose values are structs with a single field as dotted keys where pretty,
+    /// e.g. `server.tls.enabled: true`, read them back with
+    /// [`DeserializeConfig::dotted_keys`](crate::DeserializeConfig::dotted_keys).
+    pub dotted_keys: bool,
+    /// Appends the index of variants (in declaration order) as a comment where pretty, e.g. `Left /* =2 */`,
+    /// which is ignored when read back.
+    pub variant_index_comments: bool,
+    /// Writes a divider comment before fields of the outermost struct whose values span more than this many lines
+    /// where pretty, e.g. `// ---- inventory ----`, for long configs to be navigable. It's ignored when read back.
+    pub section_dividers: Option<usize>,
+    /// Writes consecutive fields of structs whose values are scalars, e.g. numbers, booleans, short strings
+    /// or unit variants, on the same line where pretty as long as it stays within this many columns,
+    /// e.g. `x: 1, y: 2, visible: true,`. Fields whose values have elements are on lines of their own.
+    pub pack_scalar_fields: Option<usize>,
+    /// Writes this text as a block comment before the outermost value, e.g. the name and version of the tool
+    /// that generated the file, as line comments if the text would end a block comment early.
+    /// It's ignored when read back.
+    ///
+    /// Text made at runtime, e.g. with a timestamp, is given with [`Serializer::with_header`] instead.
+    pub header: Option<&'static str>,
+    /// Writes strings containing newlines or longer than this many characters as paragraphs where pretty,
+    /// wrapped at spaces, e.g. `| To be, or not` and `| to be.` on the next line.
+    /// With `Some(usize::MAX)`, only strings containing newlines are paragraphs, split at them only.
+    ///
+    /// The lines are aligned under the first one. As a paragraph runs until the end of its last line,
+    /// the comma after it starts the next line, e.g. `, next: 1`.
+    ///
+    /// Strings a paragraph cannot represent, e.g. with control characters or trailing spaces on a line,
+    /// and map keys are quoted as usual.
+    #[cfg(feature = "syntax-paragraph")]
+    pub wrap_width: Option<usize>,
+    /// Writes strings as raw strings where they are no longer than escaped, e.g. `` `"C:\Users\Alex"` ``
+    /// instead of `"C:\\Users\\Alex"`, with as many backticks as needed for the string not to end early.
+    ///
+    /// Strings with control characters are quoted as usual.
+    #[cfg(feature = "syntax-raw-strings")]
+    pub raw_strings: bool,
+    /// Splits bytes encoded in base16, base32 or base64 into lines of this many characters where pretty,
+    /// continued one level deeper, e.g. for megabyte blobs. Whitespace between them is ignored when read back.
+    #[cfg(feature = "syntax-basexx")]
+    pub bytes_wrap_width: Option<usize>,
+    /// Escapes all non-ASCII characters of strings and chars, e.g. `"caf\u{e9}"`,
+    /// for systems that mangle UTF-8.
+    ///
+    /// Strings that are not ASCII are never paragraphs or raw strings then. Identifiers are written as they are.
+    pub ascii_only: bool,
+    /// Escapes characters of strings and chars that are invisible or look like others,
+    /// e.g. `"admin\u{200b}"`, so that crafted values cannot spoof reviewed documents:
+    /// bidirectional controls, zero-width and other format characters, and whitespace other than ASCII.
+    ///
+    /// Strings with them are never paragraphs or raw strings then. Identifiers are written as they are.
+    pub escape_invisible: bool,
+    /// Writes values on a single line where pretty if they fit within this many columns,
+    /// e.g. `pos: (1, 2)` or `tags: ["a", "b"]`, otherwise one element per line as usual.
+    ///
+    /// The outermost value is always written one element per line, and paragraphs never fit.
+    ///
+    /// A value that does not fit is serialized once more after the line is full,
+    /// so [`Serialize`] implementations with side effects run their first part twice.
+    pub line_width: Option<usize>,
+    /// The number of spaces per indentation level where pretty,
+    /// [`INDENT_WIDTH`](crate::limits::INDENT_WIDTH) by default.
+    pub indent_width: usize,
+    /// Writes struct names where pretty, e.g. `(Save) { .. }`, otherwise `{ .. }`.
+    /// Names that are not identifiers are never written.
+    pub struct_names: bool,
+    /// Where enum names are written before variants, e.g. `Side::Left` instead of `Left`.
+    /// Names that are not identifiers are never written.
+    pub enum_paths: EnumPaths,
+    /// Writes a comma after the last element where pretty.
+    ///
+    /// Tuples with a single element keep it, e.g. `(1,)`, to stay tuples when read back.
+    pub trailing_commas: bool,
+    /// Writes all floats in hexadecimal, e.g. `0x1.8p3`, like [`adapters::hex_float`](crate::adapters::hex_float).
+    pub hex_floats: bool,
+    /// Writes NaNs other than [`f64::NAN`] and [`f32::NAN`] with their bits, e.g. `NaN:0x7ff8000000000001`
+    /// or `NaN:0xffc00000` for a negative `f32`, which are read back bit for bit.
+    ///
+    /// `f32` NaNs are widened into `f64` and narrowed back on the way, which keeps the payloads of quiet NaNs
+    /// on common platforms, but may quiet signaling ones. The bits of NaNs computed at runtime, e.g. by `0.0 / 0.0`,
+    /// differ between platforms, and so does the output.
+    pub nan_payloads: bool,
+    /// The radix of unsigned integers, e.g. `0xff` or `0b1010` for bitmasks. Indices of variants stay decimal.
+    pub unsigned_radix: Radix,
+    /// Separates groups of this many digits of decimal integers with underscores,
+    /// e.g. `18_446_744_073_709_551_615` with 3. Integers with no more digits than that are written as they are.
+    pub digit_separators: Option<usize>,
+    /// Rounds floats to at most this many significant digits, e.g. `0.1 + 0.2` is written as `0.3` with 15,
+    /// rounded to nearest from the exact value, ties to even. Hexadecimal floats are always exact.
+    ///
+    /// Floats are otherwise written with as few digits as read back the same, up to 17 for `f64` and 9 for `f32`.
+    pub significant_digits: Option<usize>,
+    /// Writes values marked with [`adapters::shared`](crate::adapters::shared) once per pointer and place,
+    /// e.g. for asset exports with thousands of entries sharing the same `Arc`, copying the output afterwards.
+    ///
+    /// Values are identified by their address, so they must not change while being serialized.
+    pub memoize_shared: bool,
+    /// The maximum nesting depth of values, [`RECURSION_LIMIT`](crate::limits::RECURSION_LIMIT) by default,
+    /// e.g. raised for deliberately deep trees, whose serialization needs a large enough stack.
+    pub recursion_limit: usize,
+    /// The line ending of pretty outputs.
+    pub newline: Newline,
 }
 
 impl SerializeConfig {
     pub const fn minimal() -> Self {
         Self {
-            minimize_after_depth: 0,
+            expand: Expand::UntilDepth(0),
+            #[cfg(feature = "syntax-basexx")]
             bytes_flavor: BytesFlavor::Base64,
+            #[cfg(not(feature = "syntax-basexx"))]
+            bytes_flavor: BytesFlavor::Normal,
+            unit_variant_indices: false,
+            set_sugar: false,
+            sort_keys: false,
+            skip_none_fields: false,
+            ident_keys: false,
+            dotted_keys: false,
+            variant_index_comments: false,
+            section_dividers: None,
+            pack_scalar_fields: None,
+            header: None,
+            #[cfg(feature = "syntax-paragraph")]
+            wrap_width: None,
+            #[cfg(feature = "syntax-raw-strings")]
+            raw_strings: false,
+            #[cfg(feature = "syntax-basexx")]
+            bytes_wrap_width: None,
+            ascii_only: false,
+            escape_invisible: false,
+            line_width: None,
+            indent_width: INDENT_WIDTH,
+            struct_names: true,
+            enum_paths: EnumPaths::PrettyOnly,
+            trailing_commas: true,
+            hex_floats: false,
+            nan_payloads: false,
+            unsigned_radix: Radix::Decimal,
+            digit_separators: None,
+            significant_digits: None,
+            memoize_shared: false,
+            recursion_limit: RECURSION_LIMIT,
+            newline: Newline::Lf,
         }
     }
 
     pub const fn comfort() -> Self {
         Self {
-            minimize_after_depth: 6,
+            expand: Expand::UntilDepth(6),
+            bytes_flavor: BytesFlavor::Normal,
+            unit_variant_indices: false,
+            set_sugar: false,
+            sort_keys: false,
+            skip_none_fields: false,
+            ident_keys: false,
+            dotted_keys: false,
+            variant_index_comments: false,
+            section_dividers: None,
+            pack_scalar_fields: None,
+            header: None,
+            #[cfg(feature = "syntax-paragraph")]
+            wrap_width: None,
+            #[cfg(feature = "syntax-raw-strings")]
+            raw_strings: false,
+            #[cfg(feature = "syntax-basexx")]
+            bytes_wrap_width: None,
+            ascii_only: false,
+            escape_invisible: false,
+            line_width: None,
+            indent_width: INDENT_WIDTH,
+            struct_names: true,
+            enum_paths: EnumPaths::PrettyOnly,
+            trailing_commas: true,
+            hex_floats: false,
+            nan_payloads: false,
+            unsigned_radix: Radix::Decimal,
+            digit_separators: None,
+            significant_digits: None,
+            memoize_shared: false,
+            recursion_limit: RECURSION_LIMIT,
+            newline: Newline::Lf,
+        }
+    }
+
+    /// The minimal output that is the same for the same data, e.g. to hash or sign documents,
+    /// whichever features are enabled: maps sorted by their keys, floats in hexadecimal,
+    /// bytes as escaped strings, no struct names or enum paths, and no options of the syntax.
+    ///
+    /// The output doesn't depend on the platform either: NaNs are written as `NaN` whatever their bits,
+    /// which differ between platforms when computed, and newlines within strings are escaped.
+    ///
+    /// Values equal by [`PartialEq`] may still be written differently where they differ as data:
+    /// - Sequences keep their order, which for a `HashSet` varies from one run to another,
+    ///   write them with [`adapters::sorted`](crate::adapters::sorted).
+    /// - `-0.0` is written as `-0x0p0` and `0.0` as `0x0p0`, as they are read back.
+    ///
+    /// Changing the output of equal values is a breaking change of this crate.
+    pub const fn canonical() -> Self {
+        Self {
+            expand: Expand::UntilDepth(0),
             bytes_flavor: BytesFlavor::Normal,
+            unit_variant_indices: false,
+            set_sugar: false,
+            sort_keys: true,
+            skip_none_fields: false,
+            ident_keys: false,
+            dotted_keys: false,
+            variant_index_comments: false,
+            section_dividers: None,
+            pack_scalar_fields: None,
+            header: None,
+            #[cfg(feature = "syntax-paragraph")]
+            wrap_width: None,
+            #[cfg(feature = "syntax-raw-strings")]
+            raw_strings: false,
+            #[cfg(feature = "syntax-basexx")]
+            bytes_wrap_width: None,
+            ascii_only: false,
+            escape_invisible: false,
+            line_width: None,
+            indent_width: INDENT_WIDTH,
+            struct_names: false,
+            enum_paths: EnumPaths::Never,
+            trailing_commas: false,
+            hex_floats: true,
+            nan_payloads: false,
+            unsigned_radix: Radix::Decimal,
+            digit_separators: None,
+            significant_digits: None,
+            memoize_shared: false,
+            recursion_limit: RECURSION_LIMIT,
+            newline: Newline::Lf,
         }
     }
+
+    /// Starts from [`comfort`](Self::comfort), e.g. `SerializeConfig::builder().indent_width(2).build()`.
+    pub const fn builder() -> SerializeConfigBuilder {
+        SerializeConfigBuilder(Self::comfort())
+    }
 }
 
+/// Builds a [`SerializeConfig`] fluently, see its fields for what each option does.
+#[derive(Debug, Clone, Copy)]
+pub struct SerializeConfigBuilder(SerializeConfig);
+
+impl SerializeConfigBuilder {
+    pub const fn expand(mut self, expand: Expand) -> Self {
+        self.0.expand = expand;
+        self
+    }
+    pub const fn bytes_flavor(mut self, flavor: BytesFlavor) -> Self {
+        self.0.bytes_flavor = flavor;
+        self
+    }
+    pub const fn unit_variant_indices(mut self, enable: bool) -> Self {
+        self.0.unit_variant_indices = enable;
+        self
+    }
+    pub const fn set_sugar(mut self, enable: bool) -> Self {
+        self.0.set_sugar = enable;
+        self
+    }
+    pub const fn sort_keys(mut self, enable: bool) -> Self {
+        self.0.sort_keys = enable;
+        self
+    }
+    pub const fn skip_none_fields(mut self, enable: bool) -> Self {
+        self.0.skip_none_fields = enable;
+        self
+    }
+    pub const fn ident_keys(mut self, enable: bool) -> Self {
+        self.0.ident_keys = enable;
+        self
+    }
+    pub const fn dotted_keys(mut self, enable: bool) -> Self {
+        self.0.dotted_keys = enable;
+        self
+    }
+    pub const fn variant_index_comments(mut self, enable: bool) -> Self {
+        self.0.variant_index_comments = enable;
+        self
+    }
+    pub const fn pack_scalar_fields(mut self, width: Option<usize>) -> Self {
+        self.0.pack_scalar_fields = width;
+        self
+    }
+    pub const fn section_dividers(mut self, lines: Option<usize>) -> Self {
+        self.0.section_dividers = lines;
+        self
+    }
+    pub const fn header(mut self, text: Option<&'static str>) -> Self {
+        self.0.header = text;
+        self
+    }
+    #[cfg(feature = "syntax-paragraph")]
+    pub const fn wrap_width(mut self, width: Option<usize>) -> Self {
+        self.0.wrap_width = width;
+        self
+    }
+    #[cfg(feature = "syntax-raw-strings")]
+    pub const fn raw_strings(mut self, enable: bool) -> Self {
+        self.0.raw_strings = enable;
+        self
+    }
+    #[cfg(feature = "syntax-basexx")]
+    pub const fn bytes_wrap_width(mut self, width: Option<usize>) -> Self {
+        self.0.bytes_wrap_width = width;
+        self
+    }
+    pub const fn ascii_only(mut self, enable: bool) -> Self {
+        self.0.ascii_only = enable;
+        self
+    }
+    pub const fn escape_invisible(mut self, enable: bool) -> Self {
+        self.0.escape_invisible = enable;
+        self
+    }
+    pub const fn line_width(mut self, width: Option<usize>) -> Self {
+        self.0.line_width = width;
+        self
+    }
+    pub const fn indent_width(mut self, width: usize) -> Self {
+        self.0.indent_width = width;
+        self
+    }
+    pub const fn struct_names(mut self, enable: bool) -> Self {
+        self.0.struct_names = enable;
+        self
+    }
+    pub const fn enum_paths(mut self, paths: EnumPaths) -> Self {
+        self.0.enum_paths = paths;
+        self
+    }
+    pub const fn trailing_commas(mut self, enable: bool) -> Self {
+        self.0.trailing_commas = enable;
+        self
+    }
+    pub const fn hex_floats(mut self, enable: bool) -> Self {
+        self.0.hex_floats = enable;
+        self
+    }
+    pub const fn nan_payloads(mut self, enable: bool) -> Self {
+        self.0.nan_payloads = enable;
+        self
+    }
+    pub const fn unsigned_radix(mut self, radix: Radix) -> Self {
+        self.0.unsigned_radix = radix;
+        self
+    }
+    pub const fn digit_separators(mut self, group: Option<usize>) -> Self {
+        self.0.digit_separators = group;
+        self
+    }
+    pub const fn significant_digits(mut self, digits: Option<usize>) -> Self {
+        self.0.significant_digits = digits;
+        self
+    }
+    pub const fn memoize_shared(mut self, enable: bool) -> Self {
+        self.0.memoize_shared = enable;
+        self
+    }
+    pub const fn recursion_limit(mut self, limit: usize) -> Self {
+        self.0.recursion_limit = limit;
+        self
+    }
+    pub const fn newline(mut self, newline: Newline) -> Self {
+        self.0.newline = newline;
+        self
+    }
+
+    pub const fn build(self) -> SerializeConfig {
+        self.0
+    }
+}
+
+impl From<SerializeConfig> for SerializeConfigBuilder {
+    /// Starts from another config, e.g. [`SerializeConfig::minimal`].
+    fn from(cfg: SerializeConfig) -> Self {
+        Self(cfg)
+    }
+}
+
+/// How bytes are written. The encoded flavors exist only with the feature `syntax-basexx`.
+#[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BytesFlavor {
     Normal,
+    #[cfg(feature = "syntax-basexx")]
     Base16,
+    #[cfg(feature = "syntax-basexx")]
     Base32,
+    #[cfg(feature = "syntax-basexx")]
     Base64,
 }
 
+/// The depths where values are pretty, counting the outermost value as depth 0,
+/// its elements, fields and entries as depth 1, and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expand {
+    /// Pretty at any depth.
+    All,
+    /// Pretty until this depth, e.g. `UntilDepth(0)` is minimal everywhere.
+    UntilDepth(u8),
+    /// Pretty from the first depth until the second, e.g. to keep the outer levels of a long list compact
+    /// while expanding the big values deep inside.
+    Between(u8, u8),
+}
+
+impl Expand {
+    /// Whether values at `depth` are minimized.
+    const fn minimizes(self, depth: usize) -> bool {
+        match self {
+            Expand::All => false,
+            Expand::UntilDepth(until) => depth >= until as usize,
+            Expand::Between(from, until) => depth < from as usize || depth >= until as usize,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnumPaths {
+    /// `Item::Water` everywhere, e.g. for self-documenting saves that are otherwise minimal.
+    Always,
+    /// `Item::Water` where pretty, `Water` where minimized.
+    PrettyOnly,
+    /// `Water` everywhere.
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    /// `255`
+    Decimal,
+    /// `0b11111111`
+    Binary,
+    /// `0o377`
+    Octal,
+    /// `0xff`
+    Hexadecimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Newline {
+    /// `\n`
+    Lf,
+    /// `\r\n`, e.g. for tools on Windows.
+    CrLf,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ObjectType {
     Tuple,
@@ -87,30 +575,383 @@ enum ObjectType {
 ///
 /// Usually convenience functions [`to_string`], [`to_string_pretty`]... are enough.
 pub struct Serializer<W: Write> {
-    dst: W,
+    dst: Columned<W>,
     dep: usize,
     cfg: SerializeConfig,
-    buf: Box<[u8; BUFFER_SIZE]>,
+    /// Where numbers are formatted, of `BUFFER_SIZE` bytes, lent to forks.
+    buf: Box<[u8]>,
+
+    /// Inside [`adapters::hex_float`](crate::adapters::hex_float).
+    hex_floats: bool,
+    /// Inside a unit adapter, e.g. [`adapters::si`](crate::adapters::si), strings are written without quotes.
+    suffixed: bool,
+    /// Inside an adapter of [`fmt`](crate::fmt).
+    form: Option<Form>,
+    /// Serializing a map key, which cannot be a paragraph.
+    in_key: bool,
+    /// Serializing a map key written as an identifier, see [`SerializeConfig::ident_keys`].
+    ident_key: bool,
+    /// A paragraph was just written, what follows must start on a new line.
+    after_paragraph: bool,
+    /// Writing a value on a single line, see [`SerializeConfig::line_width`].
+    inline: bool,
+    /// Outputs of shared values, see [`SerializeConfig::memoize_shared`].
+    memo: HashMap<MemoKey, Rendered>,
+    /// Where values were written, see [`Serializer::with_source_map`].
+    spans: Option<SourceMap>,
+    /// The path of the value being written, kept along with the source map.
+    path: FieldPath,
+}
+
+/// A step from a value into one it contains, see [`Serializer::with_source_map`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// The value of a field of a struct or struct variant, including each key of a dotted key.
+    Field(&'static str),
+    /// An element of a sequence, tuple or tuple variant, by index from 0.
+    Index(usize),
+    /// The value of an entry of a map, with the key as written, e.g. `"alpha"` with its quotes.
+    Key(String),
+}
+
+/// The path of a value from the outermost one, e.g. `[Field("inventory"), Index(2)]`.
+pub type FieldPath = Vec<PathSegment>;
+
+/// Where values were written, as byte ranges of the output, see [`Serializer::with_source_map`].
+pub type SourceMap = Vec<(FieldPath, Range<usize>)>;
+
+/// The output of a value written into a buffer, see [`Serializer::render`].
+struct Rendered {
+    output: Vec<u8>,
+    /// Whether the output ends with a paragraph.
+    after_paragraph: bool,
+    /// Paths relative to the value, and ranges relative to the output.
+    spans: SourceMap,
+}
+
+/// A shared value and where it's written, as its output depends on the latter.
+///
+/// Values of different types or sizes may have the same address, e.g. zero-sized ones, or a struct and its first field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct MemoKey {
+    addr: usize,
+    ty: &'static str,
+    size: usize,
+    dep: usize,
+    col: usize,
+    in_key: bool,
+    inline: bool,
 }
 
 impl<W: Write> Serializer<W> {
     pub fn new(writer: W, cfg: SerializeConfig) -> Self {
         Self {
-            dst: writer,
+            dst: Columned {
+                dst: writer,
+                buf: Vec::new(),
+                buf_start: (0, 0),
+                line: 0,
+                col: 0,
+                pos: 0,
+                header: cfg
+                    .header
+                    .map_or_else(Vec::new, |text| header_comment(text, cfg.newline)),
+            },
             dep: 0,
             cfg,
-            buf: Box::new([0; BUFFER_SIZE]),
+            buf: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            hex_floats: false,
+            suffixed: false,
+            form: None,
+            in_key: false,
+            ident_key: false,
+            after_paragraph: false,
+            inline: false,
+            memo: HashMap::new(),
+            spans: None,
+            path: Vec::new(),
+        }
+    }
+
+    /// Also records where each field, element and entry is written, e.g. for tools to highlight
+    /// or post-process specific fields of generated files, see [`source_map`](Self::source_map).
+    pub fn with_source_map(mut self) -> Self {
+        self.spans = Some(Vec::new());
+        self
+    }
+
+    /// Writes `text` as the header, instead of [`SerializeConfig::header`], e.g. with a timestamp made at runtime.
+    /// Given before anything is written.
+    pub fn with_header(mut self, text: &str) -> Self {
+        self.dst.header = header_comment(text, self.cfg.newline);
+        self
+    }
+
+    /// Buffers up to `capacity` bytes before writing them into the writer, e.g. for a `File` or `TcpStream`,
+    /// which would otherwise be written every few bytes. Given before anything is written.
+    ///
+    /// Call [`flush`](Self::flush) or [`into_inner`](Self::into_inner) afterwards, the buffer is not written when dropped.
+    pub fn with_buffer(mut self, capacity: usize) -> Self {
+        self.dst.buf = Vec::with_capacity(capacity);
+        self
+    }
+
+    /// Writes what is buffered into the writer, and flushes it, see [`with_buffer`](Self::with_buffer).
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.dst.flush()?)
+    }
+
+    /// Writes what is buffered into the writer, and returns it, see [`with_buffer`](Self::with_buffer).
+    pub fn into_inner(mut self) -> Result<W> {
+        self.dst.write_buffered()?;
+        Ok(self.dst.dst)
+    }
+
+    /// Where values were written so far, by their paths from the outermost value, containers before
+    /// what they contain. Ranges are in bytes of the output, and cover values without their keys,
+    /// so that each key of a dotted key, e.g. `server` of `server.port: 80`, covers the value after it.
+    ///
+    /// Values inside map keys are not recorded, and neither is anything without [`with_source_map`](Self::with_source_map).
+    pub fn source_map(&self) -> &[(FieldPath, Range<usize>)] {
+        self.spans.as_deref().unwrap_or_default()
+    }
+
+    /// Writes the items of `iter` as a sequence as soon as each one is produced, e.g. for exports of
+    /// millions of records from a database cursor, without collecting them first:
+    ///
+    /// ```
+    /// # use std::io::BufWriter;
+    /// let mut ser = keon::Serializer::new(BufWriter::new(std::io::sink()), keon::SerializeConfig::comfort());
+    /// ser.collect_seq_streaming((0..1_000_000u64).map(|i| (i, i * i))).unwrap();
+    /// ```
+    ///
+    /// Memory stays bounded by the largest item, as elements are fitted within the line width one by one,
+    /// unless a [source map](Self::with_source_map) is kept. Wrap unbuffered writers in a `BufWriter`,
+    /// which is left unflushed like with any other value.
+    pub fn collect_seq_streaming<I>(&mut self, iter: I) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        let mut seq = SerializerEntry::enter(self, ObjectType::Seq)?;
+        for item in iter {
+            SerializeSeq::serialize_element(&mut seq, &item)?;
         }
+        SerializeSeq::end(seq)
+    }
+
+    /// Serializes a value inside another one, on a single line if it fits, see [`SerializeConfig::line_width`].
+    fn serialize_fitted<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        if let Some(width) = self.cfg.line_width.filter(|_| !self.inline && !self.minimize()) {
+            // One more column for the comma after it.
+            let budget = width.saturating_sub(self.dst.col + 1);
+            if budget != 0 {
+                // Written on a single line into the probe, which gives up as soon as the line is full.
+                let mut probe = self.fork(WidthProbe {
+                    budget,
+                    output: Vec::new(),
+                });
+                probe.inline = true;
+                let res = value.serialize(&mut probe);
+                self.memo = probe.memo;
+                self.buf = probe.buf;
+                if res.is_ok() {
+                    return self.write_rendered(&Rendered {
+                        output: probe.dst.dst.output,
+                        after_paragraph: false,
+                        spans: probe.spans.unwrap_or_default(),
+                    });
+                }
+            }
+        }
+        value.serialize(self)
+    }
+
+    /// A serializer writing into `dst` from where this one is, taking the memo until it's given back.
+    /// It keeps a source map of its own if this one does.
+    fn fork<D: Write>(&mut self, dst: D) -> Serializer<D> {
+        Serializer {
+            dst: Columned {
+                dst,
+                buf: Vec::new(),
+                buf_start: (0, 0),
+                line: self.dst.line,
+                col: self.dst.col,
+                pos: 0,
+                header: Vec::new(),
+            },
+            dep: self.dep,
+            cfg: self.cfg,
+            buf: std::mem::take(&mut self.buf),
+            hex_floats: self.hex_floats,
+            suffixed: self.suffixed,
+            form: self.form,
+            in_key: self.in_key,
+            ident_key: self.ident_key,
+            after_paragraph: false,
+            inline: self.inline,
+            memo: std::mem::take(&mut self.memo),
+            // Relative to the fork, as shared values may be written again elsewhere from the memo.
+            spans: self.spans.as_ref().map(|_| Vec::new()),
+            path: Vec::new(),
+        }
+    }
+
+    /// Writes into a buffer instead, to be written with [`write_rendered`](Self::write_rendered).
+    fn render(&mut self, f: impl FnOnce(&mut Serializer<Vec<u8>>) -> Result<()>) -> Result<Rendered> {
+        let mut render = self.fork(Vec::new());
+        let res = f(&mut render);
+        self.memo = render.memo;
+        self.buf = render.buf;
+        res.map(|()| Rendered {
+            output: render.dst.dst,
+            after_paragraph: render.after_paragraph,
+            spans: render.spans.unwrap_or_default(),
+        })
+    }
+
+    fn write_rendered(&mut self, rendered: &Rendered) -> Result<()> {
+        let start = self.dst.pos;
+        self.dst.write_all(&rendered.output)?;
+        self.after_paragraph = rendered.after_paragraph;
+        if let Some(spans) = &mut self.spans {
+            for (path, range) in &rendered.spans {
+                spans.push(([&self.path[..], path].concat(), range.start + start..range.end + start));
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a value at `segment` of the current path, recording where if a source map is kept.
+    fn serialize_at(
+        &mut self,
+        segment: impl FnOnce() -> PathSegment,
+        f: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        let Some(spans) = &mut self.spans else {
+            return f(self);
+        };
+        self.path.push(segment());
+        let (i, start) = (spans.len(), self.dst.pos);
+        spans.push((self.path.clone(), start..start));
+
+        let res = f(self);
+        if let Some(spans) = &mut self.spans {
+            spans[i].1.end = self.dst.pos;
+        }
+        self.path.pop();
+        res
+    }
+
+    /// Writes the output of a shared value again if it was written at the same place before,
+    /// see [`SerializeConfig::memoize_shared`].
+    fn serialize_memoized<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        // The outermost value is written once anyway, and the memo is cleared for each one.
+        if self.dep == 0 {
+            return value.serialize(self);
+        }
+
+        let key = MemoKey {
+            addr: value as *const T as *const () as usize,
+            ty: std::any::type_name::<T>(),
+            size: std::mem::size_of_val(value),
+            dep: self.dep,
+            // Only where values are fitted within the line width.
+            col: match self.cfg.line_width.is_some() && !self.minimize() {
+                true => self.dst.col,
+                false => 0,
+            },
+            in_key: self.in_key,
+            inline: self.inline,
+        };
+        let rendered = match self.memo.remove(&key) {
+            Some(rendered) => rendered,
+            None => self.render(|ser| value.serialize(ser))?,
+        };
+        let res = self.write_rendered(&rendered);
+        self.memo.insert(key, rendered);
+        res
+    }
+
+    /// Writes the `key` of a map, as an identifier if `ident`, see [`SerializeConfig::ident_keys`].
+    fn serialize_map_key<T: ?Sized + Serialize>(&mut self, key: &T, ident: bool) -> Result<()> {
+        self.in_key = true;
+        self.ident_key = ident;
+        let res = self.serialize_fitted(key);
+        self.in_key = false;
+        self.ident_key = false;
+        res
+    }
+
+    /// Writes ` => value` of a map after the `key` as written, or `: value` after an `ident` one,
+    /// or nothing for a unit with [`SerializeConfig::set_sugar`].
+    fn serialize_map_value<T: ?Sized + Serialize>(&mut self, key: &[u8], ident: bool, value: &T) -> Result<()> {
+        if self.cfg.set_sugar && matches!(value.serialize(ShapeProbe), Ok(Shape::Unit)) {
+            return Ok(());
+        }
+
+        match ident {
+            true => write!(self.dst, ":")?,
+            false => {
+                self.maybe_write_space()?;
+                write!(self.dst, "=>")?;
+            }
+        }
+        self.maybe_write_space()?;
+        self.serialize_at(
+            || PathSegment::Key(String::from_utf8_lossy(key).into_owned()),
+            |ser| ser.serialize_fitted(value),
+        )
+    }
+
+    /// Writes `key: value` of a struct or struct variant,
+    /// or `"key" => value` if the key is not an identifier, e.g. renamed by `#[serde(rename = "...")]`.
+    fn serialize_struct_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        if !is_ident(key) {
+            self.serialize_map_key(key, false)?;
+            self.maybe_write_space()?;
+            write!(self.dst, "=>")?;
+            self.maybe_write_space()?;
+            return self.serialize_at(|| PathSegment::Field(key), |ser| ser.serialize_fitted(value));
+        }
+        self.write_ident(key)?;
+
+        let mut dotted = match self.cfg.dotted_keys && !self.minimize() {
+            true => value.serialize(DottedProbe).unwrap_or_default(),
+            false => Vec::new(),
+        };
+        // The rest is written as usual, keys that are not identifiers included.
+        if let Some(end) = dotted.iter().position(|key| !is_ident(key)) {
+            dotted.truncate(end);
+        }
+        for key in &dotted {
+            write!(self.dst, ".")?;
+            self.write_ident(key)?;
+        }
+
+        write!(self.dst, ":")?;
+        self.maybe_write_space()?;
+        self.serialize_at(
+            || PathSegment::Field(key),
+            |ser| match dotted.len() {
+                0 => ser.serialize_fitted(value),
+                depth => value.serialize(DottedLeaf { ser, depth }),
+            },
+        )
     }
 
     #[inline]
     fn minimize(&self) -> bool {
-        self.dep >= self.cfg.minimize_after_depth as usize
+        self.cfg.expand.minimizes(self.dep)
     }
 
     #[inline]
     fn write_newline(&mut self) -> Result<()> {
-        Ok(writeln!(self.dst)?)
+        match self.cfg.newline {
+            Newline::Lf => self.dst.write_all(b"\n")?,
+            Newline::CrLf => self.dst.write_all(b"\r\n")?,
+        }
+        Ok(())
     }
     #[inline]
     fn write_space(&mut self) -> Result<()> {
@@ -118,8 +959,15 @@ impl<W: Write> Serializer<W> {
     }
     #[inline]
     fn write_indent(&mut self) -> Result<()> {
-        for _ in 0..self.dep {
-            write!(self.dst, "\x20\x20\x20\x20")?;
+        self.write_spaces(self.dep * self.cfg.indent_width)
+    }
+    fn write_spaces(&mut self, mut n: usize) -> Result<()> {
+        // Padding with `write!` would write the spaces one by one.
+        const SPACES: &[u8] = &[b' '; 64];
+        while n > 0 {
+            let len = n.min(SPACES.len());
+            self.dst.write_all(&SPACES[..len])?;
+            n -= len;
         }
         Ok(())
     }
@@ -135,7 +983,7 @@ impl<W: Write> Serializer<W> {
 
     #[inline]
     fn maybe_write_struct_name(&mut self, name: &str) -> Result<bool> {
-        if !self.minimize() && !name.is_empty() {
+        if self.cfg.struct_names && !self.minimize() && is_ident(name) {
             write!(self.dst, "(")?;
             self.write_ident(name)?;
             write!(self.dst, ")")?;
@@ -146,13 +994,25 @@ impl<W: Write> Serializer<W> {
     }
     #[inline]
     fn maybe_write_enum_name(&mut self, name: &str) -> Result<()> {
-        if !self.minimize() && !name.is_empty() {
+        let enabled = match self.cfg.enum_paths {
+            EnumPaths::Always => true,
+            EnumPaths::PrettyOnly => !self.minimize(),
+            EnumPaths::Never => false,
+        };
+        if enabled && is_ident(name) {
             self.write_ident(name)?;
             write!(self.dst, "::")?;
         }
         Ok(())
     }
     #[inline]
+    fn maybe_write_variant_index(&mut self, variant_index: u32) -> Result<()> {
+        if self.cfg.variant_index_comments && !self.minimize() {
+            write!(self.dst, " /* ={} */", variant_index)?;
+        }
+        Ok(())
+    }
+    #[inline]
     fn maybe_write_space(&mut self) -> Result<()> {
         if !self.minimize() {
             self.write_space()?;
@@ -162,19 +1022,103 @@ impl<W: Write> Serializer<W> {
 
     #[inline]
     fn write_i64(&mut self, v: i64) -> Result<()> {
-        Ok(self.dst.write_all(lexical_core::write(v, &mut *self.buf))?)
+        let digits = lexical_core::write(v, &mut self.buf);
+        Ok(write_grouped(&mut self.dst, digits, self.cfg.digit_separators)?)
     }
     #[inline]
     fn write_u64(&mut self, v: u64) -> Result<()> {
-        Ok(self.dst.write_all(lexical_core::write(v, &mut *self.buf))?)
+        let digits = lexical_core::write(v, &mut self.buf);
+        Ok(write_grouped(&mut self.dst, digits, self.cfg.digit_separators)?)
+    }
+    /// With the prefix, e.g. `0xff`, see [`SerializeConfig::unsigned_radix`].
+    fn write_radix(&mut self, v: u64, radix: Radix) -> Result<()> {
+        match radix {
+            Radix::Decimal => self.write_u64(v)?,
+            Radix::Binary => write!(self.dst, "{:#b}", v)?,
+            Radix::Octal => write!(self.dst, "{:#o}", v)?,
+            Radix::Hexadecimal => write!(self.dst, "{:#x}", v)?,
+        }
+        Ok(())
     }
     #[inline]
     fn write_f64(&mut self, v: f64) -> Result<()> {
-        Ok(self.dst.write_all(lexical_core::write(v, &mut *self.buf))?)
+        let v = match self.cfg.significant_digits {
+            Some(digits) if digits < 17 => round_significant(v, digits),
+            _ => v,
+        };
+        Ok(self.dst.write_all(lexical_core::write(v, &mut self.buf))?)
+    }
+    /// Like `0x1.8p3`, exact and can be read back as any float literal.
+    fn write_hex_float(&mut self, v: f64) -> Result<()> {
+        if !v.is_finite() {
+            return self.write_f64(v);
+        }
+        if v.is_sign_negative() {
+            write!(self.dst, "-")?;
+        }
+
+        let bits = v.abs().to_bits();
+        let (exp, mant) = ((bits >> 52) as i64, bits & ((1 << 52) - 1));
+        let (lead, exp) = match (exp, mant) {
+            (0, 0) => return Ok(write!(self.dst, "0x0p0")?),
+            (0, _) => (0, -1022),
+            _ => (1, exp - 1023),
+        };
+
+        let frac = format!("{:013x}", mant);
+        let frac = frac.trim_end_matches('0');
+        match frac.is_empty() {
+            true => write!(self.dst, "0x{}p{}", lead, exp)?,
+            false => write!(self.dst, "0x{}.{}p{}", lead, frac, exp)?,
+        }
+        Ok(())
+    }
+    /// See [`SerializeConfig::wrap_width`].
+    #[cfg(feature = "syntax-paragraph")]
+    fn write_paragraph(&mut self, lines: &[(char, &str)]) -> Result<()> {
+        // The lines are aligned under the first one, e.g. after a key.
+        let col = self.dst.col;
+        for (i, (marker, line)) in lines.iter().enumerate() {
+            if i != 0 {
+                self.write_newline()?;
+                self.write_spaces(col)?;
+            }
+            match line.is_empty() {
+                true => write!(self.dst, "{}", marker)?,
+                false => write!(self.dst, "{} {}", marker, line)?,
+            }
+        }
+        self.after_paragraph = true;
+        Ok(())
+    }
+    /// See [`SerializeConfig::bytes_wrap_width`].
+    #[cfg(feature = "syntax-basexx")]
+    fn write_bytes_encoded(&mut self, prefix: &str, encoded: &str) -> Result<()> {
+        let width = match self.cfg.bytes_wrap_width {
+            Some(width) if width != 0 && !self.minimize() && !self.in_key && !self.inline => width,
+            _ => return Ok(write!(self.dst, "{}\"{}\"", prefix, encoded)?),
+        };
+
+        write!(self.dst, "{}\"", prefix)?;
+        // Encoded bytes are ASCII, so chunks are whole characters.
+        for (i, line) in encoded.as_bytes().chunks(width).enumerate() {
+            if i != 0 {
+                self.write_newline()?;
+                self.dep += 1;
+                self.write_indent()?;
+                self.dep -= 1;
+            }
+            self.dst.write_all(line)?;
+        }
+        Ok(write!(self.dst, "\"")?)
     }
     #[inline] // avoids ugly and unnecessary mantissas.
     fn write_f32(&mut self, v: f32) -> Result<()> {
-        Ok(self.dst.write_all(lexical_core::write(v, &mut *self.buf))?)
+        let v = match self.cfg.significant_digits {
+            Some(digits) if digits < 9 => round_significant(v, digits),
+            _ => v,
+        };
+        Ok(self.dst.write_all(lexical_core::write(v, &mut self.buf))?)
     }
 
     #[inline]
@@ -186,11 +1130,17 @@ impl<W: Write> Serializer<W> {
             b'\r' => self.dst.write_all(br"\r")?,
             b'\'' => self.dst.write_all(br"\'")?,
             b'\"' => self.dst.write_all(b"\\\"")?,
+            b'\\' => self.dst.write_all(br"\\")?,
             0x20..=0x7e => self.dst.write_all(&[byte])?,
             _ => write!(self.dst, "\\x{:02x}", byte)?,
         }
         Ok(())
     }
+    /// See [`SerializeConfig::ascii_only`] and [`SerializeConfig::escape_invisible`].
+    #[inline]
+    fn escapes_unicode(&self, ch: char) -> bool {
+        !ch.is_ascii() && (self.cfg.ascii_only || self.cfg.escape_invisible && is_invisible(ch))
+    }
     #[inline]
     fn write_char_escaped(&mut self, ch: char) -> Result<()> {
         match ch {
@@ -200,7 +1150,9 @@ impl<W: Write> Serializer<W> {
             '\r' => self.dst.write_all(br"\r")?,
             '\'' => self.dst.write_all(br"\'")?,
             '\"' => self.dst.write_all(b"\\\"")?,
-            '\x01'..='\x19' | '\x7f' => write!(self.dst, "\\x{:02x}", ch as u8)?,
+            '\\' => self.dst.write_all(br"\\")?,
+            '\x01'..='\x1f' | '\x7f' => write!(self.dst, "\\x{:02x}", ch as u8)?,
+            _ if self.escapes_unicode(ch) => write!(self.dst, "\\u{{{:x}}}", ch as u32)?,
             _ => write!(self.dst, "{}", ch)?,
         }
         Ok(())
@@ -214,13 +1166,25 @@ pub struct SerializerEntry<'se, W: Write> {
     ser: &'se mut Serializer<W>,
     typ: ObjectType,
     ctr: usize,
+    /// Outputs of the keys and values of a map to be sorted, see [`SerializeConfig::sort_keys`].
+    entries: Vec<(Vec<u8>, Option<Rendered>)>,
+    /// The output of the current key of a map, kept along with the source map.
+    key: Vec<u8>,
+    /// The last field is a scalar on a single line, see [`SerializeConfig::pack_scalar_fields`].
+    packed: bool,
+    /// The current key of a map is written as an identifier, see [`SerializeConfig::ident_keys`].
+    ident_key: bool,
 }
 
 impl<'se, W: Write> SerializerEntry<'se, W> {
     fn enter(ser: &'se mut Serializer<W>, typ: ObjectType) -> Result<Self> {
+        // Addresses of shared values may be reused by the next outermost value.
+        if ser.dep == 0 {
+            ser.memo.clear();
+        }
         ser.dep += 1;
 
-        if ser.dep > RECURSION_LIMIT {
+        if ser.dep > ser.cfg.recursion_limit {
             Error::raise(ErrorKind::ExceededRecursionLimit)?
         }
 
@@ -235,19 +1199,62 @@ impl<'se, W: Write> SerializerEntry<'se, W> {
             ObjectType::MinNewtype | ObjectType::MinNullary => write!(ser.dst, "%")?,
         }
 
-        Ok(Self { ser, typ, ctr: 0 })
+        Ok(Self {
+            ser,
+            typ,
+            ctr: 0,
+            entries: Vec::new(),
+            key: Vec::new(),
+            packed: false,
+            ident_key: false,
+        })
     }
 
     fn leave(mut self) -> Result<()> {
+        let mut entries = std::mem::take(&mut self.entries);
+        entries.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+        for (key, value) in entries {
+            self.write_separator()?;
+            self.ser.dst.write_all(&key)?;
+            if let Some(value) = value {
+                self.ser.write_rendered(&value)?;
+            }
+        }
+
+        // Whether the entries were minimized, rather than where the container is.
+        let minimized = self.ser.minimize();
         self.ser.dep -= 1;
 
-        if !self.ser.minimize() && self.ctr != 0 {
-            self.write_separator()?
+        let closed = !matches!(
+            self.typ,
+            ObjectType::Something | ObjectType::MinNewtype | ObjectType::MinNullary
+        );
+        // A tuple of a single element needs a trailing comma, unless written already.
+        let mut single = self.typ == ObjectType::Tuple && self.ctr == 1;
+        if self.ser.after_paragraph {
+            // The trailing comma is optional, otherwise the newline is left to the container.
+            if closed {
+                self.ser.after_paragraph = false;
+                self.ser.write_newline()?;
+                self.ser.write_indent()?;
+            }
+        } else if self.ser.inline && !minimized {
+            if self.ctr != 0 && matches!(self.typ, ObjectType::Map | ObjectType::Struct) {
+                self.ser.write_space()?;
+            }
+        } else if !minimized && self.ctr != 0 {
+            if self.ser.cfg.trailing_commas || single {
+                write!(self.ser.dst, ",")?;
+                single = false;
+            }
+            // Not a separator, which would be minimized where the container itself is.
+            self.ser.write_newline()?;
+            self.ser.write_indent()?;
         }
 
         match self.typ {
             ObjectType::Seq => write!(self.ser.dst, "]")?,
-            ObjectType::Tuple if self.ctr == 1 => write!(self.ser.dst, ",)")?,
+            ObjectType::Tuple if single => write!(self.ser.dst, ",)")?,
             ObjectType::Tuple | ObjectType::TupleDocile => write!(self.ser.dst, ")")?,
             ObjectType::Map | ObjectType::Struct => write!(self.ser.dst, "}}")?,
             ObjectType::Something | ObjectType::MinNewtype | ObjectType::MinNullary => (),
@@ -257,19 +1264,96 @@ impl<'se, W: Write> SerializerEntry<'se, W> {
     }
 
     fn write_separator(&mut self) -> Result<()> {
+        if self.ser.after_paragraph {
+            // A paragraph runs until the end of the line, so the comma leads the next line instead.
+            self.ser.after_paragraph = false;
+            self.ser.write_newline()?;
+            self.ser.write_indent()?;
+            write!(self.ser.dst, ",")?;
+            self.ctr += 1;
+            return self.ser.write_space();
+        }
         if self.ctr != 0 {
             write!(self.ser.dst, ",")?;
         }
 
         self.ctr += 1;
 
-        if !self.ser.minimize() {
+        if self.ser.inline && !self.ser.minimize() {
+            if self.ctr != 1 || matches!(self.typ, ObjectType::Map | ObjectType::Struct) {
+                self.ser.write_space()?;
+            }
+        } else if !self.ser.minimize() {
             self.ser.write_newline()?;
             self.ser.write_indent()?;
         }
 
         Ok(())
     }
+
+    fn serialize_element_at<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let index = self.ctr - 1;
+        self.ser
+            .serialize_at(|| PathSegment::Index(index), |ser| ser.serialize_fitted(value))
+    }
+
+    /// Where entries start, as sorted entries are written into buffers first.
+    fn entry_col(&self) -> usize {
+        match self.ser.inline || self.ser.minimize() {
+            // Values are not fitted within the line width there, so it doesn't matter.
+            true => self.ser.dst.col,
+            false => self.ser.dep * self.ser.cfg.indent_width,
+        }
+    }
+
+    /// Writes a field, after a divider if it's long in the outermost struct, see [`SerializeConfig::section_dividers`],
+    /// or nothing if it's `None` with [`SerializeConfig::skip_none_fields`].
+    fn write_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        if self.ser.cfg.skip_none_fields && matches!(value.serialize(ShapeProbe), Ok(Shape::None)) {
+            return Ok(());
+        }
+        if let Some(width) = self.ser.cfg.pack_scalar_fields {
+            if !self.ser.inline && !self.ser.minimize() && value.serialize(ShapeProbe).is_ok() {
+                return self.write_packed_field(key, value, width);
+            }
+        }
+        self.packed = false;
+
+        self.write_separator()?;
+        match self.ser.cfg.section_dividers {
+            Some(lines) if self.ser.dep == 1 && !self.ser.minimize() => {
+                let rendered = self.ser.render(|ser| ser.serialize_struct_field(key, value))?;
+                if rendered.output.iter().filter(|&&b| b == b'\n').count() >= lines {
+                    write!(self.ser.dst, "// ---- {} ----", key)?;
+                    self.ser.write_newline()?;
+                    self.ser.write_indent()?;
+                }
+                self.ser.write_rendered(&rendered)
+            }
+            _ => self.ser.serialize_struct_field(key, value),
+        }
+    }
+
+    /// Writes a field whose value is a scalar after the last one on the same line if it fits within `width`,
+    /// see [`SerializeConfig::pack_scalar_fields`].
+    fn write_packed_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T, width: usize) -> Result<()> {
+        let rendered = self.ser.render(|ser| ser.serialize_struct_field(key, value))?;
+        // Strings may still be written as paragraphs.
+        let single_line = !rendered.after_paragraph && !rendered.output.contains(&b'\n');
+        let chars = rendered.output.iter().filter(|&&b| b & 0xC0 != 0x80).count();
+
+        // After a comma and a space, and followed by a comma.
+        match self.packed && single_line && self.ser.dst.col + 2 + chars < width {
+            true => {
+                write!(self.ser.dst, ",")?;
+                self.ser.write_space()?;
+                self.ctr += 1;
+            }
+            false => self.write_separator()?,
+        }
+        self.packed = single_line;
+        self.ser.write_rendered(&rendered)
+    }
 }
 
 //==================================================================================================
@@ -307,7 +1391,15 @@ impl<'se, W: Write> serde::Serializer for &'se mut Serializer<W> {
         self.serialize_i64(v as i64)
     }
     fn serialize_i64(self, v: i64) -> Result<()> {
-        self.write_i64(v)
+        match self.form {
+            Some(Form::Radix(radix)) if radix != Radix::Decimal => {
+                if v < 0 {
+                    write!(self.dst, "-")?;
+                }
+                self.write_radix(v.unsigned_abs(), radix)
+            }
+            _ => self.write_i64(v),
+        }
     }
 
     fn serialize_u8(self, v: u8) -> Result<()> {
@@ -320,14 +1412,30 @@ impl<'se, W: Write> serde::Serializer for &'se mut Serializer<W> {
         self.serialize_u64(v as u64)
     }
     fn serialize_u64(self, v: u64) -> Result<()> {
-        self.write_u64(v)
+        let radix = match self.form {
+            Some(Form::Radix(radix)) => radix,
+            _ => self.cfg.unsigned_radix,
+        };
+        self.write_radix(v, radix)
     }
 
     fn serialize_f32(self, v: f32) -> Result<()> {
-        self.write_f32(v)
+        if self.cfg.nan_payloads && v.is_nan() && v.to_bits() != f32::NAN.to_bits() {
+            return Ok(write!(self.dst, "NaN:{:#010x}", v.to_bits())?);
+        }
+        match self.cfg.hex_floats {
+            true => self.write_hex_float(v as f64),
+            false => self.write_f32(v),
+        }
     }
     fn serialize_f64(self, v: f64) -> Result<()> {
-        self.write_f64(v)
+        if self.cfg.nan_payloads && v.is_nan() && v.to_bits() != f64::NAN.to_bits() {
+            return Ok(write!(self.dst, "NaN:{:#018x}", v.to_bits())?);
+        }
+        match self.cfg.hex_floats || self.hex_floats {
+            true => self.write_hex_float(v),
+            false => self.write_f64(v),
+        }
     }
 
     fn serialize_char(self, v: char) -> Result<()> {
@@ -337,15 +1445,58 @@ impl<'se, W: Write> serde::Serializer for &'se mut Serializer<W> {
         Ok(())
     }
     fn serialize_str(self, v: &str) -> Result<()> {
+        if self.suffixed {
+            return Ok(write!(self.dst, "{}", v)?);
+        }
+        if self.ident_key {
+            return self.write_ident(v);
+        }
+
+        let verbatim = match self.cfg.ascii_only || self.cfg.escape_invisible {
+            true => !v.chars().any(|ch| self.escapes_unicode(ch)),
+            false => true,
+        };
+
+        #[cfg(feature = "syntax-paragraph")]
+        if !self.minimize() && !self.in_key && !self.inline && verbatim {
+            let forced = self.form == Some(Form::Paragraph);
+            let width = match forced {
+                true => Some(self.cfg.wrap_width.unwrap_or(usize::MAX)),
+                false => self.cfg.wrap_width,
+            };
+            if let Some(lines) = width.and_then(|width| paragraph_lines(v, width, forced)) {
+                return self.write_paragraph(&lines);
+            }
+        }
+
+        #[cfg(feature = "syntax-raw-strings")]
+        if (self.cfg.raw_strings || self.form == Some(Form::RawString)) && verbatim {
+            if let Some(n) = raw_string_backticks(v, self.form == Some(Form::RawString)) {
+                let backticks = "`".repeat(n);
+                return Ok(write!(self.dst, "{}\"{}\"{}", backticks, v, backticks)?);
+            }
+        }
+
+        // Runs of characters that need no escape are written at once.
+        let mut plain = 0;
         write!(self.dst, "\"")?;
-        for ch in v.chars() {
-            self.write_char_escaped(ch)?;
+        for (i, ch) in v.char_indices() {
+            if matches!(ch, '\0'..='\x1f' | '\x7f' | '\'' | '"' | '\\') || !verbatim && self.escapes_unicode(ch) {
+                self.dst.write_all(&v.as_bytes()[plain..i])?;
+                self.write_char_escaped(ch)?;
+                plain = i + ch.len_utf8();
+            }
         }
+        self.dst.write_all(&v.as_bytes()[plain..])?;
         write!(self.dst, "\"")?;
         Ok(())
     }
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
-        match self.cfg.bytes_flavor {
+        let flavor = match self.form {
+            Some(Form::Bytes(flavor)) => flavor,
+            _ => self.cfg.bytes_flavor,
+        };
+        match flavor {
             BytesFlavor::Normal => {
                 write!(self.dst, "b\"")?;
                 for byte in v {
@@ -353,9 +1504,12 @@ impl<'se, W: Write> serde::Serializer for &'se mut Serializer<W> {
                 }
                 write!(self.dst, "\"")?;
             }
-            BytesFlavor::Base16 => write!(self.dst, r#"b16"{}""#, HEXUPPER_PERMISSIVE.encode(v))?,
-            BytesFlavor::Base32 => write!(self.dst, r#"b32"{}""#, BASE32_NOPAD.encode(v))?,
-            BytesFlavor::Base64 => write!(self.dst, r#"b64"{}""#, BASE64URL_NOPAD.encode(v))?,
+            #[cfg(feature = "syntax-basexx")]
+            BytesFlavor::Base16 => self.write_bytes_encoded("b16", &HEXUPPER_PERMISSIVE.encode(v))?,
+            #[cfg(feature = "syntax-basexx")]
+            BytesFlavor::Base32 => self.write_bytes_encoded("b32", &BASE32_NOPAD.encode(v))?,
+            #[cfg(feature = "syntax-basexx")]
+            BytesFlavor::Base64 => self.write_bytes_encoded("b64", &BASE64URL_NOPAD.encode(v))?,
         }
         Ok(())
     }
@@ -365,7 +1519,7 @@ impl<'se, W: Write> serde::Serializer for &'se mut Serializer<W> {
     }
     fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<()> {
         let entry = SerializerEntry::enter(self, ObjectType::Something)?;
-        value.serialize(&mut *entry.ser)?;
+        entry.ser.serialize_fitted(value)?;
         entry.leave()?;
 
         Ok(())
@@ -392,13 +1546,39 @@ impl<'se, W: Write> serde::Serializer for &'se mut Serializer<W> {
     }
 
     fn serialize_newtype_struct<T: ?Sized + Serialize>(self, name: &'static str, value: &T) -> Result<()> {
+        if name == crate::adapters::HEX_FLOAT {
+            self.hex_floats = true;
+            let res = value.serialize(&mut *self);
+            self.hex_floats = false;
+            return res;
+        }
+        if let Some(form) = Form::from_name(name) {
+            let outer = self.form.replace(form);
+            let res = value.serialize(&mut *self);
+            self.form = outer;
+            return res;
+        }
+        if name == crate::adapters::SHARED {
+            return match self.cfg.memoize_shared {
+                true => self.serialize_memoized(value),
+                false => value.serialize(self),
+            };
+        }
+        #[cfg(feature = "unit-suffixes")]
+        if name == crate::adapters::SUFFIXED {
+            self.suffixed = true;
+            let res = value.serialize(&mut *self);
+            self.suffixed = false;
+            return res;
+        }
+
         let leading = self.maybe_write_struct_name(name)?;
 
         let entry = match !self.minimize() {
             true if leading => SerializerEntry::enter(self, ObjectType::TupleDocile)?,
             true | false => SerializerEntry::enter(self, ObjectType::MinNewtype)?,
         };
-        value.serialize(&mut *entry.ser)?;
+        entry.ser.serialize_fitted(value)?;
         entry.leave()?;
 
         Ok(())
@@ -417,17 +1597,26 @@ impl<'se, W: Write> serde::Serializer for &'se mut Serializer<W> {
     }
 
     fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
-        self.maybe_write_struct_name(name)?;
-        self.maybe_write_space()?;
+        if self.maybe_write_struct_name(name)? {
+            self.write_space()?;
+        }
 
         SerializerEntry::enter(self, ObjectType::Struct)
     }
 
     //------------------------------------------------------------------------------
 
-    fn serialize_unit_variant(self, name: &'static str, _variant_index: u32, variant: &'static str) -> Result<()> {
+    fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<()> {
+        if self.cfg.unit_variant_indices && self.minimize() && !self.ident_key {
+            return self.write_u64(variant_index as u64);
+        }
+
+        if self.ident_key {
+            return self.write_ident(variant);
+        }
         self.maybe_write_enum_name(name)?;
         self.write_ident(variant)?;
+        self.maybe_write_variant_index(variant_index)?;
 
         Ok(())
     }
@@ -435,18 +1624,19 @@ impl<'se, W: Write> serde::Serializer for &'se mut Serializer<W> {
     fn serialize_newtype_variant<T: ?Sized + Serialize>(
         self,
         name: &'static str,
-        _variant_index: u32,
+        variant_index: u32,
         variant: &'static str,
         value: &T,
     ) -> Result<()> {
         self.maybe_write_enum_name(name)?;
         self.write_ident(variant)?;
+        self.maybe_write_variant_index(variant_index)?;
 
         let entry = match !self.minimize() {
             true => SerializerEntry::enter(self, ObjectType::TupleDocile)?,
             false => SerializerEntry::enter(self, ObjectType::MinNewtype)?,
         };
-        value.serialize(&mut *entry.ser)?;
+        entry.ser.serialize_fitted(value)?;
         entry.leave()?;
 
         Ok(())
@@ -455,12 +1645,13 @@ impl<'se, W: Write> serde::Serializer for &'se mut Serializer<W> {
     fn serialize_tuple_variant(
         self,
         name: &'static str,
-        _variant_index: u32,
+        variant_index: u32,
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
         self.maybe_write_enum_name(name)?;
         self.write_ident(variant)?;
+        self.maybe_write_variant_index(variant_index)?;
 
         match len {
             0 => SerializerEntry::enter(self, ObjectType::MinNullary),
@@ -471,12 +1662,13 @@ impl<'se, W: Write> serde::Serializer for &'se mut Serializer<W> {
     fn serialize_struct_variant(
         self,
         name: &'static str,
-        _variant_index: u32,
+        variant_index: u32,
         variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
         self.maybe_write_enum_name(name)?;
         self.write_ident(variant)?;
+        self.maybe_write_variant_index(variant_index)?;
         self.maybe_write_space()?;
 
         SerializerEntry::enter(self, ObjectType::Struct)
@@ -490,7 +1682,7 @@ impl<W: Write> SerializeSeq for SerializerEntry<'_, W> {
     type Error = Error;
     fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
         self.write_separator()?;
-        value.serialize(&mut *self.ser)
+        self.serialize_element_at(value)
     }
     fn end(self) -> Result<()> {
         self.leave()
@@ -502,7 +1694,7 @@ impl<W: Write> SerializeTuple for SerializerEntry<'_, W> {
     type Error = Error;
     fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
         self.write_separator()?;
-        value.serialize(&mut *self.ser)
+        self.serialize_element_at(value)
     }
     fn end(self) -> Result<()> {
         self.leave()
@@ -514,7 +1706,7 @@ impl<W: Write> SerializeTupleStruct for SerializerEntry<'_, W> {
     type Error = Error;
     fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
         self.write_separator()?;
-        value.serialize(&mut *self.ser)
+        self.serialize_element_at(value)
     }
     fn end(self) -> Result<()> {
         self.leave()
@@ -526,7 +1718,7 @@ impl<W: Write> SerializeTupleVariant for SerializerEntry<'_, W> {
     type Error = Error;
     fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
         self.write_separator()?;
-        value.serialize(&mut *self.ser)
+        self.serialize_element_at(value)
     }
     fn end(self) -> Result<()> {
         self.leave()
@@ -537,14 +1729,42 @@ impl<W: Write> SerializeMap for SerializerEntry<'_, W> {
     type Ok = ();
     type Error = Error;
     fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        let ident = self.ser.cfg.ident_keys && matches!(key.serialize(ShapeProbe), Ok(Shape::Ident));
+        self.ident_key = ident;
+        if self.ser.cfg.sort_keys {
+            let col = self.entry_col();
+            let rendered = self.ser.render(|ser| {
+                ser.dst.col = col;
+                ser.serialize_map_key(key, ident)
+            })?;
+            self.entries.push((rendered.output, None));
+            return Ok(());
+        }
+
         self.write_separator()?;
-        key.serialize(&mut *self.ser)
+        match self.ser.spans {
+            Some(_) => {
+                self.key = self.ser.render(|ser| ser.serialize_map_key(key, ident))?.output;
+                Ok(self.ser.dst.write_all(&self.key)?)
+            }
+            None => self.ser.serialize_map_key(key, ident),
+        }
     }
     fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
-        self.ser.maybe_write_space()?;
-        write!(self.ser.dst, "=>")?;
-        self.ser.maybe_write_space()?;
-        value.serialize(&mut *self.ser)
+        if let Some((key, _)) = self.entries.last() {
+            let col = match key.iter().rposition(|&b| b == b'\n') {
+                Some(i) => key[i + 1..].iter().filter(|&&b| b & 0xC0 != 0x80).count(),
+                None => self.entry_col() + key.iter().filter(|&&b| b & 0xC0 != 0x80).count(),
+            };
+            let rendered = self.ser.render(|ser| {
+                ser.dst.col = col;
+                ser.serialize_map_value(key, self.ident_key, value)
+            })?;
+            self.entries.last_mut().unwrap().1 = Some(rendered);
+            return Ok(());
+        }
+
+        self.ser.serialize_map_value(&self.key, self.ident_key, value)
     }
     fn end(self) -> Result<()> {
         self.leave()
@@ -555,11 +1775,7 @@ impl<W: Write> SerializeStruct for SerializerEntry<'_, W> {
     type Ok = ();
     type Error = Error;
     fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
-        self.write_separator()?;
-        self.ser.write_ident(key)?;
-        write!(self.ser.dst, ":")?;
-        self.ser.maybe_write_space()?;
-        value.serialize(&mut *self.ser)
+        self.write_field(key, value)
     }
     fn end(self) -> Result<()> {
         self.leave()
@@ -570,13 +1786,567 @@ impl<W: Write> SerializeStructVariant for SerializerEntry<'_, W> {
     type Ok = ();
     type Error = Error;
     fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
-        self.write_separator()?;
-        self.ser.write_ident(key)?;
-        write!(self.ser.dst, ":")?;
-        self.ser.maybe_write_space()?;
-        value.serialize(&mut *self.ser)
+        self.write_field(key, value)
     }
     fn end(self) -> Result<()> {
         self.leave()
     }
 }
+
+//==================================================================================================
+
+/// Keeps track of the line and column where the next character is written, see [`SerializeConfig::line_width`].
+///
+/// Errors of the writer are located there, see [`ErrorKind::Io`].
+struct Columned<W: Write> {
+    dst: W,
+    /// Bytes not yet written into `dst`, up to its capacity, which is zero if unbuffered.
+    buf: Vec<u8>,
+    /// The line and column where `buf` starts, to locate errors of the writer within it.
+    buf_start: (usize, usize),
+    line: usize,
+    col: usize,
+    /// The number of bytes written.
+    pos: usize,
+    /// Written before anything else, see [`SerializeConfig::header`].
+    header: Vec<u8>,
+}
+
+impl<W: Write> Write for Columned<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if !self.header.is_empty() {
+            let header = std::mem::take(&mut self.header);
+            self.write_all(&header)?;
+        }
+
+        let capacity = self.buf.capacity();
+        if self.buf.len() + buf.len() > capacity {
+            self.write_buffered()?;
+        }
+        let len = match buf.len() < capacity {
+            true => {
+                if self.buf.is_empty() {
+                    self.buf_start = (self.line, self.col);
+                }
+                self.buf.extend_from_slice(buf);
+                buf.len()
+            }
+            false => self
+                .dst
+                .write(buf)
+                .map_err(|e| crate::error::LocatedIo::wrap(e, self.line, self.col))?,
+        };
+        self.pos += len;
+        advance(&mut self.line, &mut self.col, &buf[..len]);
+        Ok(len)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.write_buffered()?;
+        self.dst.flush()
+    }
+}
+
+impl<W: Write> Columned<W> {
+    /// Writes `buf` out, locating errors at the first byte not written.
+    fn write_buffered(&mut self) -> std::io::Result<()> {
+        let (mut line, mut col) = self.buf_start;
+        let mut written = 0;
+        let res = loop {
+            let rest = &self.buf[written..];
+            if rest.is_empty() {
+                break Ok(());
+            }
+            match self.dst.write(rest) {
+                Ok(0) => break Err(std::io::ErrorKind::WriteZero.into()),
+                Ok(len) => {
+                    advance(&mut line, &mut col, &rest[..len]);
+                    written += len;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(e) => break Err(e),
+            }
+        };
+        self.buf.drain(..written);
+        self.buf_start = (line, col);
+        res.map_err(|e| crate::error::LocatedIo::wrap(e, line, col))
+    }
+}
+
+/// Moves the `line` and `col` past `bytes`.
+fn advance(line: &mut usize, col: &mut usize, bytes: &[u8]) {
+    let chars = |bytes: &[u8]| bytes.iter().filter(|&&b| b & 0xC0 != 0x80).count();
+    match bytes.iter().rposition(|&b| b == b'\n') {
+        Some(i) => {
+            *line += bytes.iter().filter(|&&b| b == b'\n').count();
+            *col = chars(&bytes[i + 1..]);
+        }
+        None => *col += chars(bytes),
+    }
+}
+
+/// The text as a block comment, or as line comments if it would end a block comment early,
+/// followed by a line break.
+fn header_comment(text: &str, newline: Newline) -> Vec<u8> {
+    let newline = match newline {
+        Newline::Lf => "\n",
+        Newline::CrLf => "\r\n",
+    };
+    let lines = text.lines().map(str::trim_end).collect::<Vec<_>>();
+
+    // Block comments nest, so they must stay open until the end of the text.
+    let mut depth = 1;
+    let mut rest = text;
+    while depth > 0 && !rest.is_empty() {
+        if rest.starts_with("/*") {
+            depth += 1;
+            rest = &rest[2..];
+        } else if rest.starts_with("*/") {
+            depth -= 1;
+            rest = &rest[2..];
+        } else {
+            rest = &rest[rest.chars().next().unwrap().len_utf8()..];
+        }
+    }
+
+    let mut comment = String::new();
+    match (depth, &lines[..]) {
+        (1, [line]) => comment.extend(["/* ", line, " */", newline]),
+        (1, lines) => {
+            comment.extend(["/*", newline]);
+            for line in lines {
+                comment.extend([" *", if line.is_empty() { "" } else { " " }, line, newline]);
+            }
+            comment.extend([" */", newline]);
+        }
+        (_, lines) => {
+            for line in lines {
+                comment.extend(["//", if line.is_empty() { "" } else { " " }, line, newline]);
+            }
+        }
+    }
+    comment.into_bytes()
+}
+
+/// Keeps what is written, and fails once a line break is written or the budget is exceeded,
+/// to tell whether a value fits on a single line.
+struct WidthProbe {
+    budget: usize,
+    output: Vec<u8>,
+}
+
+impl Write for WidthProbe {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let chars = buf.iter().filter(|&&b| b & 0xC0 != 0x80).count();
+        match buf.contains(&b'\n') || chars > self.budget {
+            true => Err(std::io::ErrorKind::WriteZero.into()),
+            false => {
+                self.budget -= chars;
+                self.output.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+        }
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+//==================================================================================================
+
+/// Writes a decimal integer with underscores between groups of digits, see [`SerializeConfig::digit_separators`].
+fn write_grouped<W: Write>(dst: &mut W, int: &[u8], group: Option<usize>) -> std::io::Result<()> {
+    let (sign, digits) = int.split_at(int.starts_with(b"-") as usize);
+    match group {
+        Some(n) if n != 0 && digits.len() > n => {
+            let first = match digits.len() % n {
+                0 => n,
+                len => len,
+            };
+            dst.write_all(sign)?;
+            dst.write_all(&digits[..first])?;
+            for chunk in digits[first..].chunks(n) {
+                dst.write_all(b"_")?;
+                dst.write_all(chunk)?;
+            }
+            Ok(())
+        }
+        _ => dst.write_all(int),
+    }
+}
+
+/// Rounds `v` to `digits` significant digits, see [`SerializeConfig::significant_digits`].
+///
+/// The formatter rounds from the exact value, and the float nearest to the result has no more digits when written.
+fn round_significant<F: std::fmt::LowerExp + std::str::FromStr + Copy>(v: F, digits: usize) -> F {
+    format!("{:.*e}", digits.max(1) - 1, v).parse().unwrap_or(v)
+}
+
+/// See [`SerializeConfig::escape_invisible`].
+fn is_invisible(ch: char) -> bool {
+    matches!(ch,
+        // Bidirectional controls.
+        '\u{61c}' | '\u{200e}' | '\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}'
+        // Zero-width and other format characters, fillers and tags.
+        | '\u{ad}' | '\u{34f}' | '\u{115f}' | '\u{1160}' | '\u{17b4}' | '\u{17b5}' | '\u{180e}'
+        | '\u{200b}'..='\u{200d}' | '\u{2060}'..='\u{2064}' | '\u{206a}'..='\u{206f}' | '\u{3164}'
+        | '\u{feff}' | '\u{ffa0}' | '\u{fff9}'..='\u{fffb}' | '\u{e0000}'..='\u{e007f}'
+    ) || ch.is_whitespace()
+}
+
+/// Splits `v` into the lines of a paragraph with their start signs, if it contains newlines or is longer than `width`,
+/// or anyway if `forced`.
+///
+/// Each line of `v` is wrapped at the first space of a run of spaces, as `|` rejoins them with one space.
+/// Returns `None` if a paragraph cannot represent `v` exactly, as lines are trimmed at the end.
+#[cfg(feature = "syntax-paragraph")]
+fn paragraph_lines(v: &str, width: usize, forced: bool) -> Option<Vec<(char, &str)>> {
+    if v.is_empty() || !forced && !v.contains('\n') && v.chars().count() <= width {
+        return None;
+    }
+    if v.chars().any(|ch| ch.is_control() && !matches!(ch, '\n' | '\t')) {
+        return None;
+    }
+
+    let mut lines = Vec::new();
+    for (i, mut line) in v.split('\n').enumerate() {
+        if line.trim_end() != line {
+            return None;
+        }
+
+        let mut marker = match i {
+            0 => '|',
+            _ => '`',
+        };
+        while line.chars().count() > width {
+            let breakable = |&(j, ch): &(usize, char)| ch == ' ' && j != 0 && !line[..j].ends_with(char::is_whitespace);
+            let mut spaces = line.char_indices().filter(breakable);
+            let (mut at, _) = match spaces.next() {
+                Some(first) => first,
+                None => break,
+            };
+            for (j, _) in spaces {
+                match line[..j].chars().count() <= width {
+                    true => at = j,
+                    false => break,
+                }
+            }
+            lines.push((marker, &line[..at]));
+            line = &line[at + 1..];
+            marker = '|';
+        }
+        lines.push((marker, line));
+    }
+    Some(lines)
+}
+
+/// The number of backticks to write `v` as a raw string with, if it's no longer than escaped or anyway if `forced`,
+/// see [`SerializeConfig::raw_strings`].
+#[cfg(feature = "syntax-raw-strings")]
+fn raw_string_backticks(v: &str, forced: bool) -> Option<usize> {
+    if v.chars().any(char::is_control) {
+        return None;
+    }
+
+    // One more than the backticks after any quote inside, which would end the string early.
+    let n = 1 + v
+        .split('"')
+        .skip(1)
+        .map(|rest| rest.len() - rest.trim_start_matches('`').len())
+        .max()
+        .unwrap_or(0);
+    let escapes = v.chars().filter(|ch| matches!(ch, '"' | '\\' | '\'')).count();
+    (forced || escapes >= 2 * n).then_some(n)
+}
+
+//==================================================================================================
+
+/// Tells the shape of a value without writing anything, failing for values with elements.
+struct ShapeProbe;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Unit,
+    None,
+    /// A string or a unit variant that is an identifier, see [`SerializeConfig::ident_keys`].
+    Ident,
+    /// Any other value without elements, e.g. a number, a string, a unit variant, or `Some` of such a value.
+    Scalar,
+}
+
+macro_rules! probe_scalar {
+    ($($method:ident($($ty:ty),*);)*) => {
+        $(
+            fn $method(self, $(_: $ty),*) -> Result<Shape> {
+                Ok(Shape::Scalar)
+            }
+        )*
+    };
+}
+
+impl serde::Serializer for ShapeProbe {
+    type Ok = Shape;
+    type Error = Error;
+    type SerializeSeq = Impossible<Shape, Error>;
+    type SerializeTuple = Impossible<Shape, Error>;
+    type SerializeTupleStruct = Impossible<Shape, Error>;
+    type SerializeTupleVariant = Impossible<Shape, Error>;
+    type SerializeMap = Impossible<Shape, Error>;
+    type SerializeStruct = Impossible<Shape, Error>;
+    type SerializeStructVariant = Impossible<Shape, Error>;
+
+    fn serialize_unit(self) -> Result<Shape> {
+        Ok(Shape::Unit)
+    }
+    fn serialize_none(self) -> Result<Shape> {
+        Ok(Shape::None)
+    }
+
+    probe_scalar! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_bytes(&[u8]);
+        serialize_unit_struct(&'static str);
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Shape> {
+        match is_ident(v) {
+            true => Ok(Shape::Ident),
+            false => Ok(Shape::Scalar),
+        }
+    }
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<Shape> {
+        self.serialize_str(variant)
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Shape> {
+        value.serialize(ShapeProbe).map(|_| Shape::Scalar)
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, value: &T) -> Result<Shape> {
+        value.serialize(ShapeProbe).map(|_| Shape::Scalar)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Shape> {
+        value.serialize(ShapeProbe).map(|_| Shape::Scalar)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq> {
+        Error::raise(ErrorKind::UnexpectedToken)
+    }
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple> {
+        Error::raise(ErrorKind::UnexpectedToken)
+    }
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct> {
+        Error::raise(ErrorKind::UnexpectedToken)
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Error::raise(ErrorKind::UnexpectedToken)
+    }
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap> {
+        Error::raise(ErrorKind::UnexpectedToken)
+    }
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct> {
+        Error::raise(ErrorKind::UnexpectedToken)
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Error::raise(ErrorKind::UnexpectedToken)
+    }
+}
+
+//==================================================================================================
+
+/// The methods of [`DottedProbe`] and [`DottedLeaf`] for values other than structs,
+/// returning `$ret` for values that aren't compound and failing for compound ones.
+macro_rules! dotted_non_struct {
+    ($ret:expr) => {
+        dotted_non_struct! { $ret;
+            serialize_unit();
+            serialize_bool(bool);
+            serialize_i8(i8);
+            serialize_i16(i16);
+            serialize_i32(i32);
+            serialize_i64(i64);
+            serialize_u8(u8);
+            serialize_u16(u16);
+            serialize_u32(u32);
+            serialize_u64(u64);
+            serialize_f32(f32);
+            serialize_f64(f64);
+            serialize_char(char);
+            serialize_str(&str);
+            serialize_bytes(&[u8]);
+            serialize_none();
+            serialize_unit_struct(&'static str);
+            serialize_unit_variant(&'static str, u32, &'static str);
+        }
+
+        fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<Self::Ok> {
+            $ret
+        }
+        fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, _: &T) -> Result<Self::Ok> {
+            $ret
+        }
+        fn serialize_newtype_variant<T: ?Sized + Serialize>(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: &T,
+        ) -> Result<Self::Ok> {
+            $ret
+        }
+
+        fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq> {
+            Error::raise(ErrorKind::UnexpectedToken)
+        }
+        fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple> {
+            Error::raise(ErrorKind::UnexpectedToken)
+        }
+        fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct> {
+            Error::raise(ErrorKind::UnexpectedToken)
+        }
+        fn serialize_tuple_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeTupleVariant> {
+            Error::raise(ErrorKind::UnexpectedToken)
+        }
+        fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap> {
+            Error::raise(ErrorKind::UnexpectedToken)
+        }
+        fn serialize_struct_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeStructVariant> {
+            Error::raise(ErrorKind::UnexpectedToken)
+        }
+    };
+    ($ret:expr; $($method:ident($($ty:ty),*);)*) => {
+        $(
+            fn $method(self, $(_: $ty),*) -> Result<Self::Ok> {
+                $ret
+            }
+        )*
+    };
+}
+
+/// Finds the fields of the structs with a single field nested in a value, e.g. `["tls", "enabled"]`
+/// of `server`, without writing anything, see [`SerializeConfig::dotted_keys`].
+struct DottedProbe;
+
+impl serde::Serializer for DottedProbe {
+    type Ok = Vec<&'static str>;
+    type Error = Error;
+    type SerializeSeq = Impossible<Self::Ok, Error>;
+    type SerializeTuple = Impossible<Self::Ok, Error>;
+    type SerializeTupleStruct = Impossible<Self::Ok, Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+    type SerializeMap = Impossible<Self::Ok, Error>;
+    type SerializeStruct = DottedProbeStruct;
+    type SerializeStructVariant = Impossible<Self::Ok, Error>;
+
+    fn serialize_struct(self, _: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        match len {
+            1 => Ok(DottedProbeStruct(Vec::new())),
+            _ => Error::raise(ErrorKind::UnexpectedToken),
+        }
+    }
+
+    dotted_non_struct!(Ok(Vec::new()));
+}
+
+struct DottedProbeStruct(Vec<&'static str>);
+
+impl SerializeStruct for DottedProbeStruct {
+    type Ok = Vec<&'static str>;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        match self.0.is_empty() {
+            true => {
+                self.0.push(key);
+                self.0.extend(value.serialize(DottedProbe).unwrap_or_default());
+                Ok(())
+            }
+            false => Error::raise(ErrorKind::UnexpectedToken),
+        }
+    }
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.0)
+    }
+}
+
+/// Writes the innermost value found by [`DottedProbe`], `depth` structs deep.
+struct DottedLeaf<'a, W: Write> {
+    ser: &'a mut Serializer<W>,
+    depth: usize,
+}
+
+impl<'a, W: Write> serde::Serializer for DottedLeaf<'a, W> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    // The value was probed before, so it's serialized differently this time.
+    dotted_non_struct!(Error::raise(ErrorKind::UnexpectedToken));
+}
+
+impl<W: Write> SerializeStruct for DottedLeaf<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        let depth = self.depth;
+        self.ser.serialize_at(
+            || PathSegment::Field(key),
+            |ser| match depth {
+                1 => ser.serialize_fitted(value),
+                depth => value.serialize(DottedLeaf { ser, depth: depth - 1 }),
+            },
+        )
+    }
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+//! Settings of applications kept in a KEON file, read and written by path, e.g. from a settings screen or a console:
+//!
+//! ```no_run
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Default, Serialize, Deserialize)]
+//! struct Settings {
+//!     audio: Audio,
+//! }
+//!
+//! #[derive(Default, Serialize, Deserialize)]
+//! struct Audio {
+//!     volume: u8,
+//! }
+//!
+//! let mut store = keon::settings::Store::<Settings>::open("settings.keon")?;
+//! store.set_at("audio.volume", &80)?;
+//! assert_eq!(store.get().audio.volume, 80);
+//! store.save()?;
+//! # Ok::<_, keon::Error>(())
+//! ```
+
+use crate::{
+    de::decode_utf8, DeserializeConfig, Deserializer, Error, ErrorKind, FieldPath, PathSegment, Result,
+    SerializeConfig, SourceMap,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Tells apart temporary files of stores saving the same path at once, within a process.
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A file of settings deserialized as `T`, and its text as it's saved, kept in sync with each other.
+///
+/// The text is written by the serializer in the config of the store, and written again as a whole by each change,
+/// so comments and formatting of the file are not kept, neither when it's opened nor by [`set_at`](Self::set_at).
+/// Paths are those of the [source map](crate::Serializer::with_source_map)
+/// of the text written as such: fields separated by dots, and indices of sequences or keys of maps
+/// in brackets, e.g. `audio.volume`, `recent[0]` or `servers["eu"].port`.
+#[derive(Debug)]
+pub struct Store<T> {
+    path: PathBuf,
+    cfg: SerializeConfig,
+    de_cfg: DeserializeConfig,
+    settings: T,
+    text: String,
+    source_map: SourceMap,
+    dirty: bool,
+}
+
+impl<T: Serialize + DeserializeOwned> Store<T> {
+    /// Reads the file at `path`, or starts from `T::default()` if it does not exist, which is written when saved.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self>
+    where
+        T: Default,
+    {
+        Self::open_with(path, &SerializeConfig::comfort(), DeserializeConfig::strict())
+    }
+
+    /// Like [`open`](Self::open), writing the text with `cfg` and reading it with `de_cfg`,
+    /// which must accept what `cfg` writes, e.g. [`DeserializeConfig::dotted_keys`]
+    /// for [`SerializeConfig::dotted_keys`]. The text of the settings opened is read back once to check that.
+    pub fn open_with(path: impl AsRef<Path>, cfg: &SerializeConfig, de_cfg: DeserializeConfig) -> Result<Self>
+    where
+        T: Default,
+    {
+        let path = path.as_ref().to_path_buf();
+        let content = match fs::read(&path) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let settings = match &content {
+            Some(content) => read(decode_utf8(content)?, de_cfg)?,
+            None => T::default(),
+        };
+        let (text, source_map) = crate::to_string_with_source_map(&settings, cfg)?;
+        read::<T>(&text, de_cfg)?;
+        Ok(Self {
+            path,
+            cfg: *cfg,
+            de_cfg,
+            settings,
+            text,
+            source_map,
+            dirty: content.is_none(),
+        })
+    }
+
+    pub fn get(&self) -> &T {
+        &self.settings
+    }
+
+    pub fn into_inner(self) -> T {
+        self.settings
+    }
+
+    /// The text as it's saved.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the settings changed since they were read or saved.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Reads the value at `path` as `V`.
+    pub fn get_at<V: DeserializeOwned>(&self, path: &str) -> Result<V> {
+        read(&self.text[self.range(path)?], self.de_cfg)
+    }
+
+    /// Writes `value` at `path`, which must read back as `T` with it, otherwise nothing changes.
+    ///
+    /// The text is then written again from the settings read back, like by [`replace`](Self::replace).
+    pub fn set_at<V: ?Sized + Serialize>(&mut self, path: &str, value: &V) -> Result<()> {
+        let range = self.range(path)?;
+        let mut text = self.text.clone();
+        text.replace_range(range, &crate::to_string(value)?);
+        self.replace(read(&text, self.de_cfg)?)
+    }
+
+    /// Changes the settings through `f`, and writes the text again.
+    pub fn update<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> Result<R> {
+        let res = f(&mut self.settings);
+        let (text, source_map) = crate::to_string_with_source_map(&self.settings, &self.cfg)?;
+        self.sync(text, source_map);
+        Ok(res)
+    }
+
+    /// Replaces the settings, and writes the text again.
+    pub fn replace(&mut self, settings: T) -> Result<()> {
+        let (text, source_map) = crate::to_string_with_source_map(&settings, &self.cfg)?;
+        self.settings = settings;
+        self.sync(text, source_map);
+        Ok(())
+    }
+
+    /// Writes the text into the file atomically, so that it's either the old or the new text if interrupted:
+    /// written into a temporary file next to it first, which is then renamed over it.
+    ///
+    /// The temporary file is named after the process and a counter, so that stores saving the same path at once
+    /// don't write into each other's, and on Unix the directory is synced as well, so that the rename is durable.
+    pub fn save(&mut self) -> Result<()> {
+        let name = self.path.file_name().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("`{}` is not a file", self.path.display()),
+            )
+        })?;
+        let mut temp_name = name.to_os_string();
+        temp_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp = self.path.with_file_name(temp_name);
+
+        let written = fs::File::create(&temp).and_then(|mut file| {
+            file.write_all(self.text.as_bytes())?;
+            file.sync_all()
+        });
+        if let Err(e) = written.and_then(|()| fs::rename(&temp, &self.path)) {
+            let _ = fs::remove_file(&temp);
+            return Err(e.into());
+        }
+        #[cfg(unix)]
+        {
+            let dir = match self.path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            fs::File::open(dir)?.sync_all()?;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Reads the file again, dropping changes not saved, e.g. after it was edited by hand.
+    pub fn reload(&mut self) -> Result<()> {
+        let settings = read(decode_utf8(&fs::read(&self.path)?)?, self.de_cfg)?;
+        self.replace(settings)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn sync(&mut self, text: String, source_map: SourceMap) {
+        self.dirty |= text != self.text;
+        self.text = text;
+        self.source_map = source_map;
+    }
+
+    fn range(&self, path: &str) -> Result<std::ops::Range<usize>> {
+        self.source_map
+            .iter()
+            .find(|(written, _)| path_string(written) == path)
+            .map(|(_, range)| range.clone())
+            .ok_or_else(|| Error::new(ErrorKind::UnknownSetting(path.into())))
+    }
+}
+
+fn read<V: DeserializeOwned>(text: &str, cfg: DeserializeConfig) -> Result<V> {
+    let mut der = Deserializer::with_config(text, cfg);
+    let val = V::deserialize(&mut der)?;
+    der.finish()?;
+    Ok(val)
+}
+
+/// A path as given to [`Store`], e.g. `servers["eu"].port`.
+fn path_string(path: &FieldPath) -> String {
+    let mut s = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Field(field) if s.is_empty() => s.push_str(field),
+            PathSegment::Field(field) => {
+                s.push('.');
+                s.push_str(field);
+            }
+            PathSegment::Index(index) => s.push_str(&format!("[{}]", index)),
+            PathSegment::Key(key) => s.push_str(&format!("[{}]", key)),
+        }
+    }
+    s
+}
@@ -3,31 +3,50 @@ use std::{
     cmp::Ordering,
     collections::BTreeMap,
     hash::{Hash, Hasher},
+    ops::{Bound, RangeBounds},
+    sync::Arc,
 };
 
 /// Implementing [`Deserialize`] and [`Deserializer`] for Value.
 mod de;
+/// Implementing [`Value::deduplicate`].
+mod dedup;
 /// Implementing [`Serialize`] for Value.
 mod ser;
+/// Implementing [`Value::tracked`].
+mod tracked;
 
+pub use tracked::Tracked;
+
+/// A `Cow<'static, str>` with the `cow-strings` feature, otherwise a `String`.
+#[cfg(feature = "cow-strings")]
+pub type Str = std::borrow::Cow<'static, str>;
+/// A `Cow<'static, str>` with the `cow-strings` feature, otherwise a `String`.
+#[cfg(not(feature = "cow-strings"))]
+pub type Str = String;
 pub type ByteBuf = Vec<u8>;
 pub type Seq = Vec<Value>;
 pub type Map = BTreeMap<Value, Value>;
 
-/// Due to the limitation of [serde], enum variants cannot roundtrip via [`Value`].
-#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Due to the limitation of [serde], enum variants cannot roundtrip via [`Value`],
+/// they are read as a string of a unit variant's name, or a map from the name to the content.
+///
+/// [`Shared`](Value::Shared) is transparent: it compares, hashes and serializes as the value it holds.
+#[derive(Debug, Default, Clone)]
 pub enum Value {
     #[default]
     Unit,
     Bool(bool),
     Char(char),
     Number(Number),
-    String(String),
+    String(Str),
     Bytes(ByteBuf),
     Newtype(Box<Value>),
     Opt(Option<Box<Value>>),
     Seq(Seq),
     Map(Map),
+    /// A subtree shared with other places, produced by [`Value::deduplicate`].
+    Shared(Arc<Value>),
 }
 
 /// A wrapper for a number, can be one of `i64`, `u64` or `f64`.
@@ -70,11 +89,11 @@ impl_into! {
         v @ u32 => Value::from(v as u64),
         v @ u64 => Value::Number(Number::UInt(v)),
 
-        v @ f32 => Value::from(v as f64),
+        v @ f32 => Value::from(widen_f32(v)),
         v @ f64 => Value::Number(Number::Float(v)),
 
-        v @ &str => Value::String(v.to_string()),
-        v @ String => Value::String(v),
+        v @ &str => Value::String(str_from_string(v.to_string())),
+        v @ String => Value::String(str_from_string(v)),
 
         v @ &[u8] => Value::Bytes(v.to_vec()),
         v @ ByteBuf => Value::Bytes(v),
@@ -86,6 +105,265 @@ impl_into! {
     }
 }
 
+/// Widens through the shortest decimal reading back as the same `f32`,
+/// e.g. `2.3333` rather than `2.3333001136779785`, which would be written as such.
+fn widen_f32(v: f32) -> f64 {
+    // Infinities and `NaN` parse back too.
+    v.to_string().parse().unwrap_or(v as f64)
+}
+
+#[allow(clippy::useless_conversion)]
+fn str_from_string(s: String) -> Str {
+    s.into()
+}
+
+#[allow(clippy::useless_conversion)]
+fn str_into_string(s: Str) -> String {
+    s.into()
+}
+
+//------------------------------------------------------------------------------
+impl Value {
+    /// A string without allocation if the `cow-strings` feature is enabled.
+    pub fn from_static(s: &'static str) -> Value {
+        #[cfg(feature = "cow-strings")]
+        return Value::String(Str::Borrowed(s));
+        #[cfg(not(feature = "cow-strings"))]
+        return Value::from(s);
+    }
+
+    /// Whether this value means "nothing": `()` or `?`.
+    ///
+    /// `? ()`, `Newtype(Unit)` and empty containers are not nullish.
+    pub fn is_nullish(&self) -> bool {
+        match self {
+            Value::Shared(v) => v.is_nullish(),
+            v => matches!(v, Value::Unit | Value::Opt(None)),
+        }
+    }
+
+    /// Sees through [`Shared`](Value::Shared).
+    pub fn resolve(&self) -> &Value {
+        match self {
+            Value::Shared(v) => v.resolve(),
+            v => v,
+        }
+    }
+
+    /// Takes the value out of [`Shared`](Value::Shared), cloning it only if it is still shared.
+    pub fn into_resolved(self) -> Value {
+        match self {
+            Value::Shared(v) => Arc::try_unwrap(v).unwrap_or_else(|v| (*v).clone()).into_resolved(),
+            v => v,
+        }
+    }
+
+    /// Collapses nested options recursively, like [`Option::flatten`]:
+    /// `? ? x` becomes `? x` and `? ?` becomes `?`.
+    ///
+    /// Keys of maps are flattened as well, if two keys become equal, the one ordered later is kept.
+    pub fn flatten_options(self) -> Value {
+        match self {
+            Value::Opt(Some(v)) => match v.flatten_options() {
+                v @ Value::Opt(_) => v,
+                v => Value::Opt(Some(Box::new(v))),
+            },
+            Value::Newtype(v) => Value::Newtype(Box::new(v.flatten_options())),
+            Value::Seq(seq) => Value::Seq(seq.into_iter().map(Value::flatten_options).collect()),
+            Value::Map(map) => Value::Map(
+                map.into_iter()
+                    .map(|(k, v)| (k.flatten_options(), v.flatten_options()))
+                    .collect(),
+            ),
+            v @ Value::Shared(_) => v.into_resolved().flatten_options(),
+            v => v,
+        }
+    }
+
+    /// The number of elements if this is a sequence, seeing through [`Shared`](Value::Shared).
+    pub fn seq_len_hint(&self) -> Option<usize> {
+        match self.resolve() {
+            Value::Seq(seq) => Some(seq.len()),
+            _ => None,
+        }
+    }
+
+    /// Borrows a window of elements if this is a sequence, e.g. `value.seq_page(100..150)`.
+    ///
+    /// The range is clamped to the length, so a page past the end is empty rather than a panic.
+    pub fn seq_page<R: RangeBounds<usize>>(&self, range: R) -> Option<&[Value]> {
+        let Value::Seq(seq) = self.resolve() else {
+            return None;
+        };
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i.saturating_add(1),
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => seq.len(),
+        };
+        let end = end.min(seq.len());
+        Some(&seq[start.min(end)..end])
+    }
+
+    /// Iterates over consecutive pages of `page_size` elements if this is a sequence, the last one may be shorter.
+    ///
+    /// # Panics
+    ///
+    /// If `page_size` is zero.
+    pub fn seq_pages(&self, page_size: usize) -> Option<std::slice::Chunks<'_, Value>> {
+        match self.resolve() {
+            Value::Seq(seq) => Some(seq.chunks(page_size)),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the elements if this is a sequence, seeing through [`Shared`](Value::Shared) ones.
+    pub fn seq_iter(&self) -> Option<impl DoubleEndedIterator<Item = &Value> + ExactSizeIterator> {
+        match self.resolve() {
+            Value::Seq(seq) => Some(seq.iter().map(Value::resolve)),
+            _ => None,
+        }
+    }
+
+    /// Hashes the content independent of how it was written, without serializing it first.
+    ///
+    /// Maps are hashed in key order, and numbers by value: `1`, `1.0` and `Int(1)` hash the same,
+    /// so do `0.0` and `-0.0`, and all `NaN`s. Containers are prefixed with their lengths.
+    pub fn structural_hash<H: Hasher>(&self, state: &mut H) {
+        let tag = |state: &mut H, tag: u8| state.write_u8(tag);
+        match self {
+            Value::Unit => tag(state, 0),
+            Value::Bool(b) => {
+                tag(state, 1);
+                b.hash(state);
+            }
+            Value::Char(ch) => {
+                tag(state, 2);
+                ch.hash(state);
+            }
+            Value::Number(num) => {
+                tag(state, 3);
+                num.structural_hash(state);
+            }
+            Value::String(s) => {
+                tag(state, 4);
+                s.hash(state);
+            }
+            Value::Bytes(bytes) => {
+                tag(state, 5);
+                bytes.hash(state);
+            }
+            Value::Newtype(v) => {
+                tag(state, 6);
+                v.structural_hash(state);
+            }
+            Value::Opt(opt) => match opt {
+                None => tag(state, 7),
+                Some(v) => {
+                    tag(state, 8);
+                    v.structural_hash(state);
+                }
+            },
+            Value::Seq(seq) => {
+                tag(state, 9);
+                state.write_usize(seq.len());
+                seq.iter().for_each(|v| v.structural_hash(state));
+            }
+            Value::Map(map) => {
+                tag(state, 10);
+                state.write_usize(map.len());
+                for (k, v) in map {
+                    k.structural_hash(state);
+                    v.structural_hash(state);
+                }
+            }
+            Value::Shared(v) => v.structural_hash(state),
+        }
+    }
+
+    /// Position of the arm, by which values of different arms are ordered.
+    fn rank(&self) -> u8 {
+        match self {
+            Value::Unit => 0,
+            Value::Bool(_) => 1,
+            Value::Char(_) => 2,
+            Value::Number(_) => 3,
+            Value::String(_) => 4,
+            Value::Bytes(_) => 5,
+            Value::Newtype(_) => 6,
+            Value::Opt(_) => 7,
+            Value::Seq(_) => 8,
+            Value::Map(_) => 9,
+            Value::Shared(v) => v.rank(),
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Ordered by arm first, as declared.
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.resolve(), other.resolve()) {
+            (Value::Unit, Value::Unit) => Ordering::Equal,
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Char(a), Value::Char(b)) => a.cmp(b),
+            (Value::Number(a), Value::Number(b)) => a.cmp(b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+            (Value::Newtype(a), Value::Newtype(b)) => a.cmp(b),
+            (Value::Opt(a), Value::Opt(b)) => a.cmp(b),
+            (Value::Seq(a), Value::Seq(b)) => a.cmp(b),
+            (Value::Map(a), Value::Map(b)) => a.cmp(b),
+            (a, b) => a.rank().cmp(&b.rank()),
+        }
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let v = self.resolve();
+        state.write_u8(v.rank());
+        match v {
+            Value::Unit | Value::Shared(_) => (),
+            Value::Bool(b) => b.hash(state),
+            Value::Char(ch) => ch.hash(state),
+            Value::Number(num) => num.hash(state),
+            Value::String(s) => s.hash(state),
+            Value::Bytes(bytes) => bytes.hash(state),
+            Value::Newtype(v) => v.hash(state),
+            Value::Opt(opt) => opt.hash(state),
+            Value::Seq(seq) => seq.hash(state),
+            Value::Map(map) => map.hash(state),
+        }
+    }
+}
+
+/// Whether two sources mean the same, regardless of whitespace, comments and how literals are written,
+/// e.g. to check that a formatter or a migration tool didn't change anything but the layout.
+///
+/// Both are read as [`Value`]s and compared, so the same limitations apply: struct names and enum paths are ignored,
+/// and a unit variant equals a string of its name. Numbers of different kinds differ, e.g. `1` and `1.0`.
+pub fn semantically_equal(a: &str, b: &str) -> Result<bool> {
+    Ok(Value::from_str(a)? == Value::from_str(b)?)
+}
+
 //------------------------------------------------------------------------------
 impl Number {
     pub fn saturating_into_i64(self) -> i64 {
@@ -132,6 +410,32 @@ impl Number {
     }
 }
 
+impl Number {
+    /// Integral values, including integral floats, are hashed as `i128`.
+    fn structural_hash<H: Hasher>(self, state: &mut H) {
+        let integral = match self {
+            Number::Int(i) => Some(i as i128),
+            Number::UInt(u) => Some(u as i128),
+            Number::Float(f) if f.fract() == 0.0 && f.abs() < 2f64.powi(64) => Some(f as i128),
+            Number::Float(_) => None,
+        };
+        match (integral, self) {
+            (Some(i), _) => {
+                state.write_u8(0);
+                state.write_i128(i);
+            }
+            (None, Number::Float(f)) => {
+                state.write_u8(1);
+                state.write_u64(match f.is_nan() {
+                    true => f64::NAN.to_bits(),
+                    false => f.to_bits(),
+                });
+            }
+            (None, _) => unreachable!(),
+        }
+    }
+}
+
 impl PartialEq for Number {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -11,6 +11,16 @@ impl Value {
         from_str(s)
     }
 
+    /// See [`from_bytes`](crate::from_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        from_bytes(bytes)
+    }
+
+    /// See [`from_reader`](crate::from_reader), the input is read to the end before deserializing.
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self> {
+        from_reader(reader)
+    }
+
     /// Tries to deserialize this [`Value`] into `T`.
     pub fn into_rust<T: DeserializeOwned>(self) -> Result<T> {
         T::deserialize(self)
@@ -50,6 +60,9 @@ impl<'de> Visitor<'de> for ValueVisitor {
     fn visit_u64<E: serde::de::Error>(self, v: u64) -> StdResult<Self::Value, E> {
         Ok(Value::Number(Number::UInt(v)))
     }
+    fn visit_f32<E: serde::de::Error>(self, v: f32) -> StdResult<Self::Value, E> {
+        self.visit_f64(widen_f32(v))
+    }
     fn visit_f64<E: serde::de::Error>(self, v: f64) -> StdResult<Self::Value, E> {
         Ok(Value::Number(Number::Float(v)))
     }
@@ -65,7 +78,7 @@ impl<'de> Visitor<'de> for ValueVisitor {
         self.visit_string(v.to_string())
     }
     fn visit_string<E: serde::de::Error>(self, v: String) -> StdResult<Self::Value, E> {
-        Ok(Value::String(v))
+        Ok(Value::String(str_from_string(v)))
     }
 
     fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> StdResult<Self::Value, E> {
@@ -133,7 +146,7 @@ impl<'de> Deserializer<'de> for Value {
                 Number::UInt(u) => vis.visit_u64(u),
                 Number::Float(f) => vis.visit_f64(f),
             },
-            Value::String(s) => vis.visit_string(s),
+            Value::String(s) => vis.visit_string(str_into_string(s)),
             Value::Bytes(buf) => vis.visit_byte_buf(buf),
             Value::Newtype(obj) => vis.visit_newtype_struct(*obj),
             Value::Opt(opt) => match opt {
@@ -142,6 +155,7 @@ impl<'de> Deserializer<'de> for Value {
             },
             Value::Seq(seq) => vis.visit_seq(SeqAccessor::new(seq)),
             Value::Map(map) => vis.visit_map(MapAccessor::new(map)),
+            v @ Value::Shared(_) => v.into_resolved().deserialize_any(vis),
         }
     }
 }
@@ -0,0 +1,117 @@
+use super::*;
+use std::{collections::HashMap, mem::size_of};
+
+impl Value {
+    /// Replaces repeated subtrees with [`Shared`](Value::Shared) nodes, e.g. for tile maps or particle presets
+    /// where thousands of entries are identical. Returns an estimate of the heap bytes freed.
+    ///
+    /// Only strings, bytes and non-empty containers are shared, subtrees occurring once are left as they were.
+    /// Equality, ordering, hashing and serialization are unaffected.
+    pub fn deduplicate(&mut self) -> usize {
+        let mut interned = HashMap::new();
+        let saved = self.intern(&mut interned);
+        drop(interned);
+        self.unshare_unique();
+        saved
+    }
+
+    /// Bottom-up, so that subtrees of a duplicate have been counted when it is dropped.
+    fn intern(&mut self, interned: &mut HashMap<Value, Arc<Value>>) -> usize {
+        let mut saved = 0;
+        match self {
+            Value::Newtype(v) => saved += v.intern(interned),
+            Value::Opt(Some(v)) => saved += v.intern(interned),
+            Value::Seq(seq) => saved += seq.iter_mut().map(|v| v.intern(interned)).sum::<usize>(),
+            Value::Map(map) => {
+                // Keys cannot be mutated in place.
+                *map = core::mem::take(map)
+                    .into_iter()
+                    .map(|(mut k, mut v)| {
+                        saved += k.intern(interned) + v.intern(interned);
+                        (k, v)
+                    })
+                    .collect();
+            }
+            _ => (),
+        }
+
+        if !self.is_shareable() {
+            return saved;
+        }
+
+        match interned.get(&*self) {
+            Some(shared) => {
+                saved += self.heap_size();
+                *self = Value::Shared(shared.clone());
+            }
+            None => {
+                let shared = Arc::new(core::mem::take(self));
+                interned.insert(Value::Shared(shared.clone()), shared.clone());
+                *self = Value::Shared(shared);
+            }
+        }
+        saved
+    }
+
+    /// Unwraps [`Shared`](Value::Shared) nodes referenced only once.
+    fn unshare_unique(&mut self) {
+        if let Value::Shared(shared) = self {
+            if Arc::strong_count(shared) == 1 {
+                *self = core::mem::take(self).into_resolved();
+            }
+        }
+
+        match self {
+            Value::Newtype(v) => v.unshare_unique(),
+            Value::Opt(Some(v)) => v.unshare_unique(),
+            Value::Seq(seq) => seq.iter_mut().for_each(Value::unshare_unique),
+            Value::Map(map) => {
+                *map = core::mem::take(map)
+                    .into_iter()
+                    .map(|(mut k, mut v)| {
+                        k.unshare_unique();
+                        v.unshare_unique();
+                        (k, v)
+                    })
+                    .collect();
+            }
+            Value::Shared(shared) => {
+                if let Some(v) = Arc::get_mut(shared) {
+                    v.unshare_unique()
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn is_shareable(&self) -> bool {
+        match self {
+            Value::String(s) => !s.is_empty(),
+            Value::Bytes(bytes) => !bytes.is_empty(),
+            Value::Seq(seq) => !seq.is_empty(),
+            Value::Map(map) => !map.is_empty(),
+            Value::Newtype(_) | Value::Opt(Some(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Heap bytes owned by this value, not counting [`Shared`](Value::Shared) ones.
+    fn heap_size(&self) -> usize {
+        match self {
+            #[cfg(feature = "cow-strings")]
+            Value::String(Str::Borrowed(_)) => 0,
+            #[cfg(feature = "cow-strings")]
+            Value::String(Str::Owned(s)) => s.capacity(),
+            #[cfg(not(feature = "cow-strings"))]
+            Value::String(s) => s.capacity(),
+            Value::Bytes(bytes) => bytes.capacity(),
+            Value::Newtype(v) | Value::Opt(Some(v)) => size_of::<Value>() + v.heap_size(),
+            Value::Seq(seq) => seq.capacity() * size_of::<Value>() + seq.iter().map(Value::heap_size).sum::<usize>(),
+            Value::Map(map) => map
+                .iter()
+                .map(|(k, v)| 2 * size_of::<Value>() + k.heap_size() + v.heap_size())
+                .sum(),
+            _ => 0,
+        }
+    }
+}
@@ -37,6 +37,7 @@ impl Serialize for Value {
             },
             Value::Seq(seq) => ser.collect_seq(seq),
             Value::Map(map) => ser.collect_map(map),
+            Value::Shared(v) => v.serialize(ser),
         }
     }
 }
@@ -0,0 +1,204 @@
+use super::*;
+use crate::{FieldPath, PathSegment};
+
+impl Value {
+    /// Edits this value through a [`Tracked`] guard, which records the paths of what was changed,
+    /// e.g. for settings systems to save only what the user changed.
+    pub fn tracked(&mut self) -> Tracked<'_> {
+        Tracked {
+            value: self,
+            path: Vec::new(),
+            changes: Changes::Owned(Vec::new()),
+        }
+    }
+}
+
+/// A value being edited, see [`Value::tracked`], or a value inside it, see [`field`](Self::field) and [`index`](Self::index).
+///
+/// Paths are the ones of the source map of the serializer, see [`Serializer::with_source_map`](crate::Serializer::with_source_map):
+/// indices of sequences, and keys of maps as written by [`to_string`](crate::to_string), e.g. `"volume"` with its quotes.
+/// Options, newtypes and [`Shared`](Value::Shared) values are seen through to their maps and sequences,
+/// the latter copied if still shared.
+///
+/// Writing a value equal to the old one, as [`PartialEq`] compares them, is not a change.
+/// Changes are kept minimal: a change of a value covers everything inside it.
+#[derive(Debug)]
+pub struct Tracked<'a> {
+    value: &'a mut Value,
+    path: FieldPath,
+    changes: Changes<'a>,
+}
+
+#[derive(Debug)]
+enum Changes<'a> {
+    Owned(Vec<FieldPath>),
+    Borrowed(&'a mut Vec<FieldPath>),
+}
+
+impl<'a> Tracked<'a> {
+    pub fn get(&self) -> &Value {
+        self.value.resolve()
+    }
+
+    /// The path of this value from the one being tracked.
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// The paths changed so far anywhere in the value being tracked, in the order they were first changed.
+    pub fn changes(&self) -> &[FieldPath] {
+        match &self.changes {
+            Changes::Owned(changes) => changes,
+            Changes::Borrowed(changes) => changes,
+        }
+    }
+
+    pub fn into_changes(self) -> Vec<FieldPath> {
+        match self.changes {
+            Changes::Owned(changes) => changes,
+            Changes::Borrowed(changes) => changes.clone(),
+        }
+    }
+
+    /// Whether this value, anything inside it or what contains it was changed.
+    pub fn is_changed(&self) -> bool {
+        self.changes()
+            .iter()
+            .any(|path| path.starts_with(&self.path) || self.path.starts_with(path))
+    }
+
+    /// The value of the entry with `key` if this is a map.
+    pub fn field(&mut self, key: impl Into<Value>) -> Option<Tracked<'_>> {
+        let key = key.into();
+        let segment = key_segment(&key);
+        match self.value.resolve_mut() {
+            Value::Map(map) => map
+                .get_mut(&key)
+                .map(|value| child(value, &self.path, segment, &mut self.changes)),
+            _ => None,
+        }
+    }
+
+    /// The element at `index` if this is a sequence.
+    pub fn index(&mut self, index: usize) -> Option<Tracked<'_>> {
+        match self.value.resolve_mut() {
+            Value::Seq(seq) => seq
+                .get_mut(index)
+                .map(|value| child(value, &self.path, PathSegment::Index(index), &mut self.changes)),
+            _ => None,
+        }
+    }
+
+    /// Replaces this value, and returns the old one.
+    pub fn set(&mut self, value: impl Into<Value>) -> Value {
+        let value = value.into();
+        if *self.value != value {
+            let path = self.path.clone();
+            self.record(path);
+        }
+        std::mem::replace(self.value, value)
+    }
+
+    /// Inserts an entry if this is a map, and returns the old value of `key` if any.
+    ///
+    /// # Panics
+    ///
+    /// If this is not a map.
+    pub fn insert(&mut self, key: impl Into<Value>, value: impl Into<Value>) -> Option<Value> {
+        let (key, value) = (key.into(), value.into());
+        let Value::Map(map) = self.value.resolve_mut() else {
+            panic!("inserting into a value that is not a map")
+        };
+        let changed = map.get(&key) != Some(&value);
+        let old = map.insert(key.clone(), value);
+        if changed {
+            self.record_at(key_segment(&key));
+        }
+        old
+    }
+
+    /// Removes an entry if this is a map, and returns its value if any.
+    pub fn remove(&mut self, key: impl Into<Value>) -> Option<Value> {
+        let key = key.into();
+        let old = match self.value.resolve_mut() {
+            Value::Map(map) => map.remove(&key)?,
+            _ => return None,
+        };
+        self.record_at(key_segment(&key));
+        Some(old)
+    }
+
+    /// Appends an element if this is a sequence.
+    ///
+    /// # Panics
+    ///
+    /// If this is not a sequence.
+    pub fn push(&mut self, value: impl Into<Value>) {
+        let Value::Seq(seq) = self.value.resolve_mut() else {
+            panic!("pushing into a value that is not a sequence")
+        };
+        seq.push(value.into());
+        let index = seq.len() - 1;
+        self.record_at(PathSegment::Index(index));
+    }
+
+    /// Borrows this value mutably, which counts as a change of it as a whole,
+    /// prefer the other methods for finer changes.
+    pub fn get_mut(&mut self) -> &mut Value {
+        let path = self.path.clone();
+        self.record(path);
+        self.value
+    }
+
+    fn record_at(&mut self, segment: PathSegment) {
+        let mut path = self.path.clone();
+        path.push(segment);
+        self.record(path);
+    }
+
+    fn record(&mut self, path: FieldPath) {
+        let changes = match &mut self.changes {
+            Changes::Owned(changes) => changes,
+            Changes::Borrowed(changes) => changes,
+        };
+        if changes.iter().any(|changed| path.starts_with(changed)) {
+            return;
+        }
+        changes.retain(|changed| !changed.starts_with(&path));
+        changes.push(path);
+    }
+}
+
+fn child<'t>(
+    value: &'t mut Value,
+    path: &[PathSegment],
+    segment: PathSegment,
+    changes: &'t mut Changes<'_>,
+) -> Tracked<'t> {
+    let mut path = path.to_vec();
+    path.push(segment);
+    Tracked {
+        value,
+        path,
+        changes: Changes::Borrowed(match changes {
+            Changes::Owned(changes) => changes,
+            Changes::Borrowed(changes) => changes,
+        }),
+    }
+}
+
+fn key_segment(key: &Value) -> PathSegment {
+    // Values are always serializable.
+    PathSegment::Key(crate::to_string(key).unwrap_or_default())
+}
+
+impl Value {
+    /// Sees through options, newtypes and [`Shared`](Value::Shared), copying the latter if still shared.
+    fn resolve_mut(&mut self) -> &mut Value {
+        match self {
+            Value::Shared(v) => Arc::make_mut(v).resolve_mut(),
+            Value::Opt(Some(v)) | Value::Newtype(v) => v.resolve_mut(),
+            v => v,
+        }
+    }
+}
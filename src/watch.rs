@@ -0,0 +1,119 @@
+//! Reloading a KEON file whenever it changes.
+//!
+//! Requires the feature `notify`.
+
+use crate::{Error, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use serde::de::DeserializeOwned;
+use std::{
+    ffi::OsString,
+    io,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Watches a file until dropped, see [`watch`].
+pub struct Watcher {
+    _inner: RecommendedWatcher,
+}
+
+impl std::fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watcher").finish_non_exhaustive()
+    }
+}
+
+/// Reads the file at `path` as `T` now and whenever it changes, calling `callback` each time it deserializes,
+/// with the errors of the attempts that failed since the last call.
+///
+/// The first read happens on the calling thread before `watch` returns, so `callback` may be called there once,
+/// and the reads after changes on the thread of the watcher.
+///
+/// The directory of the file is watched rather than the file, so that editors replacing it on save are seen too.
+/// Changes that leave the content as it was read last time are skipped, and so is a file that does not exist yet.
+/// Only errors of setting up the watch are returned, errors of reading the file are passed on to the callback.
+pub fn watch<T, F>(path: impl AsRef<Path>, callback: F) -> Result<Watcher>
+where
+    T: DeserializeOwned + 'static,
+    F: FnMut(T, Vec<Error>) + Send + 'static,
+{
+    let path = path.as_ref().to_path_buf();
+    let name = path.file_name().map(OsString::from).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("`{}` is not a file", path.display()),
+        )
+    })?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let reloader = Arc::new(Mutex::new(Reloader {
+        path,
+        last: None,
+        failures: Vec::new(),
+        callback,
+        marker: PhantomData::<fn() -> T>,
+    }));
+
+    let handler = Arc::clone(&reloader);
+    let mut inner = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let mut reloader = handler.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match event {
+            Ok(event) => {
+                let relevant = matches!(event.kind, EventKind::Any | EventKind::Create(_) | EventKind::Modify(_))
+                    && event.paths.iter().any(|path| path.file_name() == Some(&name));
+                if relevant {
+                    reloader.reload();
+                }
+            }
+            Err(e) => reloader.failures.push(notify_error(e)),
+        }
+    })
+    .map_err(notify_error)?;
+    inner.watch(&dir, RecursiveMode::NonRecursive).map_err(notify_error)?;
+
+    reloader
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .reload();
+
+    Ok(Watcher { _inner: inner })
+}
+
+struct Reloader<T, F> {
+    path: PathBuf,
+    /// The content read last time, whether it deserialized or not.
+    last: Option<Vec<u8>>,
+    failures: Vec<Error>,
+    callback: F,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned, F: FnMut(T, Vec<Error>)> Reloader<T, F> {
+    fn reload(&mut self) {
+        let content = match std::fs::read(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+            Err(e) => return self.failures.push(e.into()),
+        };
+        if self.last.as_ref() == Some(&content) {
+            return;
+        }
+
+        match crate::from_bytes::<T>(&content) {
+            Ok(value) => (self.callback)(value, std::mem::take(&mut self.failures)),
+            Err(e) => self.failures.push(e),
+        }
+        self.last = Some(content);
+    }
+}
+
+fn notify_error(e: notify::Error) -> Error {
+    match e.kind {
+        notify::ErrorKind::Io(e) => e.into(),
+        _ => io::Error::other(e).into(),
+    }
+}
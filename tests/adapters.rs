@@ -0,0 +1,302 @@
+mod util;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashSet},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Tags {
+    #[serde(with = "keon::adapters::strict_set")]
+    sorted: BTreeSet<String>,
+    #[serde(with = "keon::adapters::strict_set")]
+    hashed: HashSet<u32>,
+}
+
+fn err(s: &str) -> String {
+    keon::from_str::<Tags>(s).unwrap_err().to_string()
+}
+
+#[test]
+fn strict_set() {
+    let tags = keon::from_str::<Tags>(r#"{ sorted: ["a", "b"], hashed: [1, 2, 3] }"#).unwrap();
+    assert_eq!(tags.sorted, BTreeSet::from_iter(["a".into(), "b".into()]));
+    assert_eq!(tags.hashed, HashSet::from_iter([1, 2, 3]));
+    assert_eq!(
+        keon::to_string(&Tags {
+            hashed: HashSet::new(),
+            ..tags
+        })
+        .unwrap(),
+        r#"{sorted:["a","b"],hashed:[]}"#
+    );
+
+    assert_eq!(
+        err("{\n    sorted: [\"a\", \"b\"],\n    hashed: [1, 2, 1, 3],\n}"),
+        ":3:20 (deserialize) duplicate element in set"
+    );
+    assert_eq!(
+        err(r#"{ sorted: ["a", "b", "a"], hashed: [] }"#),
+        ":1:22 (deserialize) duplicate element in set"
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Server {
+    #[serde(with = "keon::adapters::ranged::<1, 65535>")]
+    port: u32,
+    #[serde(with = "keon::adapters::ranged::<-10, 10>")]
+    nice: i8,
+}
+
+#[test]
+fn ranged() {
+    assert_eq!(
+        keon::from_str::<Server>("{ port: 8080, nice: -10 }").unwrap(),
+        Server { port: 8080, nice: -10 }
+    );
+    assert_eq!(
+        keon::to_string(&Server { port: 1, nice: 0 }).unwrap(),
+        "{port:1,nice:0}"
+    );
+
+    assert_eq!(
+        keon::from_str::<Server>("{\n    port: 70000,\n    nice: 0,\n}")
+            .unwrap_err()
+            .to_string(),
+        ":2:11 (deserialize) invalid value: integer `70000`, expected an integer within 1..=65535"
+    );
+    assert_eq!(
+        keon::from_str::<Server>("{ port: 80, nice: -11 }")
+            .unwrap_err()
+            .to_string(),
+        ":1:19 (deserialize) invalid value: integer `-11`, expected an integer within -10..=10"
+    );
+
+    // Beyond 64 bits, only from other deserializers.
+    use serde::de::{value::Error, IntoDeserializer};
+    let ranged = |i: i128| {
+        keon::adapters::ranged::<0, 10>::deserialize::<i128, _>(IntoDeserializer::<Error>::into_deserializer(i))
+    };
+    assert_eq!(
+        ranged(1 << 65).unwrap_err().to_string(),
+        "invalid value: integer `36893488147419103232`, expected an integer within 0..=10"
+    );
+    assert_eq!(
+        ranged(-1 << 65).unwrap_err().to_string(),
+        "invalid value: integer `-36893488147419103232`, expected an integer within 0..=10"
+    );
+    assert_eq!(
+        ranged(-1).unwrap_err().to_string(),
+        "invalid value: integer `-1`, expected an integer within 0..=10"
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Profile {
+    #[serde(with = "keon::adapters::nonempty_string")]
+    name: String,
+}
+
+#[test]
+fn nonempty_string() {
+    assert_eq!(
+        keon::from_str::<Profile>(r#"{ name: "Alex" }"#).unwrap(),
+        Profile { name: "Alex".into() }
+    );
+    assert_eq!(
+        keon::from_str::<Profile>(r#"{ name: "" }"#).unwrap_err().to_string(),
+        r#":1:9 (deserialize) invalid value: string "", expected a non-empty string"#
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn matching() {
+    struct Slug;
+    impl keon::adapters::Pattern for Slug {
+        const PATTERN: &'static str = "^[a-z][a-z0-9-]*$";
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Package {
+        #[serde(with = "keon::adapters::matching::<Slug>")]
+        name: String,
+    }
+
+    assert_eq!(
+        keon::from_str::<Package>(r#"{ name: "keon-2" }"#).unwrap(),
+        Package { name: "keon-2".into() }
+    );
+    assert_eq!(
+        keon::from_str::<Package>(r#"{ name: "Keon 2" }"#)
+            .unwrap_err()
+            .to_string(),
+        r#":1:9 (deserialize) invalid value: string "Keon 2", expected a string matching /^[a-z][a-z0-9-]*$/"#
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Session {
+    #[serde(with = "keon::adapters::system_time")]
+    started: SystemTime,
+    #[serde(with = "keon::adapters::duration")]
+    timeout: Duration,
+}
+
+#[test]
+fn human_time() {
+    let session = Session {
+        started: UNIX_EPOCH + Duration::new(1736172788, 855221200),
+        timeout: Duration::from_millis(90_250),
+    };
+
+    util::rt_min(&session, r#"{started:"2025-01-06T14:13:08.8552212Z",timeout:"90.25s"}"#).unwrap();
+
+    // Compatible with the struct forms.
+    util::backward(
+        &session,
+        "{
+            started: (SystemTime) { secs_since_epoch: 1736172788, nanos_since_epoch: 855221200 },
+            timeout: (Duration) { secs: 90, nanos: 250000000 },
+        }",
+    )
+    .unwrap();
+
+    util::backward(
+        &session,
+        r#"{ started: "2025-01-06T22:13:08.8552212+08:00", timeout: "1m 30s 250ms" }"#,
+    )
+    .unwrap();
+
+    let before_epoch = Session {
+        started: UNIX_EPOCH - Duration::new(86400 * 366, 500_000_000),
+        timeout: Duration::ZERO,
+    };
+    util::rt_min(&before_epoch, r#"{started:"1968-12-30T23:59:59.5Z",timeout:"0s"}"#).unwrap();
+
+    // Years beyond four digits.
+    let far = |secs| Session {
+        started: UNIX_EPOCH + Duration::from_secs(secs),
+        timeout: Duration::ZERO,
+    };
+    util::rt_min(&far(253402300799), r#"{started:"9999-12-31T23:59:59Z",timeout:"0s"}"#).unwrap();
+    util::rt_min(&far(253402300800), r#"{started:"+10000-01-01T00:00:00Z",timeout:"0s"}"#).unwrap();
+    let before_year_zero = Session {
+        started: UNIX_EPOCH - Duration::from_secs(62167219200 + 86400 * 365),
+        timeout: Duration::ZERO,
+    };
+    util::rt_min(&before_year_zero, r#"{started:"-0001-01-01T00:00:00Z",timeout:"0s"}"#).unwrap();
+    assert!(keon::from_str::<Session>(r#"{ started: "10000-01-01T00:00:00Z", timeout: "1s" }"#).is_err());
+    assert!(keon::from_str::<Session>(r#"{ started: "+999-01-01T00:00:00Z", timeout: "1s" }"#).is_err());
+    assert!(keon::from_str::<Session>(r#"{ started: "+9999999999999-01-01T00:00:00Z", timeout: "1s" }"#).is_err());
+    // Seconds beyond `i64`, where the days alone fit or not.
+    for started in [
+        "+999999999999-12-31T23:59:59+01:00",
+        "-999999999999-01-01T00:00:00-01:00",
+        "+292277026596-12-04T23:59:59-23:59",
+        "-292277022657-01-28T00:00:00+23:59",
+    ] {
+        let s = format!(r#"{{ started: "{}", timeout: "1s" }}"#, started);
+        assert!(keon::from_str::<Session>(&s).is_err(), "{}", started);
+    }
+
+    assert!(keon::from_str::<Session>(r#"{ started: "2025-02-29T00:00:00Z", timeout: "1s" }"#).is_err());
+    assert!(keon::from_str::<Session>(r#"{ started: "2024-02-29T00:00:00Z", timeout: "1 fortnight" }"#).is_err());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Tile {
+    name: String,
+    #[serde(serialize_with = "count_renders")]
+    solid: bool,
+}
+
+static RENDERS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn count_renders<S: serde::Serializer>(v: &bool, ser: S) -> Result<S::Ok, S::Error> {
+    RENDERS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    v.serialize(ser)
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+struct SharedTile(#[serde(with = "keon::adapters::shared")] std::sync::Arc<Tile>);
+
+#[test]
+fn shared() {
+    use keon::{Expand, SerializeConfig, SerializeConfigBuilder, Serializer};
+    use std::{collections::BTreeMap, sync::Arc};
+
+    let serialize = |value: &BTreeMap<String, Vec<SharedTile>>, cfg: SerializeConfigBuilder, memoize| {
+        RENDERS.store(0, std::sync::atomic::Ordering::Relaxed);
+        let mut buf = Vec::new();
+        let mut ser = Serializer::new(&mut buf, cfg.memoize_shared(memoize).build());
+        value.serialize(&mut ser).unwrap();
+        (
+            String::from_utf8(buf).unwrap(),
+            RENDERS.load(std::sync::atomic::Ordering::Relaxed),
+        )
+    };
+
+    let grass = Arc::new(Tile {
+        name: "grass".into(),
+        solid: false,
+    });
+    let stone = Arc::new(Tile {
+        name: "stone".into(),
+        solid: true,
+    });
+    let row = || {
+        [&grass, &stone, &grass, &grass]
+            .map(|tile| SharedTile(tile.clone()))
+            .into()
+    };
+    let map = BTreeMap::from([("a".to_string(), row()), ("b".to_string(), row())]);
+
+    // Values too wide for the line width are written again, after the probe gave up.
+    for (cfg, renders, memoized_renders) in [
+        (SerializeConfig::builder(), 8, 2),
+        (SerializeConfig::minimal().into(), 8, 2),
+        (SerializeConfig::builder().expand(Expand::UntilDepth(2)), 8, 2),
+        (SerializeConfig::builder().line_width(Some(40)), 16, 5),
+    ] {
+        let (plain, n) = serialize(&map, cfg, false);
+        assert_eq!(n, renders, "{plain}");
+        let (memoized, n) = serialize(&map, cfg, true);
+        assert_eq!(memoized, plain);
+        assert_eq!(n, memoized_renders, "{plain}");
+        assert_eq!(
+            keon::from_str::<BTreeMap<String, Vec<SharedTile>>>(&memoized).unwrap(),
+            map
+        );
+    }
+}
+
+#[test]
+fn shared_zero_sized() {
+    use keon::SerializeConfig;
+
+    // Boxes of zero-sized values have the same address, whatever the type.
+    #[derive(Serialize)]
+    struct UnitA;
+    #[derive(Serialize)]
+    struct UnitB;
+    #[derive(Serialize)]
+    struct Units {
+        #[serde(with = "keon::adapters::shared")]
+        a: Box<UnitA>,
+        #[serde(with = "keon::adapters::shared")]
+        b: Box<UnitB>,
+    }
+
+    let units = Units {
+        a: Box::new(UnitA),
+        b: Box::new(UnitB),
+    };
+    let cfg = SerializeConfig::builder().memoize_shared(true).build();
+    assert_eq!(
+        keon::to_string_with(&units, &cfg).unwrap(),
+        "(Units) {\n    a: (UnitA),\n    b: (UnitB),\n}"
+    );
+}
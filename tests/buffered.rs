@@ -0,0 +1,90 @@
+use keon::{SerializeConfig, Serializer};
+use serde::Serialize;
+use std::io;
+
+/// Counts calls of `write`, and runs out of space after `budget` bytes.
+#[derive(Default)]
+struct Device {
+    written: Vec<u8>,
+    writes: usize,
+    budget: Option<usize>,
+}
+
+impl io::Write for Device {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writes += 1;
+        let len = match &mut self.budget {
+            Some(0) => return Err(io::Error::other("disk full")),
+            Some(budget) => {
+                let len = buf.len().min(*budget);
+                *budget -= len;
+                len
+            }
+            None => buf.len(),
+        };
+        self.written.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn value() -> Vec<(u32, String)> {
+    (0..1000).map(|i| (i, format!("#{}", i))).collect()
+}
+
+fn serialize<T: Serialize>(value: &T, dst: &mut Device, buffer: usize) -> keon::Result<()> {
+    let mut ser = Serializer::new(dst, SerializeConfig::comfort()).with_buffer(buffer);
+    value.serialize(&mut ser)?;
+    ser.flush()
+}
+
+#[test]
+fn to_writer() {
+    let mut dst = Device::default();
+    keon::to_writer_pretty(&mut dst, &value()).unwrap();
+    assert_eq!(
+        String::from_utf8(dst.written).unwrap(),
+        keon::to_string_pretty(&value()).unwrap()
+    );
+    assert!(dst.writes < 10, "{}", dst.writes);
+}
+
+#[test]
+fn with_buffer() {
+    let mut unbuffered = Device::default();
+    serialize(&value(), &mut unbuffered, 0).unwrap();
+    assert!(unbuffered.writes > 5000);
+
+    for buffer in [1, 7, 64, 1 << 20] {
+        let mut buffered = Device::default();
+        serialize(&value(), &mut buffered, buffer).unwrap();
+        assert_eq!(buffered.written, unbuffered.written);
+    }
+
+    // Nothing is written until flushed.
+    let mut dst = Device::default();
+    let mut ser = Serializer::new(&mut dst, SerializeConfig::comfort()).with_buffer(1 << 20);
+    value().serialize(&mut ser).unwrap();
+    let dst = ser.into_inner().unwrap();
+    assert_eq!(dst.writes, 1);
+}
+
+#[test]
+fn located_errors() {
+    // The same as unbuffered, wherever the buffer was when it ran out of space.
+    for budget in [0, 1, 100, 1234, 5000] {
+        let mut errors = [0, 16, 1000].map(|buffer| {
+            let mut dst = Device {
+                budget: Some(budget),
+                ..Default::default()
+            };
+            let e = serialize(&value(), &mut dst, buffer).unwrap_err();
+            (e.line, e.col, dst.written.len())
+        });
+        errors.sort();
+        assert_eq!(errors[0], errors[2], "{}", budget);
+        assert_eq!(errors[0].2, budget);
+    }
+}
@@ -0,0 +1,47 @@
+use keon::{build, ErrorKind};
+use serde::Deserialize;
+use std::{fs, panic};
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Level {
+    name: String,
+    size: (u8, u8),
+}
+
+#[test]
+fn validate_dir() {
+    let dir = std::env::temp_dir().join(format!("keon-build-{}", std::process::id()));
+    fs::create_dir_all(dir.join("extra")).unwrap();
+    fs::write(dir.join("a.keon"), "{ name: \"A\", size: (8, 8) }").unwrap();
+    fs::write(dir.join("b.keon"), "{\n\tname: \"B\" (4, 4),\n}").unwrap();
+    fs::write(dir.join("notes.txt"), "not KEON").unwrap();
+    fs::write(dir.join("extra/c.keon"), "{ name: \"C\", size: 4 }").unwrap();
+
+    let failed = build::check_dir::<Level>(&dir).unwrap();
+    assert_eq!(
+        failed
+            .iter()
+            .map(|f| f.path.strip_prefix(&dir).unwrap())
+            .collect::<Vec<_>>(),
+        [std::path::Path::new("b.keon"), "extra/c.keon".as_ref()]
+    );
+    assert_eq!(failed[0].error.kind, ErrorKind::ExpectedComma);
+    assert_eq!(
+        failed[0].to_string(),
+        format!(
+            "error: expected comma\n  --> {}:2:12\n  |\n2 | \tname: \"B\" (4, 4),\n  | \t          ^\n",
+            failed[0].path.display()
+        )
+    );
+
+    fs::remove_file(dir.join("b.keon")).unwrap();
+    let panicked = panic::catch_unwind(|| build::validate_dir::<Level>(&dir)).unwrap_err();
+    let message = panicked.downcast_ref::<String>().unwrap();
+    assert!(message.starts_with("1 of the KEON assets in"));
+    assert!(message.contains("c.keon:1:"));
+
+    fs::remove_file(dir.join("extra/c.keon")).unwrap();
+    build::validate_dir::<Level>(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+}
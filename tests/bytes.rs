@@ -12,8 +12,11 @@ fn roundtrips() {
         "(Bytes)(b\"\\x01\\x02!\\\"\\x7f\\x80\")",
     )
     .unwrap();
+    util::rt_pre(&Bytes(br"C:\".to_vec()), r#"(Bytes)(b"C:\\")"#).unwrap();
 
+    #[cfg(feature = "syntax-basexx")]
     util::rt_min(&Bytes(b"".to_vec()), r#"%b64"""#).unwrap();
+    #[cfg(feature = "syntax-basexx")]
     util::rt_min(&Bytes(b"\x01\x02\x21\x22\x7f\x80".to_vec()), r#"%b64"AQIhIn-A""#).unwrap();
 }
 
@@ -24,7 +27,144 @@ fn backwards() {
         r#"%b"\x01\x02!\"\x7f\x80""#,
     )
     .unwrap();
+    #[cfg(feature = "syntax-basexx")]
     util::backward(&Bytes(b"\x01\x02\x21\x22\x7f\x80".to_vec()), r#"%b64"AQIhIn-A""#).unwrap();
+    #[cfg(feature = "syntax-basexx")]
     util::backward(&Bytes(b"\x01\x02\x21\x22\x7f\x80".to_vec()), r#"%b32"AEBCCIT7QA""#).unwrap();
+    #[cfg(feature = "syntax-basexx")]
     util::backward(&Bytes(b"\x01\x02\x21\x22\x7f\x80".to_vec()), r#"%b16"010221227F80""#).unwrap();
 }
+
+#[test]
+#[cfg(all(feature = "syntax-raw-strings", feature = "syntax-basexx"))]
+fn max_bytes_len() {
+    use keon::{DeserializeConfig, Deserializer, ErrorKind};
+
+    let limited = |s: &str| {
+        let mut cfg = DeserializeConfig::strict();
+        cfg.max_bytes_len = 4;
+        let mut der = Deserializer::with_config(s, cfg);
+        Bytes::deserialize(&mut der).map(|b| b.0)
+    };
+
+    assert_eq!(limited(r#"%b"abcd""#).unwrap(), b"abcd");
+    assert_eq!(limited(r#"%b`"abcd"`"#).unwrap(), b"abcd");
+    assert_eq!(limited(r#"%b16"61626364""#).unwrap(), b"abcd");
+    assert_eq!(limited(r#"%b32"MFRGGZA""#).unwrap(), b"abcd");
+    assert_eq!(limited(r#"%b64"YWJjZA""#).unwrap(), b"abcd");
+
+    for s in [
+        r#"%b"abcde""#,
+        r#"%b"\x00\x01\x02\x03\x04""#,
+        r#"%b`"abcde"`"#,
+        r#"%b16"6162636465""#,
+        r#"%b32"MFRGGZDF""#,
+        r#"%b64"YWJjZGU""#,
+    ] {
+        let e = limited(s).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::ExceededBytesLimit(4), "{}", s);
+        assert_eq!(
+            e.to_string(),
+            ":1:2 bytes literal exceeds the limit of 4 bytes",
+            "{}",
+            s
+        );
+    }
+
+    // Checked before decoding, a huge literal is rejected as soon as its length is known.
+    let huge = format!("%b64\"{}\"", "A".repeat(1 << 24));
+    assert_eq!(limited(&huge).unwrap_err().kind, ErrorKind::ExceededBytesLimit(4));
+
+    // Unlimited by default.
+    let huge = format!("%b64\"{}\"", "qqqq".repeat(1 << 18));
+    assert_eq!(keon::from_str::<Bytes>(&huge).unwrap().0.len(), 3 << 18);
+}
+
+#[test]
+#[cfg(feature = "syntax-basexx")]
+fn wrapped() {
+    use keon::{BytesFlavor, SerializeConfig, Serializer};
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Blob {
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+        #[serde(with = "serde_bytes")]
+        tiny: Vec<u8>,
+    }
+
+    let blob = Blob {
+        data: (0..=20).collect(),
+        tiny: b"abc".to_vec(),
+    };
+    let to_string = |flavor| {
+        let cfg = SerializeConfig::builder()
+            .bytes_flavor(flavor)
+            .bytes_wrap_width(Some(8))
+            .build();
+        let mut buf = Vec::new();
+        blob.serialize(&mut Serializer::new(&mut buf, cfg)).unwrap();
+        String::from_utf8(buf).unwrap()
+    };
+
+    let pretty = to_string(BytesFlavor::Base64);
+    assert_eq!(
+        pretty,
+        "(Blob) {\n    data: b64\"AAECAwQF\n        BgcICQoL\n        DA0ODxAR\n        EhMU\",\n    tiny: b64\"YWJj\",\n}"
+    );
+    assert_eq!(keon::from_str::<Blob>(&pretty).unwrap(), blob);
+
+    let pretty = to_string(BytesFlavor::Base16);
+    assert!(pretty.contains("b16\"00010203\n        04050607\n"), "{}", pretty);
+    assert_eq!(keon::from_str::<Blob>(&pretty).unwrap(), blob);
+    assert_eq!(keon::from_str::<Blob>(&to_string(BytesFlavor::Base32)).unwrap(), blob);
+
+    // Any whitespace is skipped, but not other characters.
+    util::backward(&Bytes(b"abcd".to_vec()), "%b64\" YWJj\r\n\tZA \"").unwrap();
+    assert!(keon::from_str::<Bytes>("%b64\"YWJj,ZA\"").is_err());
+}
+
+#[test]
+#[cfg(feature = "syntax-basexx")]
+fn per_field() {
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Blob {
+        #[serde(with = "keon::bytes::base16")]
+        digest: [u8; 4],
+        #[serde(with = "keon::bytes::normal")]
+        magic: Vec<u8>,
+        #[serde(with = "keon::bytes::base32")]
+        key: Box<[u8]>,
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+    }
+    let blob = Blob {
+        digest: *b"\x7fELF",
+        magic: b"\x7fELF".to_vec(),
+        key: b"\x7fELF".to_vec().into(),
+        data: b"\x7fELF".to_vec(),
+    };
+
+    let s = keon::to_string(&blob).unwrap();
+    assert_eq!(
+        s,
+        r#"{digest:b16"7F454C46",magic:b"\x7fELF",key:b32"P5CUYRQ",data:b64"f0VMRg"}"#
+    );
+    assert_eq!(keon::from_str::<Blob>(&s).unwrap(), blob);
+
+    // Any flavor reads back, the length still has to fit.
+    let s = r#"{digest:b"\x7fELF",magic:b16"7F",key:b"",data:b""}"#;
+    assert_eq!(keon::from_str::<Blob>(s).unwrap().magic, b"\x7f");
+    let err = keon::from_str::<Blob>(r#"{digest:b16"7F",magic:b"",key:b"",data:b""}"#).unwrap_err();
+    assert!(err.to_string().contains("invalid length 1"), "{}", err);
+
+    // Others see a newtype struct of bytes.
+    assert_eq!(
+        serde_json::to_string(&blob).unwrap(),
+        r#"{"digest":[127,69,76,70],"magic":[127,69,76,70],"key":[127,69,76,70],"data":[127,69,76,70]}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<Blob>(&serde_json::to_string(&blob).unwrap()).unwrap(),
+        blob
+    );
+}
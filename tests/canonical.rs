@@ -0,0 +1,96 @@
+mod util;
+
+use keon::{Expand, SerializeConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use util::ser_with;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Side {
+    Left,
+    Right { by: f32 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Document {
+    name: String,
+    ratio: f64,
+    side: Side,
+    #[serde(with = "serde_bytes")]
+    digest: Vec<u8>,
+    labels: HashMap<String, u32>,
+}
+
+#[test]
+fn canonical() {
+    let doc = |labels: &[(&str, u32)]| Document {
+        name: "caf\u{e9}\n".into(),
+        ratio: 0.1,
+        side: Side::Right { by: -1.5 },
+        digest: vec![0x7f, b'E', b'L', b'F'],
+        labels: labels.iter().map(|&(k, v)| (k.to_string(), v)).collect(),
+    };
+
+    let s = ser_with(
+        &doc(&[("zeta", 1), ("alpha", 2), ("mu", 3)]),
+        SerializeConfig::canonical(),
+    );
+    assert_eq!(
+        s,
+        r#"{name:"café\n",ratio:0x1.999999999999ap-4,side:Right{by:-0x1.8p0},digest:b"\x7fELF",labels:{"alpha"=>2,"mu"=>3,"zeta"=>1}}"#
+    );
+    for labels in [
+        [("mu", 3), ("zeta", 1), ("alpha", 2)],
+        [("alpha", 2), ("mu", 3), ("zeta", 1)],
+    ] {
+        assert_eq!(ser_with(&doc(&labels), SerializeConfig::canonical()), s);
+    }
+    assert_eq!(
+        keon::from_str::<Document>(&s).unwrap(),
+        doc(&[("zeta", 1), ("alpha", 2), ("mu", 3)])
+    );
+
+    // Equal, but written apart as they are read back apart.
+    assert_eq!(ser_with(&[0.0, -0.0], SerializeConfig::canonical()), "(0x0p0,-0x0p0)");
+    let zeros = keon::from_str::<(f64, f64)>("(0x0p0,-0x0p0)").unwrap();
+    assert!(zeros.0.is_sign_positive() && zeros.1.is_sign_negative());
+
+    // Every control character is escaped, including ESC.
+    assert_eq!(ser_with("\x1b[0m", SerializeConfig::canonical()), r#""\x1b[0m""#);
+    assert_eq!(ser_with(&'\x1f', SerializeConfig::canonical()), r"'\x1f'");
+    assert_eq!(keon::from_str::<String>(r#""\x1b[0m""#).unwrap(), "\x1b[0m");
+}
+
+#[test]
+fn sort_keys() {
+    let map = HashMap::from([(10, "ten"), (9, "nine"), (100, "hundred")]);
+    let cfg = SerializeConfig::builder().sort_keys(true).build();
+
+    // Keys are sorted as written.
+    assert_eq!(
+        ser_with(
+            &map,
+            SerializeConfig::builder()
+                .sort_keys(true)
+                .expand(Expand::UntilDepth(0))
+                .build()
+        ),
+        r#"{10=>"ten",100=>"hundred",9=>"nine"}"#
+    );
+
+    // The same as a sorted map where pretty, including nested maps and values fitted within the line width.
+    let nested = HashMap::from([
+        ("b", HashMap::from([("y", vec![1, 2]), ("x", vec![3])])),
+        ("a", HashMap::from([("z", vec![4; 20])])),
+    ]);
+    let sorted = nested
+        .iter()
+        .map(|(k, v)| (*k, v.iter().collect::<BTreeMap<_, _>>()))
+        .collect::<BTreeMap<_, _>>();
+    for cfg in [
+        cfg,
+        SerializeConfig::builder().sort_keys(true).line_width(Some(40)).build(),
+    ] {
+        assert_eq!(ser_with(&nested, cfg), ser_with(&sorted, cfg));
+    }
+}
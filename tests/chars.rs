@@ -5,9 +5,25 @@ fn roundtrips() {
     util::rt_min(&'a', "'a'").unwrap();
     util::rt_min(&'\n', "'\\n'").unwrap();
     util::rt_min(&'\0', "'\\0'").unwrap();
+    util::rt_min(&'\\', "'\\\\'").unwrap();
     util::rt_min(&'\x08', "'\\x08'").unwrap();
     util::rt_min(&'\u{11}', "'\\x11'").unwrap();
     util::rt_min(&'\u{3000}', "'\u{3000}'").unwrap();
     util::rt_min(&'\u{2731}', "'✱'").unwrap();
     util::rt_min(&'✱', "'✱'").unwrap();
 }
+
+#[test]
+fn too_many() {
+    use keon::ErrorKind;
+
+    for s in ["'ab'", "'a\u{4E2D}'", "'\u{4E2D}a'", "'a\\n'", "'\\na'"] {
+        assert_eq!(
+            keon::from_str::<char>(s).unwrap_err().kind,
+            ErrorKind::InvalidCharacterTooMany,
+            "{}",
+            s
+        );
+    }
+    assert_eq!(keon::from_str::<char>("'a").unwrap_err().kind, ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "clap")]
+
+use clap::{CommandFactory, FromArgMatches, Parser};
+use keon::{
+    cli::{self, Source},
+    Value,
+};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+struct Args {
+    #[arg(long, id = "server.host")]
+    host: Option<String>,
+    #[arg(long, id = "server.port", default_value_t = 8080)]
+    port: u16,
+    #[arg(long)]
+    verbose: bool,
+    #[arg(long = "include")]
+    include: Vec<PathBuf>,
+    #[arg(long, id = "log-level")]
+    log_level: Option<u8>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    name: String,
+    server: Server,
+    verbose: bool,
+    #[serde(default)]
+    include: Vec<PathBuf>,
+    log_level: Option<u8>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+fn merge(cmd: clap::Command, config: &str, argv: &[&str]) -> cli::Merged {
+    let matches = cmd.clone().try_get_matches_from(argv).unwrap();
+    Args::from_arg_matches(&matches).unwrap();
+    cli::merge(Value::from_str(config).unwrap(), &cmd, &matches)
+}
+
+#[test]
+fn precedence() {
+    let config = r#"{ name: "app", server: { host: "localhost" }, verbose: true }"#;
+
+    let merged = merge(Args::command(), config, &["app"]);
+    assert_eq!(merged.source("name"), Some(Source::File));
+    assert_eq!(merged.source("server.host"), Some(Source::File));
+    assert_eq!(merged.source("server.port"), Some(Source::Default));
+    assert_eq!(merged.source("verbose"), Some(Source::File));
+    assert_eq!(merged.source("log_level"), None);
+    assert_eq!(merged.source("server"), None);
+    assert_eq!(
+        merged.into_rust::<Config>().unwrap(),
+        Config {
+            name: "app".into(),
+            server: Server {
+                host: "localhost".into(),
+                port: 8080,
+            },
+            verbose: true,
+            include: vec![],
+            log_level: None,
+        }
+    );
+
+    let merged = merge(
+        Args::command(),
+        r#"{ name: "app", server: { host: "localhost", port: 80 }, verbose: false }"#,
+        &["app", "--port", "443", "--host", "123", "--verbose"],
+    );
+    assert_eq!(merged.get("server.port"), Some(&Value::from(443u64)));
+    // Strings are taken verbatim, even if they read as other values.
+    assert_eq!(merged.get("server.host"), Some(&Value::from("123")));
+    assert_eq!(merged.source("server.port"), Some(Source::CommandLine));
+    assert_eq!(merged.source("verbose"), Some(Source::CommandLine));
+    assert_eq!(merged.source("name"), Some(Source::File));
+}
+
+#[test]
+fn sequences_and_env() {
+    std::env::set_var("KEON_TEST_CLI_LOG_LEVEL", "3");
+    let cmd = Args::command().mut_arg("log-level", |arg| arg.env("KEON_TEST_CLI_LOG_LEVEL"));
+    let merged = merge(
+        cmd,
+        r#"{ name: "app", server: { host: "localhost" }, include: ["a"], log_level: ? 1 }"#,
+        &["app", "--include", "b", "--include", "c"],
+    );
+    assert_eq!(merged.source("log_level"), Some(Source::Env));
+    assert_eq!(merged.source("include"), Some(Source::CommandLine));
+    assert_eq!(
+        merged.sources().collect::<Vec<_>>(),
+        [
+            ("include", Source::CommandLine),
+            ("log_level", Source::Env),
+            ("name", Source::File),
+            ("server.host", Source::File),
+            ("server.port", Source::Default),
+            ("verbose", Source::Default),
+        ]
+    );
+
+    let config = merged.into_rust::<Config>().unwrap();
+    assert_eq!(config.include, [PathBuf::from("b"), PathBuf::from("c")]);
+    assert_eq!(config.log_level, Some(3));
+}
+
+#[test]
+fn nested_replacement() {
+    // A whole map from the file is replaced, the value at its path takes its source.
+    let mut merged = cli::Merged::new(Value::from_str(r#"{ server: { host: "a", port: 1 } }"#).unwrap());
+    let cmd = clap::Command::new("app").arg(clap::Arg::new("server").long("server"));
+    let matches = cmd.clone().try_get_matches_from(["app", "--server", "off"]).unwrap();
+    merged.apply(&cmd, &matches);
+
+    assert_eq!(merged.get("server"), Some(&Value::from("off")));
+    assert_eq!(merged.source("server"), Some(Source::CommandLine));
+    assert_eq!(merged.source("server.host"), None);
+    assert_eq!(merged.sources().count(), 1);
+}
@@ -0,0 +1,100 @@
+mod util;
+
+use keon::{EnumPaths, Newline, SerializeConfig, SerializeConfigBuilder, Serializer};
+use serde::{Deserialize, Serialize};
+use util::ser_with;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Save {
+    side: Side,
+    single: (u8,),
+    speed: f64,
+}
+
+const SAVE: Save = Save {
+    side: Side::Right,
+    single: (1,),
+    speed: 12.0,
+};
+
+#[test]
+fn conveniences() {
+    let cfg = SerializeConfig::builder().newline(Newline::CrLf).build();
+    let s = keon::to_string_with(&SAVE, &cfg).unwrap();
+    let mut buf = Vec::new();
+    SAVE.serialize(&mut Serializer::new(&mut buf, cfg)).unwrap();
+    assert_eq!(buf, s.as_bytes());
+
+    let mut buf = Vec::new();
+    keon::to_writer_with(&mut buf, &SAVE, &cfg).unwrap();
+    assert_eq!(buf, s.as_bytes());
+    assert_eq!(keon::from_str::<Save>(&s).unwrap(), SAVE);
+
+    assert_eq!(keon::to_vec(&SAVE).unwrap(), keon::to_string(&SAVE).unwrap().as_bytes());
+    assert_eq!(
+        keon::to_vec_pretty(&SAVE).unwrap(),
+        keon::to_string_pretty(&SAVE).unwrap().as_bytes()
+    );
+}
+
+#[test]
+fn defaults() {
+    let cfg = SerializeConfig::builder().build();
+    assert_eq!(ser_with(&SAVE, cfg), keon::to_string_pretty(&SAVE).unwrap());
+
+    let cfg = SerializeConfigBuilder::from(SerializeConfig::minimal()).build();
+    assert_eq!(ser_with(&SAVE, cfg), keon::to_string(&SAVE).unwrap());
+}
+
+#[test]
+fn knobs() {
+    let cfg = SerializeConfig::builder()
+        .indent_width(2)
+        .struct_names(false)
+        .enum_paths(EnumPaths::Never)
+        .trailing_commas(false)
+        .hex_floats(true)
+        .build();
+    let s = ser_with(&SAVE, cfg);
+    assert_eq!(s, "{\n  side: Right,\n  single: (\n    1,\n  ),\n  speed: 0x1.8p3\n}");
+    assert_eq!(keon::from_str::<Save>(&s).unwrap(), SAVE);
+
+    let floats = vec![0.5f32, -0.1f32];
+    let cfg = SerializeConfigBuilder::from(SerializeConfig::minimal())
+        .hex_floats(true)
+        .build();
+    assert_eq!(ser_with(&floats, cfg), "[0x1p-1,-0x1.99999ap-4]");
+    assert_eq!(ser_with(&floats, SerializeConfig::minimal()), "[0.5,-0.1]");
+}
+
+#[test]
+fn enum_paths() {
+    let cfg = SerializeConfigBuilder::from(SerializeConfig::minimal())
+        .enum_paths(EnumPaths::Always)
+        .build();
+    let s = ser_with(&SAVE, cfg);
+    assert_eq!(s, "{side:Side::Right,single:(1,),speed:12.0}");
+    assert_eq!(keon::from_str::<Save>(&s).unwrap(), SAVE);
+
+    let sides = [Some(Side::Left), None];
+    assert_eq!(ser_with(&sides, cfg), "(?Side::Left,?)");
+    assert_eq!(ser_with(&sides, SerializeConfig::minimal()), "(?Left,?)");
+}
+
+#[test]
+fn crlf() {
+    let cfg = SerializeConfig::builder().newline(Newline::CrLf).build();
+    let s = ser_with(&SAVE, cfg);
+    assert_eq!(
+        s,
+        "(Save) {\r\n    side: Side::Right,\r\n    single: (\r\n        1,\r\n    ),\r\n    speed: 12.0,\r\n}"
+    );
+    assert_eq!(s.replace("\r\n", "\n"), keon::to_string_pretty(&SAVE).unwrap());
+    assert_eq!(keon::from_str::<Save>(&s).unwrap(), SAVE);
+}
@@ -0,0 +1,87 @@
+use keon::SerializeConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Dot,
+    Circle(f64),
+    Rect(u32, u32),
+    Path { points: Vec<(i32, i32)> },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Meters(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Marker;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Scene {
+    title: String,
+    scale: f64,
+    offset: i8,
+    visible: bool,
+    initial: char,
+    shapes: Vec<Shape>,
+    depth: Meters,
+    marker: Marker,
+    parent: Option<Box<Scene>>,
+    names: BTreeMap<u8, String>,
+    pair: (u8,),
+}
+
+const SCENE: &str = keon::const_str!(Scene {
+    title: "Hello, \"world\"",
+    scale: 1.5,
+    offset: -3,
+    visible: true,
+    initial: 'h',
+    shapes: [
+        Shape::Dot,
+        Shape::Circle(0.5),
+        Shape::Rect(2, 3),
+        self::Shape::Path { points: [(0, 0), (1, -1)] },
+    ],
+    depth: Meters(10),
+    marker: Marker,
+    parent: Some(Scene {
+        title: "",
+        scale: 1e3,
+        offset: 0,
+        visible: false,
+        initial: '\n',
+        shapes: [],
+        depth: Meters(0),
+        marker: Marker,
+        parent: None,
+        names: {},
+        pair: (0,),
+    }),
+    names: { 1 => "one", 2 => "two" },
+    pair: (7,),
+});
+
+#[test]
+fn const_str() {
+    let scene = keon::from_str::<Scene>(SCENE).unwrap();
+    assert_eq!(
+        scene.shapes[3],
+        Shape::Path {
+            points: vec![(0, 0), (1, -1)]
+        }
+    );
+    assert_eq!(scene.parent.as_ref().unwrap().scale, 1000.0);
+
+    // The same as written at runtime, except literals written otherwise.
+    let minimal = keon::to_string_with(&scene, &SerializeConfig::minimal()).unwrap();
+    assert_eq!(SCENE.replace("1e3", "1000.0"), minimal);
+
+    const EMPTY: [&str; 4] = [
+        keon::const_str!([]),
+        keon::const_str!(()),
+        keon::const_str!({}),
+        keon::const_str!(Some(None)),
+    ];
+    assert_eq!(EMPTY, ["[]", "()", "{}", "??"]);
+}
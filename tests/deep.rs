@@ -12,3 +12,55 @@ fn deep_object() {
         keon::ErrorKind::ExceededRecursionLimit
     );
 }
+
+#[test]
+fn recursion_limit() {
+    use keon::{limits::RECURSION_LIMIT, DeserializeConfig, Deserializer};
+    use serde::Deserialize;
+
+    assert!(keon::from_str::<Value>(&"?".repeat(RECURSION_LIMIT)).is_ok());
+    assert!(keon::from_str::<Value>(&"?".repeat(RECURSION_LIMIT + 1)).is_err());
+
+    let mut cfg = DeserializeConfig::strict();
+    cfg.recursion_limit = 256;
+    let s = "[".repeat(200);
+    let mut der = Deserializer::with_config(&s, cfg);
+    assert_eq!(
+        Value::deserialize(&mut der).unwrap_err().kind,
+        keon::ErrorKind::UnexpectedEof
+    );
+
+    cfg.recursion_limit = 2;
+    let mut der = Deserializer::with_config("[[[]]]", cfg);
+    assert_eq!(
+        Value::deserialize(&mut der).unwrap_err().kind,
+        keon::ErrorKind::ExceededRecursionLimit
+    );
+}
+
+#[test]
+fn serializer_recursion_limit() {
+    use keon::{DeserializeConfig, Deserializer, SerializeConfig};
+    use serde::Deserialize;
+
+    let nested = |depth| (0..depth).fold(Value::Unit, |v, _| Value::Seq(vec![v]));
+    let cfg = SerializeConfig::minimal();
+    assert!(keon::to_string_with(&nested(128), &cfg).is_ok());
+    assert_eq!(
+        keon::to_string_with(&nested(129), &cfg).unwrap_err().kind,
+        keon::ErrorKind::ExceededRecursionLimit
+    );
+
+    let deep = nested(200);
+    let s = keon::to_string_with(&deep, &SerializeConfig::builder().recursion_limit(256).build()).unwrap();
+    let mut cfg = DeserializeConfig::strict();
+    cfg.recursion_limit = 256;
+    assert_eq!(
+        Value::deserialize(&mut Deserializer::with_config(&s, cfg)).unwrap(),
+        deep
+    );
+
+    let cfg = SerializeConfig::builder().recursion_limit(2).build();
+    assert!(keon::to_string_with(&nested(2), &cfg).is_ok());
+    assert!(keon::to_string_with(&nested(3), &cfg).is_err());
+}
@@ -0,0 +1,76 @@
+mod util;
+
+use keon::{DeserializeConfig, ErrorKind};
+use serde::Deserialize;
+use util::de_with;
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Backend {
+    Disk {
+        path: String,
+        #[serde(default)]
+        quota: u64,
+    },
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    name: String,
+    #[serde(default = "default_retries")]
+    retries: u8,
+    proxy: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    backend: Backend,
+}
+
+fn default_retries() -> u8 {
+    3
+}
+
+const PLACEHOLDERS: DeserializeConfig = {
+    let mut cfg = DeserializeConfig::strict();
+    cfg.default_placeholders = true;
+    cfg.dotted_keys = true;
+    cfg
+};
+
+#[test]
+fn placeholders() {
+    let config = Config {
+        name: "main".into(),
+        retries: 3,
+        proxy: None,
+        tags: vec![],
+        backend: Backend::Disk {
+            path: "/srv".into(),
+            quota: 0,
+        },
+    };
+    let s = r#"{
+        retries: _,
+        name: "main",
+        proxy: _, tags: _,
+        backend: Disk { path: "/srv", quota: _ },
+        // Trailing, without a comma.
+        tags: _
+    }"#;
+    assert_eq!(de_with::<Config>(s, PLACEHOLDERS).unwrap(), config);
+    let mut cfg = PLACEHOLDERS;
+    cfg.default_placeholders = false;
+    assert!(de_with::<Config>(s, cfg).is_err());
+
+    assert_eq!(
+        de_with::<Config>(
+            r#"{ retries: 5, tags: ["a"], name: "b", backend: Disk { path: _ } }"#,
+            PLACEHOLDERS
+        )
+        .unwrap_err()
+        .kind,
+        ErrorKind::Deserialize("missing field `path`".into())
+    );
+
+    // Not in other places.
+    assert!(de_with::<Vec<Option<u8>>>("[_]", PLACEHOLDERS).is_err());
+    assert!(de_with::<Config>(r#"{ name: "main", backend.Disk: _ }"#, PLACEHOLDERS).is_err());
+}
@@ -0,0 +1,162 @@
+mod util;
+
+use keon::{DeserializeConfig, ErrorKind, SerializeConfig, SerializeConfigBuilder, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use util::{de_with, ser_with};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    server: Server,
+    log: Option<Log>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Server {
+    host: String,
+    port: u16,
+    tls: Tls,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Tls {
+    enabled: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Log {
+    level: Level,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Level {
+    value: u8,
+}
+
+fn config() -> Config {
+    Config {
+        name: "app".into(),
+        server: Server {
+            host: "localhost".into(),
+            port: 80,
+            tls: Tls { enabled: true },
+        },
+        log: Some(Log {
+            level: Level { value: 3 },
+        }),
+    }
+}
+
+const DOTTED: DeserializeConfig = {
+    let mut cfg = DeserializeConfig::strict();
+    cfg.dotted_keys = true;
+    cfg
+};
+
+#[test]
+fn expands() {
+    let s = r#"{
+        name: "app",
+        server.host: "localhost",
+        server.tls.enabled: true,
+        server.port: 80,
+        log.level.value: 3,
+    }"#;
+    assert_eq!(de_with::<Config>(s, DOTTED).unwrap(), config());
+
+    // Mixed with explicit nesting below the dotted keys.
+    let s = r#"{ server.tls: { enabled: true }, server.host: "localhost", server.port: 80, name: "app", log: ? }"#;
+    let expected = Config { log: None, ..config() };
+    assert_eq!(de_with::<Config>(s, DOTTED).unwrap(), expected);
+
+    // Into maps and values, followed by other kinds of entries.
+    let s = r#"{ a.b.c: 1, a.b.d: 2, a.e: 3, "f" => 4 }"#;
+    let map = de_with::<BTreeMap<String, Value>>(s, DOTTED).unwrap();
+    assert_eq!(
+        map["a"],
+        de_with::<Value>(r#"{ "b" => { "c" => 1, "d" => 2 }, "e" => 3 }"#, DOTTED).unwrap()
+    );
+    assert_eq!(map["f"], Value::Number(keon::Number::UInt(4)));
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+    enum Key {
+        #[serde(rename = "a")]
+        A,
+        Left,
+    }
+    let map = de_with::<BTreeMap<Key, Value>>("{ a.b: 1, Left => 2, }", DOTTED).unwrap();
+    assert_eq!(map.keys().collect::<Vec<_>>(), [&Key::A, &Key::Left]);
+}
+
+#[test]
+fn conflicts() {
+    let conflict = |s: &str| match de_with::<Value>(s, DOTTED).unwrap_err().kind {
+        ErrorKind::ConflictingDottedKey(key) => key,
+        kind => panic!("{:?} for {}", kind, s),
+    };
+
+    assert_eq!(conflict("{ a: { b: 1 }, a.c: 2 }"), "a");
+    assert_eq!(conflict("{ a.c: 2, a: { b: 1 } }"), "a");
+    assert_eq!(conflict("{ a.b: 1, x: 0, a.c: 2 }"), "a");
+    assert_eq!(conflict("{ a.b.c: 1, a.b: 2 }"), "a.b");
+    assert_eq!(conflict("{ a.b: 1, a.b.c: 2 }"), "a.b");
+    assert_eq!(conflict("{ x.a.b: 1, x.c: 0, x.a.d: 2 }"), "x.a");
+
+    // Duplicates are up to the visitor as usual.
+    assert!(de_with::<Value>("{ a.b: 1, a.b: 2 }", DOTTED).is_ok());
+    assert!(de_with::<Value>("{ a: 1, a: 2 }", DOTTED).is_ok());
+}
+
+#[test]
+fn syntax() {
+    for s in ["{ a.: 1 }", "{ a.b => 1 }", "{ a.b }", "{ a.1: 1 }"] {
+        assert_eq!(
+            de_with::<Value>(s, DOTTED).unwrap_err().kind,
+            ErrorKind::ExpectedDottedKey,
+            "{}",
+            s
+        );
+    }
+
+    // Only where enabled.
+    let err = keon::from_str::<Config>(r#"{ server.port: 80 }"#).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::UnexpectedToken);
+}
+
+#[test]
+fn collapses() {
+    let pretty = ser_with(&config(), SerializeConfig::builder().dotted_keys(true).build());
+    assert_eq!(
+        pretty,
+        r#"(Config) {
+    name: "app",
+    server: (Server) {
+        host: "localhost",
+        port: 80,
+        tls.enabled: true,
+    },
+    log: ? (Log) {
+            level.value: 3,
+        },
+}"#
+    );
+    assert_eq!(de_with::<Config>(&pretty, DOTTED).unwrap(), config());
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Outer {
+        log: Log,
+    }
+    let outer = Outer {
+        log: Log {
+            level: Level { value: 3 },
+        },
+    };
+    let pretty = ser_with(&outer, SerializeConfig::builder().dotted_keys(true).build());
+    assert_eq!(pretty, "(Outer) {\n    log.level.value: 3,\n}");
+    assert_eq!(de_with::<Outer>(&pretty, DOTTED).unwrap(), outer);
+
+    // Never where minimized.
+    let cfg = SerializeConfigBuilder::from(SerializeConfig::minimal()).dotted_keys(true);
+    let min = ser_with(&outer, cfg.build());
+    assert_eq!(min, "{log:{level:{value:3}}}");
+}
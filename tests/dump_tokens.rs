@@ -0,0 +1,48 @@
+use keon::debug::dump_tokens;
+
+#[test]
+fn listing() {
+    assert_eq!(
+        dump_tokens(
+            "(Save) {\n    name: \"Al\\x65x\", // comment\n    side: Side::Left,\n    n: [-0x1F, 1.5e3, 'c', ?],\n}"
+        ),
+        "\
+:1:1 0..1 `(`
+:1:2 1..5 identifier `Save`
+:1:6 5..6 `)`
+:1:8 7..8 `{`
+:2:5 13..17 identifier `name`
+:2:9 17..18 `:`
+:2:11 19..28 literal String(\"Alex\")
+:2:20 28..29 `,`
+:3:5 45..49 identifier `side`
+:3:9 49..50 `:`
+:3:11 51..55 identifier `Side`
+:3:15 55..57 `::`
+:3:17 57..61 identifier `Left`
+:3:21 61..62 `,`
+:4:5 67..68 identifier `n`
+:4:6 68..69 `:`
+:4:8 70..71 `[`
+:4:9 71..76 literal Int(-31)
+:4:14 76..77 `,`
+:4:16 78..83 literal Float(1500.0)
+:4:21 83..84 `,`
+:4:23 85..88 literal Char('c')
+:4:26 88..89 `,`
+:4:28 90..91 `?`
+:4:29 91..92 `]`
+:4:30 92..93 `,`
+:5:1 94..95 `}`
+"
+    );
+}
+
+#[test]
+fn stops_at_error() {
+    assert_eq!(
+        dump_tokens("[1, \"oops"),
+        ":1:1 0..1 `[`\n:1:2 1..2 literal UInt(1)\n:1:3 2..3 `,`\n:1:5 4..9 error: unexpected EOF\n"
+    );
+    assert_eq!(dump_tokens(""), "");
+}
@@ -0,0 +1,58 @@
+use keon::ErrorKind;
+use serde::Deserialize;
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+struct Settings {
+    volume: u8,
+    muted: bool,
+}
+
+#[test]
+fn empty_documents() {
+    for s in ["", "  \n\t", "// nothing here\n", "/* nor here */"] {
+        assert_eq!(keon::from_str_optional::<Settings>(s).unwrap(), None);
+        assert_eq!(keon::from_str_or_default::<Settings>(s).unwrap(), Settings::default());
+        assert_eq!(
+            keon::from_str::<Option<Settings>>(s).unwrap_err().kind,
+            ErrorKind::EmptyDocument
+        );
+        assert!(keon::is_empty_document(s));
+    }
+}
+
+#[test]
+fn empty_document_errors() {
+    let e = keon::from_str::<Settings>("// just\n// comments\n  ").unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        ":3:3 document contains no value (only comments/whitespace)"
+    );
+
+    // Not an empty document, but a truncated one.
+    assert!(!keon::is_empty_document("["));
+    assert_eq!(
+        keon::from_str::<Vec<u8>>("[").unwrap_err().kind,
+        ErrorKind::UnexpectedEof
+    );
+
+    assert!(!keon::is_empty_document("/* unclosed"));
+    assert!(!keon::is_empty_document("()"));
+}
+
+#[test]
+fn non_empty_documents() {
+    let s = "// loud\n{ volume: 11, muted: false }";
+    let settings = Settings {
+        volume: 11,
+        muted: false,
+    };
+    assert_eq!(keon::from_str_optional::<Settings>(s).unwrap(), Some(settings));
+    assert_eq!(keon::from_str_or_default::<Settings>(s).unwrap().volume, 11);
+
+    // `?` is a value, not an empty document.
+    assert_eq!(keon::from_str_optional::<Option<u8>>("?").unwrap(), Some(None));
+
+    // Errors are still reported.
+    assert!(keon::from_str_or_default::<Settings>("{ volume: 11 } {}").is_err());
+    assert!(keon::from_str_optional::<Settings>("/* unclosed").is_err());
+}
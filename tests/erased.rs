@@ -0,0 +1,46 @@
+#![cfg(feature = "erased")]
+
+use keon::{Deserializer, ErasedDeserializer, ErasedSerializer, SerializeConfig, Serializer};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Circle { radius: f32 },
+    Square(u8),
+}
+
+/// What a plugin sees, without naming the format.
+fn load(der: &mut dyn erased_serde::Deserializer) -> Result<Vec<Shape>, erased_serde::Error> {
+    erased_serde::deserialize(der)
+}
+fn save(
+    shapes: &dyn erased_serde::Serialize,
+    ser: &mut dyn erased_serde::Serializer,
+) -> Result<(), erased_serde::Error> {
+    shapes.erased_serialize(ser)
+}
+
+#[test]
+fn erased() {
+    let shapes = vec![Shape::Circle { radius: 1.5 }, Shape::Square(2)];
+
+    let mut buf = Vec::new();
+    let mut ser = ErasedSerializer::new(Serializer::new(&mut buf, SerializeConfig::minimal()));
+    save(&shapes, &mut ser.erase()).unwrap();
+    drop(ser);
+    assert_eq!(buf, b"[Circle{radius:1.5},Square%2]");
+
+    let mut der = ErasedDeserializer::new(Deserializer::from_str("[Circle { radius: 1.5 }, Square(2)] // done"));
+    assert_eq!(load(&mut der.erase()).unwrap(), shapes);
+    der.into_inner().finish().unwrap();
+
+    let mut der = ErasedDeserializer::new(Deserializer::from_str("[Circle { radius: 1.5 },\n Square(2, 3)]"));
+    let e = load(&mut der.erase()).unwrap_err();
+    assert!(e.to_string().contains(":2:"), "{}", e);
+
+    // Several values through the same deserializer.
+    let mut der = ErasedDeserializer::new(Deserializer::from_str(r#"{ "a" => 1 }"#));
+    let map: BTreeMap<String, u8> = erased_serde::deserialize(&mut der.erase()).unwrap();
+    assert_eq!(map, BTreeMap::from([("a".into(), 1)]));
+}
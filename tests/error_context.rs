@@ -0,0 +1,102 @@
+use keon::{limits::ERROR_CONTEXT_DEPTH, ErrorContext};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+enum Item {
+    Sword { damage: u8 },
+    Potion(u8),
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+struct Save {
+    name: String,
+    inventory: Vec<Item>,
+    #[serde(default)]
+    stash: BTreeMap<u8, (u8, u8)>,
+}
+
+#[test]
+fn context() {
+    let e = keon::from_str::<Save>(
+        r#"{
+    name: "Alex",
+    inventory: [Potion(1), Sword { damage: 1 }, Sword { damage: 300 }],
+}"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        e.context(),
+        [
+            ErrorContext::Field("damage".into()),
+            ErrorContext::Variant("Sword".into()),
+            ErrorContext::Element(2),
+            ErrorContext::Field("inventory".into()),
+        ]
+    );
+    assert_eq!(
+        e.to_string(),
+        ":3:65 (deserialize) invalid value: integer `300`, expected u8"
+    );
+    assert_eq!(
+        format!("{:#}", e),
+        ":3:65 (deserialize) invalid value: integer `300`, expected u8
+    while parsing value of field `damage`
+    while parsing content of variant `Sword`
+    while parsing element 2
+    while parsing value of field `inventory`"
+    );
+
+    let e =
+        keon::from_str::<Save>(r#"{ name: "Alex", inventory: [], stash: { 1 => (1, 2), 2 => (3, -4) } }"#).unwrap_err();
+    assert_eq!(
+        e.context(),
+        [
+            ErrorContext::Element(1),
+            ErrorContext::Entry(1),
+            ErrorContext::Field("stash".into()),
+        ]
+    );
+
+    // Errors of what's missing are in the context of what contains it.
+    let e = keon::from_str::<Save>(r#"{ name: "Alex", inventory: [Sword {}] }"#).unwrap_err();
+    assert_eq!(e.context()[0], ErrorContext::Variant("Sword".into()));
+    let e = keon::from_str::<Save>(r#"{ inventory: [] }"#).unwrap_err();
+    assert_eq!(e.context(), []);
+}
+
+#[test]
+fn bounded() {
+    let e =
+        keon::from_str::<Vec<Vec<Vec<Vec<Vec<Vec<Vec<Vec<Vec<Vec<u8>>>>>>>>>>>("[[[[[[[[[[x]]]]]]]]]]").unwrap_err();
+    assert_eq!(e.context(), vec![ErrorContext::Element(0); ERROR_CONTEXT_DEPTH]);
+}
+
+#[test]
+fn dotted_keys() {
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Config {
+        server: Server,
+    }
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Server {
+        host: String,
+        tls: Tls,
+    }
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Tls {
+        enabled: bool,
+        port: u16,
+    }
+
+    let mut cfg = keon::DeserializeConfig::strict();
+    cfg.dotted_keys = true;
+    let s = r#"{ server.host: "a", server.tls.enabled: true, server.tls.port: -1 }"#;
+    let e = Config::deserialize(&mut keon::Deserializer::with_config(s, cfg)).unwrap_err();
+    assert_eq!(e.context(), [ErrorContext::Field("server.tls.port".into())]);
+}
@@ -36,9 +36,170 @@ fn deserialization() {
         )
     );
 
-    // Variants cannot made into Value via serde.
-    // What's more, the located error of these are featured.
-    // This is about peek, but shouldn't have much impact.
-    assert_eq!(":1:11", err_line_col("after_this"));
-    assert_eq!(":1:17", err_line_col("after_path_sep::before_this"));
+    // Variants are made into Value as strings or maps, and are complete at the identifier,
+    // so errors of what follows them are located at what follows, unlike errors of their kind.
+    assert_eq!(":1:12", err_line_col("after_this :"));
+    assert_eq!(":1:17", err_line_col("after_path_sep::"));
+}
+
+/// Errors located the same by every way of reading, and by the token listing for lexical errors.
+#[test]
+fn across_entry_points() {
+    use serde::de::IgnoredAny;
+
+    let lexical = [
+        "asdf`",
+        "\"abc",
+        "{ a: 1, b: \"x\\q\" }",
+        "'ab'",
+        "[\n  1,\n  @\n]",
+        "b\"\\xZZ\"",
+        "  \u{3000}?",
+        "\u{e9}`",
+        "\r\n\r\n  `",
+        "{ r#type: 1 }",
+    ];
+    let syntactic = [
+        "{ foo: 1 } bar",
+        "(Foo) { a: 1",
+        "Side::",
+        "Foo:: 1",
+        "[1 2]",
+        "{ a 1 }",
+        "{\n  a: ?\n  b: 2 }",
+        "[Foo::Bar\n   ,, ]",
+        "{ a: Foo  ::\n  1 }",
+        "[ Foo :: Bar 3 ]",
+    ];
+
+    for s in lexical.iter().chain(&syntactic) {
+        let e = err_line_col(s);
+        let located = |r: keon::Result<_>| r.map(|_: IgnoredAny| ()).unwrap_err().to_string();
+        assert_eq!(e, located(keon::from_str(s)).split(' ').next().unwrap(), "{:?}", s);
+        assert_eq!(
+            e,
+            located(keon::from_bytes(s.as_bytes())).split(' ').next().unwrap(),
+            "{:?}",
+            s
+        );
+        assert_eq!(
+            e,
+            located(keon::from_reader(s.as_bytes())).split(' ').next().unwrap(),
+            "{:?}",
+            s
+        );
+    }
+    for s in lexical {
+        let dump = keon::debug::dump_tokens(s);
+        let last = dump.lines().last().unwrap();
+        assert!(last.contains("error:"), "{:?}", dump);
+        assert_eq!(err_line_col(s), last.split(' ').next().unwrap(), "{:?}", s);
+    }
+}
+
+/// Errors of the visitor are located at what it was given, not at what was peeked after it.
+#[test]
+fn after_peeking() {
+    use serde::Deserialize;
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Config {
+        level: u8,
+        #[serde(default)]
+        side: Option<Side>,
+    }
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    enum Side {
+        Left,
+        Right { by: u8 },
+    }
+
+    let err_line_col = |s: &str| {
+        let msg = keon::from_str::<Config>(s).unwrap_err().to_string();
+        msg.split(' ').next().unwrap().to_string()
+    };
+    assert_eq!(":1:10", err_line_col("{ level: Foo }"));
+    assert_eq!(":1:10", err_line_col("{ level: Foo, }"));
+    assert_eq!(":1:10", err_line_col("{ level: \"x\" }"));
+    assert_eq!(":1:15", err_line_col("{ level: Foo::Bar }"));
+    assert_eq!(":2:13", err_line_col("{ level: 1,\n    side: ? Up\n}"));
+    assert_eq!(":2:9", err_line_col("{ level: 1, side: ?\n        Up\n}"));
+    assert_eq!(":1:27", err_line_col("{ level: 1, side: ? Side::Up }"));
+
+    // So are variants followed by what doesn't fit their kind.
+    assert_eq!(":1:21", err_line_col("{ level: 1, side: ? Left(1) }"));
+    assert_eq!(":1:21", err_line_col("{ level: 1, side: ? Right }"));
+    assert_eq!(":1:27", err_line_col("{ level: 1, side: ? Side::Right, }"));
+    assert_eq!(
+        ":2:9",
+        err_line_col(
+            "{ level: 1, side: ?
+        Right
+}"
+        )
+    );
+
+    // Unlike errors of the syntax, or of what is missing.
+    assert_eq!(":1:35", err_line_col("{ level: 1, side: ? Right { by: 1 2 } }"));
+    assert_eq!(":1:16", err_line_col("{ side: ? Left }"));
+}
+
+#[test]
+fn serialization_io() {
+    use std::{error::Error, io};
+
+    /// Accepts `budget` bytes, then runs out of space.
+    struct Full {
+        budget: usize,
+    }
+    impl io::Write for Full {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            match self.budget {
+                0 => Err(io::Error::new(io::ErrorKind::StorageFull, "disk full")),
+                _ => {
+                    let len = buf.len().min(self.budget);
+                    self.budget -= len;
+                    Ok(len)
+                }
+            }
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let value = vec![vec![1, 2], vec![3, 4]];
+    let e = keon::to_writer_pretty(Full { budget: 12 }, &value).unwrap_err();
+    let keon::ErrorKind::Io(io_err) = &e.kind else {
+        panic!("{e:?}")
+    };
+    assert_eq!(io_err.kind(), io::ErrorKind::StorageFull);
+    assert_eq!(":3:5 (IO) disk full", e.to_string());
+
+    let source = e.source().unwrap().downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.kind(), io::ErrorKind::StorageFull);
+    assert_eq!(source.to_string(), "disk full");
+}
+
+#[test]
+fn sources() {
+    use std::error::Error;
+
+    let e = keon::from_str::<Value>("[1, 0x1_0000_0000_0000_0000]").unwrap_err();
+    assert!(e.source().unwrap().is::<lexical_core::Error>(), "{e:?}");
+
+    #[cfg(feature = "syntax-basexx")]
+    {
+        let e = keon::from_str::<Value>("b64\"not base64!\"").unwrap_err();
+        assert!(e.source().unwrap().is::<data_encoding::DecodeError>(), "{e:?}");
+    }
+
+    let e = keon::from_bytes::<Value>(b"\"\xE9\"").unwrap_err();
+    let utf8 = e.source().unwrap().downcast_ref::<std::str::Utf8Error>().unwrap();
+    assert_eq!(utf8.error_len(), Some(1));
+
+    let e = keon::from_str::<Value>("[1 2]").unwrap_err();
+    assert!(e.source().is_none());
 }
@@ -0,0 +1,46 @@
+use keon::{Expand, SerializeConfig};
+
+fn serialize(value: &Vec<Vec<(u8,)>>, expand: Expand) -> String {
+    let cfg = SerializeConfig::builder().expand(expand).build();
+    let s = keon::to_string_with(value, &cfg).unwrap();
+    assert_eq!(&keon::from_str::<Vec<Vec<(u8,)>>>(&s).unwrap(), value);
+    s
+}
+
+#[test]
+fn expand() {
+    let value = vec![vec![(1,), (2,)], vec![(3,)]];
+
+    assert_eq!(
+        serialize(&value, Expand::All),
+        "[\n    [\n        (\n            1,\n        ),\n        (\n            2,\n        ),\n    ],\n    [\n        (\n            3,\n        ),\n    ],\n]"
+    );
+    assert_eq!(serialize(&value, Expand::UntilDepth(0)), "[[(1,),(2,)],[(3,)]]");
+    assert_eq!(serialize(&value, Expand::UntilDepth(1)), "[[(1,),(2,)],[(3,)]]");
+    assert_eq!(
+        serialize(&value, Expand::UntilDepth(2)),
+        "[\n    [(1,),(2,)],\n    [(3,)],\n]"
+    );
+
+    // The outer levels compact, the deep ones expanded.
+    assert_eq!(
+        serialize(&value, Expand::Between(2, 3)),
+        "[[\n        (1,),\n        (2,),\n    ],[\n        (3,),\n    ]]"
+    );
+    assert_eq!(
+        serialize(&value, Expand::Between(1, 2)),
+        serialize(&value, Expand::UntilDepth(2))
+    );
+}
+
+#[test]
+fn deeper_than_comfort() {
+    let value = vec![vec![vec![vec![vec![vec![vec![1]]]]]]];
+    let s = keon::to_string_with(&value, &SerializeConfig::builder().expand(Expand::All).build()).unwrap();
+    let closing = (0..7)
+        .rev()
+        .map(|dep| format!("{}],", " ".repeat(4 * dep)))
+        .collect::<Vec<_>>();
+    assert!(s.ends_with(&format!("{}1,\n{}", " ".repeat(28), closing.join("\n")).trim_end_matches(',')));
+    assert!(keon::to_string_pretty(&value).unwrap().contains("[[1]]"));
+}
@@ -0,0 +1,33 @@
+use keon::{extract_strings, ErrorKind};
+
+#[test]
+fn extract() {
+    let source = r#"(Dialogue) {
+    speaker: "Al\x65x", // "not a string"
+    lines: ["Hello!", "Bye."],
+    mood: Mood::Happy,
+    choices: { "yes" => 'y', "no" => 'n' },
+    icon: b"png",
+}"#;
+    let strings = extract_strings(source).unwrap();
+    assert_eq!(
+        strings.iter().map(|(_, s)| s.as_str()).collect::<Vec<_>>(),
+        ["Alex", "Hello!", "Bye.", "yes", "no"]
+    );
+    assert_eq!(
+        strings
+            .iter()
+            .map(|(span, _)| &source[span.clone()])
+            .collect::<Vec<_>>(),
+        [r#""Al\x65x""#, r#""Hello!""#, r#""Bye.""#, r#""yes""#, r#""no""#]
+    );
+
+    assert_eq!(extract_strings("").unwrap(), []);
+
+    let e = extract_strings("[\"ok\",\n \"oops").unwrap_err();
+    assert_eq!(
+        (e.line, e.col),
+        (std::num::NonZeroU32::new(2), std::num::NonZeroU32::new(2))
+    );
+    assert_eq!(e.kind, ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Device {
+    #[serde(with = "keon::fmt::hex")]
+    mask: u32,
+    #[serde(with = "keon::fmt::binary")]
+    pins: Vec<u8>,
+    #[serde(with = "keon::fmt::octal")]
+    mode: u16,
+    #[serde(with = "keon::fmt::hex")]
+    offset: i64,
+    id: u64,
+}
+
+#[test]
+fn radix() {
+    let device = Device {
+        mask: 0xdead_beef,
+        pins: vec![0b1010, 0],
+        mode: 0o755,
+        offset: i64::MIN,
+        id: 42,
+    };
+    let s = keon::to_string(&device).unwrap();
+    assert_eq!(
+        s,
+        "{mask:0xdeadbeef,pins:[0b1010,0b0],mode:0o755,offset:-0x8000000000000000,id:42}"
+    );
+    assert_eq!(keon::from_str::<Device>(&s).unwrap(), device);
+
+    // Others see a newtype struct.
+    assert_eq!(
+        serde_json::to_string(&device).unwrap(),
+        r#"{"mask":3735928559,"pins":[10,0],"mode":493,"offset":-9223372036854775808,"id":42}"#
+    );
+}
+
+#[test]
+#[cfg(all(feature = "syntax-paragraph", feature = "syntax-raw-strings"))]
+fn strings() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Entry {
+        #[serde(with = "keon::fmt::paragraph")]
+        about: String,
+        #[serde(with = "keon::fmt::raw_string")]
+        pattern: String,
+        #[serde(with = "keon::fmt::raw_string")]
+        control: String,
+        #[serde(with = "keon::fmt::paragraph")]
+        aliases: Vec<String>,
+    }
+
+    let entry = Entry {
+        about: "Short.".into(),
+        pattern: r"\d+".into(),
+        control: "tab\t".into(),
+        aliases: vec!["one".into(), "two\nlines".into()],
+    };
+    let s = keon::to_string_pretty(&entry).unwrap();
+    assert_eq!(
+        s,
+        r#"(Entry) {
+    about: | Short.
+    , pattern: `"\d+"`,
+    control: "tab\t",
+    aliases: [
+        | one
+        , | two
+          ` lines
+    ],
+}"#
+    );
+    assert_eq!(keon::from_str::<Entry>(&s).unwrap(), entry);
+
+    // Quoted where minimized, as paragraphs need their own lines.
+    let s = keon::to_string(&entry).unwrap();
+    assert!(s.starts_with(r#"{about:"Short.",pattern:`"\d+"`,"#), "{s}");
+    assert_eq!(keon::from_str::<Entry>(&s).unwrap(), entry);
+}
@@ -0,0 +1,336 @@
+//! Checks the exported EBNF against the deserializer, by matching a corpus of documents with the syntax rules,
+//! while the token rules are left to the lexer.
+
+mod util;
+
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Debug)]
+enum Expr {
+    Alt(Vec<Expr>),
+    Seq(Vec<Expr>),
+    Opt(Box<Expr>),
+    Rep(Box<Expr>),
+    Rule(String),
+    Term(String),
+    Prose,
+}
+
+struct Parser<'a> {
+    toks: Vec<&'a str>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Self {
+        let mut toks = Vec::new();
+        let mut rest = src;
+        loop {
+            rest = rest.trim_start();
+            let Some(ch) = rest.chars().next() else { break };
+            let len = if rest.starts_with("(*") {
+                let end = rest.find("*)").expect("unclosed comment");
+                rest = &rest[end + 2..];
+                continue;
+            } else if ch == '"' || ch == '\'' || ch == '?' {
+                rest[1..].find(ch).expect("unclosed terminal") + 2
+            } else if ch.is_ascii_alphanumeric() || ch == '_' {
+                rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len())
+            } else {
+                ch.len_utf8()
+            };
+            toks.push(&rest[..len]);
+            rest = &rest[len..];
+        }
+        Self { toks, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.toks.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> &'a str {
+        self.pos += 1;
+        self.toks[self.pos - 1]
+    }
+
+    fn expect(&mut self, tok: &str) {
+        assert_eq!(self.bump(), tok, "at token {}", self.pos);
+    }
+
+    fn rules(mut self) -> HashMap<String, Expr> {
+        let mut rules = HashMap::new();
+        while self.peek().is_some() {
+            let name = self.bump().to_string();
+            self.expect("=");
+            let expr = self.alt();
+            self.expect(";");
+            assert!(rules.insert(name.clone(), expr).is_none(), "`{name}` is defined twice");
+        }
+        rules
+    }
+
+    fn alt(&mut self) -> Expr {
+        let mut alts = vec![self.seq()];
+        while self.peek() == Some("|") {
+            self.bump();
+            alts.push(self.seq());
+        }
+        Expr::Alt(alts)
+    }
+
+    fn seq(&mut self) -> Expr {
+        let mut items = Vec::new();
+        loop {
+            let item = match self.peek().unwrap() {
+                "|" | ";" | ")" | "]" | "}" => break,
+                "(" | "[" | "{" => {
+                    let open = self.bump();
+                    let inner = self.alt();
+                    match open {
+                        "(" => (self.expect(")"), inner).1,
+                        "[" => (self.expect("]"), Expr::Opt(Box::new(inner))).1,
+                        _ => (self.expect("}"), Expr::Rep(Box::new(inner))).1,
+                    }
+                }
+                tok if tok.starts_with('?') => (self.bump(), Expr::Prose).1,
+                tok if tok.starts_with(['"', '\'']) => Expr::Term(self.bump()[1..tok.len() - 1].to_string()),
+                _ => Expr::Rule(self.bump().to_string()),
+            };
+            items.push(item);
+        }
+        Expr::Seq(items)
+    }
+}
+
+fn grammar() -> HashMap<String, Expr> {
+    Parser::new(keon::grammar::ebnf()).rules()
+}
+
+/// Tokens as the grammar sees them: `IDENT`, `LITERAL`, or the punctuation itself.
+/// `None` if the lexer rejects the document.
+fn tokenize(doc: &str) -> Option<Vec<String>> {
+    let mut toks = Vec::new();
+    for line in keon::debug::dump_tokens(doc).lines() {
+        let what = line.splitn(3, ' ').nth(2).unwrap();
+        toks.push(match what {
+            _ if what.starts_with("error:") => return None,
+            _ if what.starts_with("identifier ") => "IDENT".to_string(),
+            _ if what.starts_with("literal ") => "LITERAL".to_string(),
+            _ => what.trim_matches('`').to_string(),
+        });
+    }
+    Some(toks)
+}
+
+/// All positions where `expr` can end when started at `pos`.
+fn ends(rules: &HashMap<String, Expr>, expr: &Expr, toks: &[String], pos: usize) -> BTreeSet<usize> {
+    match expr {
+        Expr::Alt(alts) => alts.iter().flat_map(|alt| ends(rules, alt, toks, pos)).collect(),
+        Expr::Seq(items) => items.iter().fold(BTreeSet::from([pos]), |set, item| {
+            set.into_iter().flat_map(|p| ends(rules, item, toks, p)).collect()
+        }),
+        Expr::Opt(inner) => {
+            let mut set = ends(rules, inner, toks, pos);
+            set.insert(pos);
+            set
+        }
+        Expr::Rep(inner) => {
+            let mut set = BTreeSet::from([pos]);
+            let mut frontier = set.clone();
+            while !frontier.is_empty() {
+                frontier = frontier
+                    .into_iter()
+                    .flat_map(|p| ends(rules, inner, toks, p))
+                    .filter(|p| !set.contains(p))
+                    .collect();
+                set.extend(frontier.iter().copied());
+            }
+            set
+        }
+        Expr::Rule(name) if name.chars().all(|c| c.is_ascii_uppercase() || c == '_') => {
+            match toks.get(pos) == Some(name) {
+                true => BTreeSet::from([pos + 1]),
+                false => BTreeSet::new(),
+            }
+        }
+        Expr::Rule(name) => ends(rules, &rules[name], toks, pos),
+        Expr::Term(term) => match toks.get(pos) == Some(term) {
+            true => BTreeSet::from([pos + 1]),
+            false => BTreeSet::new(),
+        },
+        Expr::Prose => unreachable!("prose in syntax rules"),
+    }
+}
+
+fn accepts(rules: &HashMap<String, Expr>, doc: &str) -> bool {
+    match tokenize(doc) {
+        Some(toks) => ends(rules, &rules["document"], &toks, 0).contains(&toks.len()),
+        None => false,
+    }
+}
+
+/// Whether `expr` can span `pos..end`, noting the alternatives, options and repetitions taken
+/// by one such derivation in `taken`, by their addresses.
+fn derive(
+    rules: &HashMap<String, Expr>,
+    expr: &Expr,
+    toks: &[String],
+    (pos, end): (usize, usize),
+    taken: &mut BTreeSet<usize>,
+) -> bool {
+    match expr {
+        Expr::Alt(alts) => match alts.iter().find(|alt| ends(rules, alt, toks, pos).contains(&end)) {
+            Some(alt) => {
+                taken.insert(alt as *const Expr as usize);
+                derive(rules, alt, toks, (pos, end), taken)
+            }
+            None => false,
+        },
+        Expr::Seq(items) => derive_seq(rules, items, toks, (pos, end), taken),
+        Expr::Opt(_) | Expr::Rep(_) if pos == end => true,
+        Expr::Opt(inner) => {
+            taken.insert(expr as *const Expr as usize);
+            derive(rules, inner, toks, (pos, end), taken)
+        }
+        Expr::Rep(inner) => {
+            let mid = ends(rules, inner, toks, pos)
+                .into_iter()
+                .find(|&mid| mid > pos && ends(rules, expr, toks, mid).contains(&end));
+            match mid {
+                Some(mid) => {
+                    taken.insert(expr as *const Expr as usize);
+                    derive(rules, inner, toks, (pos, mid), taken) && derive(rules, expr, toks, (mid, end), taken)
+                }
+                None => false,
+            }
+        }
+        Expr::Rule(name) if name.chars().all(|c| c.is_ascii_uppercase() || c == '_') => end == pos + 1,
+        Expr::Rule(name) => derive(rules, &rules[name], toks, (pos, end), taken),
+        Expr::Term(_) => end == pos + 1,
+        Expr::Prose => unreachable!("prose in syntax rules"),
+    }
+}
+
+fn derive_seq(
+    rules: &HashMap<String, Expr>,
+    items: &[Expr],
+    toks: &[String],
+    (pos, end): (usize, usize),
+    taken: &mut BTreeSet<usize>,
+) -> bool {
+    let Some((first, rest)) = items.split_first() else {
+        return pos == end;
+    };
+    let rest_ends = |mid| {
+        rest.iter().fold(BTreeSet::from([mid]), |set, item| {
+            set.into_iter().flat_map(|p| ends(rules, item, toks, p)).collect()
+        })
+    };
+    match ends(rules, first, toks, pos)
+        .into_iter()
+        .find(|&mid| rest_ends(mid).contains(&end))
+    {
+        Some(mid) => derive(rules, first, toks, (pos, mid), taken) && derive_seq(rules, rest, toks, (mid, end), taken),
+        None => false,
+    }
+}
+
+/// The alternatives, options and repetitions of the syntax rules by their addresses, described.
+fn choices(rules: &HashMap<String, Expr>) -> HashMap<usize, String> {
+    fn walk(rule: &str, expr: &Expr, out: &mut HashMap<usize, String>) {
+        let id = expr as *const Expr as usize;
+        match expr {
+            Expr::Alt(alts) => {
+                for (i, alt) in alts.iter().enumerate() {
+                    if alts.len() > 1 {
+                        out.insert(
+                            alt as *const Expr as usize,
+                            format!("alternative {} of `{}`", i + 1, rule),
+                        );
+                    }
+                    walk(rule, alt, out);
+                }
+            }
+            Expr::Seq(items) => items.iter().for_each(|item| walk(rule, item, out)),
+            Expr::Opt(inner) => {
+                out.insert(id, format!("an option of `{}`", rule));
+                walk(rule, inner, out);
+            }
+            Expr::Rep(inner) => {
+                out.insert(id, format!("a repetition of `{}`", rule));
+                walk(rule, inner, out);
+            }
+            Expr::Rule(_) | Expr::Term(_) | Expr::Prose => (),
+        }
+    }
+
+    let mut out = HashMap::new();
+    for (name, expr) in rules {
+        if !name.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+            walk(name, expr, &mut out);
+        }
+    }
+    out
+}
+
+#[test]
+fn well_formed() {
+    fn refs<'a>(expr: &'a Expr, out: &mut Vec<&'a str>) {
+        match expr {
+            Expr::Alt(items) | Expr::Seq(items) => items.iter().for_each(|e| refs(e, out)),
+            Expr::Opt(inner) | Expr::Rep(inner) => refs(inner, out),
+            Expr::Rule(name) => out.push(name),
+            Expr::Term(_) | Expr::Prose => (),
+        }
+    }
+
+    let rules = grammar();
+    let mut names = Vec::new();
+    rules.values().for_each(|expr| refs(expr, &mut names));
+    for name in names {
+        assert!(rules.contains_key(name), "`{name}` is not defined");
+    }
+    assert!(rules.contains_key("document"));
+}
+
+#[test]
+fn conformance() {
+    let rules = grammar();
+    for &doc in util::ACCEPTED {
+        assert!(accepts(&rules, doc), "grammar rejects {doc:?}");
+        if let Err(e) = keon::from_str::<keon::Value>(doc) {
+            panic!("deserializer rejects {doc:?}: {e}");
+        }
+    }
+    for &doc in util::REJECTED {
+        assert!(!accepts(&rules, doc), "grammar accepts {doc:?}");
+        assert!(
+            keon::from_str::<keon::Value>(doc).is_err(),
+            "deserializer accepts {doc:?}"
+        );
+    }
+}
+
+/// Every choice of the syntax rules is taken by a document of the corpus, so that a change of the grammar
+/// comes with documents of it, which the other parsers are checked against, e.g. that of `keon::ide`.
+#[test]
+fn coverage() {
+    let rules = grammar();
+    let mut taken = BTreeSet::new();
+    for &doc in util::ACCEPTED {
+        let toks = tokenize(doc).unwrap();
+        assert!(
+            derive(&rules, &rules["document"], &toks, (0, toks.len()), &mut taken),
+            "grammar rejects {doc:?}"
+        );
+    }
+    let mut missing = choices(&rules)
+        .into_iter()
+        .filter(|(id, _)| !taken.contains(id))
+        .map(|(_, what)| what)
+        .collect::<Vec<_>>();
+    missing.sort();
+    assert!(missing.is_empty(), "no document in `util::ACCEPTED` takes {missing:#?}");
+}
@@ -0,0 +1,69 @@
+use keon::{Newline, SerializeConfig};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Save {
+    name: String,
+    level: u8,
+}
+
+fn save() -> Save {
+    Save {
+        name: "Alex".into(),
+        level: 3,
+    }
+}
+
+fn serialize(cfg: SerializeConfig) -> String {
+    let s = keon::to_string_with(&save(), &cfg).unwrap();
+    assert_eq!(keon::from_str::<Save>(&s).unwrap(), save());
+    s
+}
+
+#[test]
+fn header() {
+    let cfg = SerializeConfig::builder()
+        .header(Some("Generated by savegen 1.2.0"))
+        .build();
+    assert_eq!(
+        serialize(cfg),
+        "/* Generated by savegen 1.2.0 */\n(Save) {\n    name: \"Alex\",\n    level: 3,\n}"
+    );
+
+    let cfg = SerializeConfig::builder()
+        .header(Some("Generated by savegen 1.2.0\n\nat 2026-10-17T08:00:00Z"))
+        .newline(Newline::CrLf)
+        .build();
+    assert!(serialize(cfg)
+        .starts_with("/*\r\n * Generated by savegen 1.2.0\r\n *\r\n * at 2026-10-17T08:00:00Z\r\n */\r\n(Save) {\r\n"));
+
+    let mut cfg = SerializeConfig::minimal();
+    cfg.header = Some("Nested /* comments */ are fine");
+    assert_eq!(
+        serialize(cfg),
+        r#"/* Nested /* comments */ are fine */
+{name:"Alex",level:3}"#
+    );
+
+    // Otherwise the header would end its comment early.
+    cfg.header = Some("Unbalanced */ or /* markers");
+    assert_eq!(
+        serialize(cfg),
+        r#"// Unbalanced */ or /* markers
+{name:"Alex",level:3}"#
+    );
+}
+
+#[test]
+fn runtime_header() {
+    use keon::Serializer;
+
+    let version = format!("Generated by savegen {}", 1 + 1);
+    let cfg = SerializeConfig::builder().header(Some("Replaced")).build();
+    let mut ser = Serializer::new(Vec::new(), cfg).with_header(&version);
+    save().serialize(&mut ser).unwrap();
+    assert_eq!(
+        String::from_utf8(ser.into_inner().unwrap()).unwrap(),
+        "/* Generated by savegen 2 */\n(Save) {\n    name: \"Alex\",\n    level: 3,\n}"
+    );
+}
@@ -0,0 +1,167 @@
+#![cfg(feature = "schemars")]
+
+mod util;
+
+use keon::ide::{completions, Completion, CompletionKind};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[allow(dead_code)]
+#[derive(Deserialize, JsonSchema)]
+struct Config {
+    /// Shown in the title bar.
+    name: String,
+    retries: Option<u32>,
+    separator: char,
+    verbose: bool,
+    shapes: Vec<Shape>,
+    origin: (f64, f64),
+    limits: BTreeMap<String, Side>,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, JsonSchema)]
+enum Shape {
+    Dot,
+    /// A circle of the radius.
+    Circle(f64),
+    Rect {
+        w: f64,
+        h: f64,
+    },
+    Pair(Side, Side),
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, JsonSchema)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// The labels of the completions at `|`.
+fn labels(source: &str) -> Vec<String> {
+    let offset = source.find('|').unwrap();
+    let source = source.replacen('|', "", 1);
+    let schema = keon::schema::schema_for::<Config>();
+    completions(&source, offset, &schema)
+        .into_iter()
+        .map(|completion| completion.label)
+        .collect()
+}
+
+#[test]
+fn fields() {
+    // In the order of the schema, which sorts them.
+    let all = ["limits", "name", "origin", "retries", "separator", "shapes", "verbose"];
+    assert_eq!(labels("|"), ["{}"]);
+    assert_eq!(labels("{|"), all);
+    assert_eq!(labels("(Config) {\n    |\n}"), all);
+    assert_eq!(
+        labels("{ name: \"x\", |"),
+        ["limits", "origin", "retries", "separator", "shapes", "verbose"]
+    );
+    assert_eq!(labels("{ name: \"x\", re|"), ["retries"]);
+    assert_eq!(labels("{ shapes: [Rect { w: 1.0, |"), ["h"]);
+    assert_eq!(labels("{ origin.|"), Vec::<String>::new());
+    assert_eq!(labels("{ limits.x: |"), ["Left", "Right"]);
+
+    let schema = keon::schema::schema_for::<Config>();
+    assert_eq!(
+        completions("{ na", 4, &schema),
+        [Completion {
+            label: "name".into(),
+            kind: CompletionKind::Field,
+            detail: Some("Shown in the title bar.".into()),
+        }]
+    );
+}
+
+#[test]
+fn values() {
+    assert_eq!(labels("{ name: |"), ["\"\""]);
+    assert_eq!(labels("{ retries: |"), ["0", "?"]);
+    assert_eq!(labels("{ retries: ? |"), ["0", "?"]);
+    assert_eq!(labels("{ separator: |"), ["''"]);
+    assert_eq!(labels("{ verbose: |"), ["true", "false"]);
+    assert_eq!(labels("{ verbose: f|"), ["false"]);
+    assert_eq!(labels("{ origin: |"), ["()"]);
+    assert_eq!(labels("{ origin: (1.0, |"), ["0.0"]);
+    assert_eq!(labels("{ shapes: |"), ["[]"]);
+    assert_eq!(labels("{ limits: |"), ["{}"]);
+    assert_eq!(labels("{ limits: { \"x\" => |"), ["Left", "Right"]);
+}
+
+#[test]
+fn variants() {
+    assert_eq!(labels("{ shapes: [|"), ["Dot", "Circle", "Rect", "Pair"]);
+    assert_eq!(labels("{ shapes: [Dot, C|"), ["Circle"]);
+    assert_eq!(labels("{ shapes: [Shape::|"), ["Dot", "Circle", "Rect", "Pair"]);
+    assert_eq!(labels("{ shapes: [Shape::R|"), ["Rect"]);
+    assert_eq!(labels("{ shapes: [Circle(|"), ["0.0"]);
+    assert_eq!(labels("{ shapes: [Pair(Left, |"), ["Left", "Right"]);
+    assert_eq!(labels("{ shapes: [Rect { h: |"), ["0.0"]);
+}
+
+#[test]
+fn nothing() {
+    // After a value, inside literals and comments, and after syntax errors.
+    assert!(labels("{ name: \"x\" |").is_empty());
+    assert!(labels("{ name: \"x|\" }").is_empty());
+    assert!(labels("{ // na|\n}").is_empty());
+    assert!(labels("{ name: ] |").is_empty());
+    assert!(labels("{ unknown: |").is_empty());
+    assert!(labels("{ name: \"x\" } |").is_empty());
+    assert!(labels("{ name: NaN:|").is_empty());
+}
+
+#[test]
+fn deep() {
+    use keon::{limits::RECURSION_LIMIT, Value};
+
+    // As deep as the deserializer reads, and no deeper.
+    let schema = schemars::json_schema!({
+        "$ref": "#/$defs/Nested",
+        "$defs": { "Nested": { "type": "array", "items": { "$ref": "#/$defs/Nested" } } },
+    });
+    let open = "[".repeat(RECURSION_LIMIT - 1);
+    assert!(!completions(&open, open.len(), &schema).is_empty());
+    assert!(keon::from_str::<Value>(&format!("{open}0{}", "]".repeat(RECURSION_LIMIT - 1))).is_ok());
+    let open = "[".repeat(RECURSION_LIMIT);
+    assert!(completions(&open, open.len(), &schema).is_empty());
+    assert!(keon::from_str::<Value>(&format!("{open}0{}", "]".repeat(RECURSION_LIMIT))).is_err());
+
+    for open in ["[", "{ a: ", "A(", "?", "(", "%"] {
+        let source = open.repeat(20_000);
+        assert!(completions(&source, source.len(), &schema).is_empty(), "{open:?}");
+    }
+}
+
+#[test]
+fn agrees_with_deserializer() {
+    use keon::{DeserializeConfig, Deserializer, Value};
+
+    // The syntax to opt into is always read, the documents it's for may be edited.
+    let mut cfg = DeserializeConfig::default();
+    cfg.set_sugar = true;
+    cfg.dotted_keys = true;
+    let deserialize = |doc| {
+        let mut der = Deserializer::with_config(doc, cfg);
+        Value::deserialize(&mut der).and_then(|_| der.finish())
+    };
+
+    // The field `b` is suggested after `a` only if the latter was read as a whole value,
+    // a line break ending any comment in it.
+    let schema = schemars::json_schema!({
+        "type": "object",
+        "properties": { "a": true, "b": true },
+    });
+    for &doc in util::ACCEPTED.iter().chain(util::REJECTED) {
+        let source = format!("{{ a: {}\n, ", doc);
+        let read = completions(&source, source.len(), &schema)
+            .iter()
+            .any(|c| c.label == "b");
+        assert_eq!(read, deserialize(doc).is_ok(), "{doc:?}");
+    }
+}
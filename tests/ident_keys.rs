@@ -0,0 +1,93 @@
+use keon::{PathSegment::*, SerializeConfig, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+enum Action {
+    Up,
+    Down,
+    Jump(u8),
+}
+
+fn roundtrip(map: &HashMap<String, u8>, cfg: SerializeConfig) -> String {
+    let s = keon::to_string_with(map, &cfg).unwrap();
+    assert_eq!(&keon::from_str::<HashMap<String, u8>>(&s).unwrap(), map);
+    assert_eq!(
+        keon::from_str::<Value>(&s).unwrap(),
+        keon::from_str::<Value>(&keon::to_string(map).unwrap()).unwrap()
+    );
+    s
+}
+
+#[test]
+fn ident_keys() {
+    let map = HashMap::from([
+        ("alpha".to_string(), 1),
+        ("two words".to_string(), 2),
+        ("true".to_string(), 3),
+        ("é".to_string(), 4),
+        ("42".to_string(), 5),
+    ]);
+    let cfg = SerializeConfig::builder().ident_keys(true).sort_keys(true);
+    assert_eq!(
+        roundtrip(&map, cfg.build()),
+        r#"{
+    "42" => 5,
+    "two words" => 2,
+    `true: 3,
+    alpha: 1,
+    é: 4,
+}"#
+    );
+    let mut minimal = SerializeConfig::minimal();
+    minimal.ident_keys = true;
+    minimal.sort_keys = true;
+    assert_eq!(
+        roundtrip(&map, minimal),
+        r#"{"42"=>5,"two words"=>2,`true:3,alpha:1,é:4}"#
+    );
+
+    // Only keys that are strings themselves.
+    let nested = BTreeMap::from([(Some("a"), BTreeMap::from([("b", 1)]))]);
+    assert_eq!(keon::to_string_with(&nested, &minimal).unwrap(), r#"{?"a"=>{b:1}}"#);
+
+    // Mapped as written.
+    let (s, source_map) = keon::to_string_with_source_map(&BTreeMap::from([("k", 1)]), &minimal).unwrap();
+    assert_eq!(s, "{k:1}");
+    assert_eq!(source_map, [(vec![Key("k".to_string())], 3..4)]);
+
+    // Written as they were otherwise.
+    assert!(keon::to_string_pretty(&map).unwrap().contains("\"alpha\" => 1,"));
+}
+
+#[test]
+fn unit_variant_keys() {
+    let keybinds = BTreeMap::from([(Action::Up, 'W'), (Action::Down, 'S'), (Action::Jump(2), ' ')]);
+    let cfg = SerializeConfig::builder()
+        .ident_keys(true)
+        .variant_index_comments(true)
+        .build();
+    let s = keon::to_string_with(&keybinds, &cfg).unwrap();
+    assert_eq!(
+        s,
+        "{
+    Up: 'W',
+    Down: 'S',
+    Action::Jump /* =2 */(2) => ' ',
+}"
+    );
+    assert_eq!(keon::from_str::<BTreeMap<Action, char>>(&s).unwrap(), keybinds);
+
+    // Also over indices of unit variants.
+    let mut minimal = SerializeConfig::minimal();
+    minimal.ident_keys = true;
+    minimal.unit_variant_indices = true;
+    assert_eq!(
+        keon::to_string_with(&keybinds, &minimal).unwrap(),
+        "{Up:'W',Down:'S',Jump%2=>' '}"
+    );
+    assert_eq!(
+        keon::to_string_with(&BTreeMap::from([("key", Action::Up)]), &minimal).unwrap(),
+        "{key:0}"
+    );
+}
@@ -0,0 +1,154 @@
+use keon::{
+    import::{csv_rows, from_csv, CellType, SchemaHint},
+    ErrorKind, SerializeConfig, Serializer, Value,
+};
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Person {
+    id: u32,
+    name: String,
+    score: Option<f64>,
+    active: bool,
+}
+
+const PEOPLE: &str = "id,name,score,active\r\n\
+                      1,Alice,9.5,true\r\n\
+                      2,\"Bob \"\"the\"\" Builder\",,false\r\n\
+                      \r\n\
+                      3,\"Carol\nSmith\",-2,true\r\n";
+
+#[test]
+fn csv() {
+    let hint = SchemaHint::new().column("score", CellType::Float).optional("score");
+    let rows = from_csv(PEOPLE.as_bytes(), &hint).unwrap();
+    // In order of the names of the columns, which the header keeps.
+    assert_eq!(
+        csv_rows(PEOPLE.as_bytes(), &hint).unwrap().header(),
+        ["id", "name", "score", "active"]
+    );
+    assert_eq!(
+        rows.to_string().unwrap(),
+        concat!(
+            r#"[{"active"=>true,"id"=>1,"name"=>"Alice","score"=>?9.5},"#,
+            r#"{"active"=>false,"id"=>2,"name"=>"Bob \"the\" Builder","score"=>?},"#,
+            r#"{"active"=>true,"id"=>3,"name"=>"Carol\nSmith","score"=>?-2.0}]"#,
+        )
+    );
+
+    // Fixtures read back as structs.
+    let people = keon::from_str::<Vec<Person>>(&rows.to_string_pretty().unwrap()).unwrap();
+    assert_eq!(people[0].score, Some(9.5));
+    assert_eq!(people[1].score, None);
+    assert_eq!(people[2].name, "Carol\nSmith");
+
+    // Empty cells are strings, unless a type is expected.
+    let e = from_csv(PEOPLE.as_bytes(), &SchemaHint::new().column("score", CellType::Float)).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        ":3:25 (deserialize) invalid value: string \"\", expected a float"
+    );
+    let rows = from_csv(PEOPLE.as_bytes(), &SchemaHint::new()).unwrap();
+    assert_eq!(
+        rows.seq_page(1..2).unwrap()[0].to_string().unwrap(),
+        r#"{"active"=>false,"id"=>2,"name"=>"Bob \"the\" Builder","score"=>""}"#
+    );
+}
+
+#[test]
+fn inferred() {
+    let csv = "a;b;c;d;e;f\n007;-3;1e3;TRUE;;18446744073709551616\n";
+    let rows = from_csv(csv.as_bytes(), &SchemaHint::new().delimiter(';')).unwrap();
+    assert_eq!(
+        rows.to_string().unwrap(),
+        r#"[{"a"=>"007","b"=>-3,"c"=>1000.0,"d"=>"TRUE","e"=>"","f"=>1.8446744073709552e19}]"#
+    );
+
+    let rows = from_csv(
+        csv.as_bytes(),
+        &SchemaHint::new().delimiter(';').default_type(CellType::String),
+    )
+    .unwrap();
+    assert_eq!(
+        rows.to_string().unwrap(),
+        r#"[{"a"=>"007","b"=>"-3","c"=>"1e3","d"=>"TRUE","e"=>"","f"=>"18446744073709551616"}]"#
+    );
+
+    // `-0` is the integer `0` as well, also where an integer is expected, unlike `-0.0`.
+    let csv = "a,b,c\n0,-0,-0.0\n";
+    let expected = r#"[{"a"=>0,"b"=>0,"c"=>-0.0}]"#;
+    let rows = from_csv(csv.as_bytes(), &SchemaHint::new()).unwrap();
+    assert_eq!(rows.to_string().unwrap(), expected);
+    let rows = from_csv(csv.as_bytes(), &SchemaHint::new().column("b", CellType::Integer)).unwrap();
+    assert_eq!(rows.to_string().unwrap(), expected);
+
+    assert_eq!(
+        from_csv("".as_bytes(), &SchemaHint::new()).unwrap(),
+        Value::Seq(Vec::new())
+    );
+}
+
+#[test]
+fn located_errors() {
+    let hint = SchemaHint::new().column("id", CellType::Integer);
+    let e = from_csv("name,id\nA,1\nB,x\n".as_bytes(), &hint).unwrap_err();
+    assert_eq!(
+        format!("{:#}", e),
+        ":3:3 (deserialize) invalid value: string \"x\", expected an integer\n\
+         \x20   while parsing value of field `id`\n\
+         \x20   while parsing element 1"
+    );
+
+    let e = from_csv("a,b\n1,2,3\n".as_bytes(), &SchemaHint::new()).unwrap_err();
+    assert_eq!(e.kind, ErrorKind::InvalidLength { expected: 2, found: 3 });
+    assert_eq!(e.line.unwrap().get(), 2);
+
+    let e = from_csv("a,b\n\"1\"2,3\n".as_bytes(), &SchemaHint::new()).unwrap_err();
+    assert_eq!((e.kind, e.col.unwrap().get()), (ErrorKind::UnexpectedToken, 4));
+
+    let e = from_csv("a\n\"open\n".as_bytes(), &SchemaHint::new()).unwrap_err();
+    assert_eq!((e.kind, e.line.unwrap().get()), (ErrorKind::UnexpectedEof, 2));
+
+    let e = from_csv("id,name,id\n1,A,2\n".as_bytes(), &SchemaHint::new()).unwrap_err();
+    assert_eq!(e.to_string(), ":1:9 (deserialize) duplicate column `id`");
+}
+
+#[test]
+fn streaming() {
+    let csv = (0..1000).fold(String::from("n,square\n"), |csv, n| csv + &format!("{},{}\n", n, n * n));
+    let mut buf = Vec::new();
+    let mut ser = Serializer::new(&mut buf, SerializeConfig::minimal());
+    let rows = csv_rows(csv.as_bytes(), &SchemaHint::new()).unwrap();
+    assert_eq!(rows.header(), ["n", "square"]);
+    ser.collect_seq_streaming(rows.map(Result::unwrap)).unwrap();
+
+    let all = from_csv(csv.as_bytes(), &SchemaHint::new()).unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), all.to_string().unwrap());
+}
+
+#[cfg(feature = "ndjson")]
+#[test]
+fn ndjson() {
+    use keon::import::{from_ndjson, from_ndjson_with};
+
+    let ndjson = "{\"id\": 1, \"name\": \"Alice\", \"score\": 9.5, \"active\": true}\n\
+                  \n\
+                  {\"id\": 2, \"name\": \"Bob\", \"score\": null, \"active\": false, \"tags\": [\"a\"]}\n";
+    let rows = from_ndjson(ndjson.as_bytes()).unwrap();
+    assert_eq!(
+        rows.to_string().unwrap(),
+        concat!(
+            r#"[{"active"=>true,"id"=>1,"name"=>"Alice","score"=>9.5},"#,
+            r#"{"active"=>false,"id"=>2,"name"=>"Bob","score"=>?,"tags"=>["a"]}]"#,
+        )
+    );
+    let rows = from_ndjson_with(ndjson.as_bytes(), &SchemaHint::new().optional("score")).unwrap();
+    let people = keon::from_str::<Vec<Person>>(&rows.to_string().unwrap()).unwrap();
+    assert_eq!((people[0].score, people[1].score), (Some(9.5), None));
+
+    let e = from_ndjson("{}\n\n{\"id\": }\n".as_bytes()).unwrap_err();
+    assert_eq!(
+        format!("{:#}", e),
+        ":3:8 (deserialize) expected value\n    while parsing element 1"
+    );
+}
@@ -0,0 +1,50 @@
+use keon::{DeserializeConfig, Deserializer, ErrorKind, WarningKind};
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Switches {
+    a: bool,
+    b: bool,
+    c: bool,
+    d: bool,
+}
+
+fn lenient() -> DeserializeConfig {
+    let mut cfg = DeserializeConfig::strict();
+    cfg.lenient_bools = true;
+    cfg
+}
+
+#[test]
+fn lenient_bools() {
+    let mut der = Deserializer::with_config("{ a: yes, b: no,\n  c: on, d: true }", lenient());
+    let switches = Switches::deserialize(&mut der).unwrap();
+    der.finish().unwrap();
+
+    assert_eq!(
+        switches,
+        Switches {
+            a: true,
+            b: false,
+            c: true,
+            d: true,
+        }
+    );
+
+    let warnings = der.warnings();
+    assert_eq!(warnings.len(), 3);
+    assert_eq!(warnings[0].kind, WarningKind::LenientBool("yes".into()));
+    assert_eq!(warnings[2].to_string().split(' ').next().unwrap(), ":2:6");
+}
+
+#[test]
+fn strict_by_default() {
+    assert_eq!(
+        keon::from_str::<bool>("yes").unwrap_err().kind,
+        ErrorKind::Deserialize("invalid type: enum, expected a boolean".into())
+    );
+
+    // Only applies to booleans.
+    let mut der = Deserializer::with_config("yes", lenient());
+    assert!(String::deserialize(&mut der).is_err());
+}
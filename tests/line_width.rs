@@ -0,0 +1,106 @@
+mod util;
+
+use keon::{Expand, SerializeConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use util::ser_with;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Dot,
+    Pair(i32, i32),
+    Point { x: i32, y: i32 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Scene {
+    name: String,
+    single: (u8,),
+    points: Vec<Point>,
+    shapes: Vec<Shape>,
+    tags: BTreeMap<String, Option<Vec<u8>>>,
+    empty: Vec<u8>,
+}
+
+fn scene() -> Scene {
+    Scene {
+        name: "Intro".into(),
+        single: (1,),
+        points: vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }],
+        shapes: vec![Shape::Dot, Shape::Pair(1, 2), Shape::Point { x: 3, y: 4 }],
+        tags: BTreeMap::from([("a".into(), Some(vec![1, 2])), ("b".into(), None)]),
+        empty: vec![],
+    }
+}
+
+#[test]
+fn fitted() {
+    let s = ser_with(&scene(), SerializeConfig::builder().line_width(Some(60)).build());
+    assert_eq!(
+        s,
+        r#"(Scene) {
+    name: "Intro",
+    single: (1,),
+    points: [
+        (Point) { x: 1, y: 2 },
+        (Point) { x: 3, y: 4 },
+    ],
+    shapes: [
+        Shape::Dot,
+        Shape::Pair(1, 2),
+        Shape::Point { x: 3, y: 4 },
+    ],
+    tags: { "a" => ? [1, 2], "b" => ? },
+    empty: [],
+}"#
+    );
+    assert!(s.lines().all(|line| line.chars().count() <= 60));
+    assert_eq!(keon::from_str::<Scene>(&s).unwrap(), scene());
+}
+
+#[test]
+fn widths() {
+    // The comma after a value counts, the outermost value is never inlined.
+    assert_eq!(
+        ser_with(&vec![(1, 2)], SerializeConfig::builder().line_width(Some(11)).build()),
+        "[\n    (1, 2),\n]"
+    );
+    assert_eq!(
+        ser_with(&vec![(1, 2)], SerializeConfig::builder().line_width(Some(10)).build()),
+        "[\n    (\n        1,\n        2,\n    ),\n]"
+    );
+
+    // Columns are counted in characters.
+    assert_eq!(
+        ser_with(
+            &vec![vec!["ひらがな"]],
+            SerializeConfig::builder().line_width(Some(17)).build()
+        ),
+        "[\n    [\"ひらがな\"],\n]"
+    );
+
+    // Without a width, everything is expanded.
+    assert_eq!(
+        ser_with(&vec![(1, 2)], SerializeConfig::builder().line_width(None).build()),
+        keon::to_string_pretty(&vec![(1, 2)]).unwrap()
+    );
+    assert_eq!(
+        ser_with(&scene(), SerializeConfig::builder().line_width(Some(0)).build()),
+        keon::to_string_pretty(&scene()).unwrap()
+    );
+}
+
+#[test]
+fn minimized_depth() {
+    let cfg = SerializeConfig::builder()
+        .line_width(Some(80))
+        .expand(Expand::UntilDepth(2))
+        .build();
+    assert_eq!(ser_with(&vec![vec![vec![1, 2]]], cfg), "[\n    [[1,2]],\n]");
+}
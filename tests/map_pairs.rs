@@ -0,0 +1,82 @@
+mod util;
+
+use keon::{DeserializeConfig, ErrorKind};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use util::de_with;
+
+const PAIRS: DeserializeConfig = {
+    let mut cfg = DeserializeConfig::strict();
+    cfg.map_pairs = true;
+    cfg
+};
+
+#[test]
+fn map_into_pairs() {
+    let pairs = vec![("a".to_string(), 1), ("b".to_string(), 2), ("a".to_string(), 3)];
+    assert_eq!(
+        de_with::<Vec<(String, i32)>>("{ a: 1, b: 2, a: 3 }", PAIRS).unwrap(),
+        pairs
+    );
+    assert_eq!(
+        de_with::<Vec<(String, i32)>>(r#"{ "a" => 1, "b" => 2, "a" => 3, }"#, PAIRS).unwrap(),
+        pairs
+    );
+    assert_eq!(de_with::<Vec<(u8, char)>>("{}", PAIRS).unwrap(), vec![]);
+
+    // Sequences of pairs are still sequences.
+    assert_eq!(
+        de_with::<Vec<(String, i32)>>(r#"[("a", 1)]"#, PAIRS).unwrap(),
+        pairs[..1]
+    );
+
+    // Nested, and into a struct of two fields.
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Binding(String, Vec<(u8, u8)>);
+    assert_eq!(
+        de_with::<Vec<Binding>>(r#"{ ctrl: { 1 => 2, 1 => 3 } }"#, PAIRS).unwrap(),
+        vec![Binding("ctrl".into(), vec![(1, 2), (1, 3)])]
+    );
+
+    assert!(keon::from_str::<Vec<(String, i32)>>("{ a: 1 }").is_err());
+}
+
+#[test]
+fn pairs_into_map() {
+    let map = de_with::<BTreeMap<String, i32>>(r#"[("a", 1), ("b", 2,), ("a", 3),]"#, PAIRS).unwrap();
+    assert_eq!(map, BTreeMap::from([("a".into(), 3), ("b".into(), 2)]));
+    // Duplicate keys are up to the map, as in braces.
+    assert_eq!(
+        de_with::<BTreeMap<String, i32>>("{ a: 1, b: 2, a: 3 }", PAIRS).unwrap(),
+        map
+    );
+    assert_eq!(de_with::<HashMap<u8, u8>>("[]", PAIRS).unwrap(), HashMap::new());
+
+    // Maps are still maps, and structs are not affected.
+    assert_eq!(
+        de_with::<BTreeMap<u8, u8>>("{ 1 => 2 }", PAIRS).unwrap(),
+        BTreeMap::from([(1, 2)])
+    );
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Point {
+        x: i32,
+    }
+    assert!(de_with::<Point>(r#"[("x", 1)]"#, PAIRS).is_err());
+
+    let e = de_with::<BTreeMap<u8, u8>>("[(1, 2), 3]", PAIRS).unwrap_err();
+    assert_eq!(e.to_string(), ":1:10 expected a pair `(key, value)`");
+    let e = de_with::<BTreeMap<u8, u8>>("[(1, 2, 3)]", PAIRS).unwrap_err();
+    assert_eq!(e.kind, ErrorKind::ExpectedPair);
+    let e = de_with::<BTreeMap<u8, u8>>("[(1)]", PAIRS).unwrap_err();
+    assert_eq!(e.kind, ErrorKind::ExpectedComma);
+
+    assert!(keon::from_str::<BTreeMap<u8, u8>>("[(1, 2)]").is_err());
+}
+
+#[test]
+fn set_sugar_first() {
+    let mut cfg = PAIRS;
+    cfg.set_sugar = true;
+    assert_eq!(de_with::<Vec<String>>("{ a, b }", cfg).unwrap(), ["a", "b"]);
+}
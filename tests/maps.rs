@@ -3,15 +3,15 @@ mod util;
 
 #[test]
 fn roundtrips() {
-    let map = BTreeMap::<i32, i32>::from_iter(vec![(1, 2), (3, 4)].into_iter());
+    let map = BTreeMap::<i32, i32>::from_iter(vec![(1, 2), (3, 4)]);
 
     util::rt_min(&map, "{1=>2,3=>4}").unwrap();
     util::rt_pre(&map, "{\n    1 => 2,\n    3 => 4,\n}").unwrap();
 
     let mut mapmap = BTreeMap::<BTreeMap<i32, i32>, BTreeMap<i32, i32>>::new();
     mapmap.insert(
-        BTreeMap::from_iter(vec![(1, 2), (3, 4)].into_iter()),
-        BTreeMap::from_iter(vec![(5, 6), (7, 8)].into_iter()),
+        BTreeMap::from_iter(vec![(1, 2), (3, 4)]),
+        BTreeMap::from_iter(vec![(5, 6), (7, 8)]),
     );
 
     util::rt_min(&mapmap, "{{1=>2,3=>4}=>{5=>6,7=>8}}").unwrap();
@@ -29,3 +29,27 @@ fn roundtrips() {
     )
     .unwrap();
 }
+
+#[test]
+fn borrowed_idents() {
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Entry<'a> {
+        Name(&'a str),
+        Other(i32),
+    }
+
+    // Field names and variants are borrowed from the input, however long.
+    let doc = "{ a_field_name_longer_than_inline_strings: Unit, b: AVariantNameLongerThanInlineStrings, c: 1 }";
+    let map = keon::from_str::<BTreeMap<&str, Entry>>(doc).unwrap();
+    assert_eq!(
+        map,
+        BTreeMap::from_iter([
+            ("a_field_name_longer_than_inline_strings", Entry::Name("Unit")),
+            ("b", Entry::Name("AVariantNameLongerThanInlineStrings")),
+            ("c", Entry::Other(1)),
+        ])
+    );
+}
@@ -31,3 +31,46 @@ fn roundtrips() {
     util::rt_pre( &Enum::TupleUnit()   , "Enum::TupleUnit%"    ).unwrap();
     util::rt_pre( &Enum::StructUnit {} , "Enum::StructUnit {}" ).unwrap();
 }
+
+#[test]
+fn mayary_chains() {
+    use keon::{ErrorKind, Value};
+
+    #[derive(Debug, Deserialize)]
+    struct Wrapper(#[allow(dead_code)] u8);
+
+    let err = |s| keon::from_str::<Wrapper>(s).unwrap_err();
+    let at = |s| err(s).to_string().split(' ').next().unwrap().to_string();
+
+    // Points at the `%` that was rejected, the second one in chains.
+    assert_eq!(at("%"), ":1:1");
+    assert_eq!(at("%%"), ":1:2");
+    assert_eq!(at("% %"), ":1:3");
+    assert_eq!(at("%% 1"), ":1:2");
+    assert_eq!(at("% % % 1"), ":1:3");
+
+    assert_eq!(
+        err("%%").kind,
+        ErrorKind::InvalidMayary("invalid type: sequence, expected u8".into())
+    );
+    assert_eq!(
+        err("% %").to_string(),
+        ":1:3 (deserialize) invalid type: sequence, expected u8; \
+         note that `%` is a newtype wrapper with zero or one item: \
+         `%` alone is nullary, `% T` wraps `T` and `% %` wraps a nullary"
+    );
+
+    // Errors inside are still located by themselves.
+    assert_eq!(at("% -1"), ":1:3");
+
+    // Well-formed chains.
+    assert_eq!(keon::from_str::<Wrapper>("%1").unwrap().0, 1);
+    assert_eq!(
+        keon::from_str::<Value>("% % %").unwrap(),
+        Value::Newtype(Box::new(Value::Newtype(Box::new(Value::Seq(vec![])))))
+    );
+    assert_eq!(
+        keon::from_str::<Value>("[%, %%, % % 1,]").unwrap(),
+        keon::from_str::<Value>("[%, % %, %%1]").unwrap()
+    );
+}
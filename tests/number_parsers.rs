@@ -0,0 +1,89 @@
+use keon::{Deserializer, ErrorKind, Value};
+use serde::Deserialize;
+
+/// Thousandths, e.g. `1.25d`.
+#[derive(Debug, PartialEq, Deserialize)]
+struct Decimal(i64);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Ratio(u32, u32);
+
+/// Meters, e.g. `3.5m` or `-2ft`.
+#[derive(Debug, PartialEq, Deserialize)]
+struct Length {
+    meters: f64,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Recipe {
+    price: Decimal,
+    share: Ratio,
+    height: Length,
+    count: u32,
+}
+
+fn decimal(text: &str) -> Option<Value> {
+    let (int, frac) = text.strip_suffix('d')?.split_once('.')?;
+    let frac = format!("{:0<3}", frac).parse::<i64>().ok()?;
+    let value = int.parse::<i64>().ok()? * 1000 + frac;
+    Some(Value::Newtype(Box::new(value.into())))
+}
+
+fn ratio(text: &str) -> Option<Value> {
+    let (num, den) = text.split_once('/')?;
+    Some(Value::Seq(vec![
+        num.parse::<u32>().ok()?.into(),
+        den.parse::<u32>().ok()?.into(),
+    ]))
+}
+
+fn length(text: &str) -> Option<Value> {
+    let meters = match text.strip_suffix("ft") {
+        Some(num) => num.parse::<f64>().ok()? * 0.3048,
+        None => text.strip_suffix('m')?.parse::<f64>().ok()?,
+    };
+    Some(Value::Seq(vec![meters.into()]))
+}
+
+fn deserialize<'de, T: Deserialize<'de>>(s: &'de str) -> keon::Result<T> {
+    let mut der = Deserializer::from_str(s);
+    der.number_parser(decimal).number_parser(ratio).number_parser(length);
+    let val = T::deserialize(&mut der)?;
+    der.finish()?;
+    Ok(val)
+}
+
+#[test]
+fn claimed() {
+    assert_eq!(
+        deserialize::<Recipe>("{ price: 1.25d, share: 1/3, height: 3.5m, count: 2 }").unwrap(),
+        Recipe {
+            price: Decimal(1250),
+            share: Ratio(1, 3),
+            height: Length { meters: 3.5 },
+            count: 2,
+        }
+    );
+    assert_eq!(
+        deserialize::<Vec<Length>>("[-2ft, 0.5m]").unwrap(),
+        [Length { meters: -0.6096 }, Length { meters: 0.5 }]
+    );
+
+    // Plain numbers, comments and hex floats are not affected.
+    assert_eq!(
+        deserialize::<Vec<f64>>("[1.5, 1//3\n, 0x1p-1, 2/*m*/]").unwrap(),
+        [1.5, 1.0, 0.5, 2.0]
+    );
+}
+
+#[test]
+fn unclaimed() {
+    let e = deserialize::<Recipe>("{ price: 1.25d, share: 1/3, height: 3.5km, count: 2 }").unwrap_err();
+    assert_eq!(e.kind, ErrorKind::UnclaimedNumber("3.5km".into()));
+
+    let e = keon::from_str::<Value>("[1, 3.5q]").unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        ":1:5 number `3.5q` has a suffix, but no number parser claimed it"
+    );
+}
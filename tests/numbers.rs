@@ -13,8 +13,354 @@ fn roundtrips() {
     util::rt_min(&2.3333f64, "2.3333").unwrap();
     util::rt_min(&f32::INFINITY, "inf").unwrap();
     util::rt_min(&f32::NEG_INFINITY, "-inf").unwrap();
-    util::rt_min(&10f32.powi(f32::MAX_10_EXP), "1.0e38").unwrap();
-    util::rt_min(&10f64.powi(f64::MAX_10_EXP), "1.0e308").unwrap();
-    util::rt_min(&10f32.powi(f32::MIN_10_EXP), "1.0e-37").unwrap();
-    util::rt_min(&10f64.powi(f64::MIN_10_EXP), "1.0e-307").unwrap();
+    util::rt_min(&1e38f32, "1.0e38").unwrap();
+    util::rt_min(&1e308f64, "1.0e308").unwrap();
+    util::rt_min(&1e-37f32, "1.0e-37").unwrap();
+    util::rt_min(&1e-307f64, "1.0e-307").unwrap();
+}
+
+#[test]
+fn denormals_and_signed_zeros() {
+    util::rt_min(&5e-324f64, "5.0e-324").unwrap();
+    util::rt_min(&(f64::MIN_POSITIVE / 2.0), "1.1125369292536007e-308").unwrap();
+    util::rt_min(&1e-45f32, "1.0e-45").unwrap();
+    util::rt_min(&(f32::MIN_POSITIVE / 2.0), "5.877472e-39").unwrap();
+
+    // Compared by bits, as `-0.0 == 0.0`.
+    let canonical = keon::SerializeConfig::canonical();
+    for (v, s, hex) in [(-0.0f64, "-0.0", "-0x0p0"), (0.0, "0.0", "0x0p0")] {
+        assert_eq!(keon::to_string(&v).unwrap(), s);
+        assert_eq!(keon::to_string_with(&v, &canonical).unwrap(), hex);
+        for s in [s, hex] {
+            assert_eq!(keon::from_str::<f64>(s).unwrap().to_bits(), v.to_bits());
+            assert_eq!(keon::from_str::<f32>(s).unwrap().to_bits(), (v as f32).to_bits());
+        }
+    }
+    let value = keon::to_string(&keon::from_str::<keon::Value>("-0.0").unwrap()).unwrap();
+    assert_eq!(value, "-0.0");
+
+    // Every `f32` reads back as itself, from its shortest decimal read as `f64` first.
+    let mut x = 0x9e37_79b9_u32;
+    for _ in 0..20_000 {
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        let v = f32::from_bits(x);
+        if v.is_nan() {
+            continue;
+        }
+        for cfg in [keon::SerializeConfig::minimal(), canonical] {
+            let s = keon::to_string_with(&v, &cfg).unwrap();
+            assert_eq!(keon::from_str::<f32>(&s).unwrap().to_bits(), v.to_bits(), "{}", s);
+        }
+    }
+}
+
+/// A `Value` holds `f64`s only, widened from `f32`s without gaining digits.
+#[test]
+fn f32_in_values() {
+    use keon::Value;
+    use serde::{de::IntoDeserializer, Deserialize};
+
+    for (v, s) in [
+        (2.3333f32, "2.3333"),
+        (0.1, "0.1"),
+        (1e-45, "1.0e-45"),
+        (f32::MAX, "3.4028235e38"),
+        (f32::NEG_INFINITY, "-inf"),
+    ] {
+        // Visited as `f32` by other deserializers, e.g. `serde::de::value`.
+        let visited: keon::Result<Value> = Value::deserialize(v.into_deserializer());
+        for value in [Value::from(v), visited.unwrap()] {
+            assert_eq!(keon::to_string(&value).unwrap(), s);
+            assert_eq!(value.into_rust::<f32>().unwrap(), v);
+        }
+    }
+    assert!(matches!(Value::from(f32::NAN), Value::Number(keon::Number::Float(f)) if f.is_nan()));
+}
+
+/// Number literals are scanned as a whole, regardless of their length and digit separators.
+#[test]
+fn long_literals() {
+    // 200 digits.
+    let s = format!("1{}_0.0", "_000".repeat(66));
+    assert_eq!(s.chars().filter(char::is_ascii_digit).count(), 201);
+    assert_eq!(keon::from_str::<f64>(&s).unwrap(), 1e199);
+    assert_eq!(
+        keon::from_str::<f64>(&format!("0.{}_1", "000_".repeat(66))).unwrap(),
+        1e-199
+    );
+
+    let zeros = "0000_".repeat(50);
+    assert_eq!(keon::from_str::<u8>(&format!("{}1", zeros)).unwrap(), 1);
+    assert_eq!(keon::from_str::<i8>(&format!("-0x_{}f", zeros)).unwrap(), -15);
+    assert_eq!(
+        keon::from_str::<Vec<u8>>(&format!("[{0}1, {0}2,]", zeros)).unwrap(),
+        [1, 2]
+    );
+
+    // Too large for any integer, not truncated into a smaller one.
+    assert!(keon::from_str::<u64>(&"9999_".repeat(50)).is_err());
+}
+
+#[test]
+fn digit_separators() {
+    assert_eq!(keon::from_str::<u64>("1_000_000").unwrap(), 1_000_000);
+    assert_eq!(keon::from_str::<i64>("-1__0_").unwrap(), -10);
+    assert_eq!(keon::from_str::<u8>("0b_1111_0000").unwrap(), 0xF0);
+    assert_eq!(keon::from_str::<f64>("1_000.000_5").unwrap(), 1000.0005);
+    assert_eq!(keon::from_str::<f64>("1e-_3").unwrap(), 1e-3);
+
+    // Not leading.
+    assert!(keon::from_str::<u64>("_1").is_err());
+
+    // Written in groups.
+    let serialize = |group| {
+        let cfg = keon::SerializeConfig::builder()
+            .expand(keon::Expand::UntilDepth(0))
+            .digit_separators(group)
+            .build();
+        let ints = (u64::MAX, i64::MIN, 123_456u32, -1000i16, 999u16, 0u8);
+        let s = util::ser_with(&ints, cfg);
+        assert_eq!(keon::from_str::<(u64, i64, u32, i16, u16, u8)>(&s).unwrap(), ints);
+        s
+    };
+    assert_eq!(
+        serialize(Some(3)),
+        "(18_446_744_073_709_551_615,-9_223_372_036_854_775_808,123_456,-1_000,999,0)"
+    );
+    assert_eq!(
+        serialize(Some(4)),
+        "(1844_6744_0737_0955_1615,-922_3372_0368_5477_5808,12_3456,-1000,999,0)"
+    );
+    assert_eq!(
+        serialize(None),
+        "(18446744073709551615,-9223372036854775808,123456,-1000,999,0)"
+    );
+}
+
+#[test]
+fn hex_floats() {
+    assert_eq!(keon::from_str::<f64>("0x1.8p3").unwrap(), 12.0);
+    assert_eq!(keon::from_str::<f64>("-0x1.8p-1").unwrap(), -0.75);
+    assert_eq!(keon::from_str::<f64>("0xA.8P+0").unwrap(), 10.5);
+    assert_eq!(keon::from_str::<f64>("0x_1_0.0_1p1_0").unwrap(), 16388.0);
+    assert_eq!(keon::from_str::<f32>("0x1.fffffep127").unwrap(), f32::MAX);
+    assert_eq!(keon::from_str::<f64>("0x1.fffffffffffffp1023").unwrap(), f64::MAX);
+    assert_eq!(keon::from_str::<f64>("0x1p-1074").unwrap(), 5e-324);
+
+    // Rounding, ties to even.
+    assert_eq!(keon::from_str::<f64>("0x1.00000000000008p0").unwrap(), 1.0);
+    assert_eq!(
+        keon::from_str::<f64>("0x1.00000000000018p0").unwrap(),
+        1.0 + 2.0 * f64::EPSILON
+    );
+    assert_eq!(
+        keon::from_str::<f64>("0x1.000000000000080000000000000000000000001p0").unwrap(),
+        1.0 + f64::EPSILON
+    );
+    assert_eq!(keon::from_str::<f64>("0x1p-1075").unwrap(), 0.0);
+    assert_eq!(keon::from_str::<f64>("0x1.fffffffffffff8p1023").unwrap(), f64::INFINITY);
+
+    // The binary exponent is required.
+    assert!(keon::from_str::<f64>("0x1.8").is_err());
+    assert!(keon::from_str::<f64>("0x1p").is_err());
+}
+
+#[test]
+fn significant_digits() {
+    use keon::{Expand, SerializeConfig};
+    use util::ser_with;
+
+    let digits = |digits| {
+        SerializeConfig::builder()
+            .expand(Expand::UntilDepth(0))
+            .significant_digits(digits)
+            .build()
+    };
+
+    let floats = vec![
+        0.1 + 0.2,
+        1234.5678,
+        2.0 / 3.0,
+        -1e-7 / 3.0,
+        1.0,
+        6.02214076e23,
+        f64::NAN,
+    ];
+    assert_eq!(
+        ser_with(&floats, digits(None)),
+        "[0.30000000000000004,1234.5678,0.6666666666666666,-3.3333333333333334e-8,1.0,6.02214076e23,NaN]"
+    );
+    assert_eq!(
+        ser_with(&floats, digits(Some(15))),
+        "[0.3,1234.5678,0.666666666666667,-3.33333333333333e-8,1.0,6.02214076e23,NaN]"
+    );
+    assert_eq!(
+        ser_with(&floats, digits(Some(3))),
+        "[0.3,1230.0,0.667,-3.33e-8,1.0,6.02e23,NaN]"
+    );
+
+    // Rounded from the exact value, `0.15` is slightly less and `0.25` a tie to even.
+    assert_eq!(
+        ser_with(&vec![0.15, 0.25, 0.35, 9.96], digits(Some(1))),
+        "[0.1,0.2,0.3,10.0]"
+    );
+    assert_eq!(
+        ser_with(&vec![1.0f32 / 3.0, 16777215.0], digits(Some(4))),
+        "[0.3333,16780000.0]"
+    );
+    assert_eq!(ser_with(&vec![1.0f32 / 3.0], digits(None)), "[0.33333334]");
+}
+
+#[test]
+fn unsigned_radix() {
+    use keon::{Radix, SerializeConfig, SerializeConfigBuilder};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Register {
+        mask: u8,
+        addr: u64,
+        offset: i32,
+    }
+
+    let reg = Register {
+        mask: 0b1010,
+        addr: 0xdead_beef,
+        offset: -16,
+    };
+    for (radix, expected) in [
+        (Radix::Decimal, "{mask:10,addr:3735928559,offset:-16}"),
+        (
+            Radix::Binary,
+            "{mask:0b1010,addr:0b11011110101011011011111011101111,offset:-16}",
+        ),
+        (Radix::Octal, "{mask:0o12,addr:0o33653337357,offset:-16}"),
+        (Radix::Hexadecimal, "{mask:0xa,addr:0xdeadbeef,offset:-16}"),
+    ] {
+        let cfg = SerializeConfigBuilder::from(SerializeConfig::minimal())
+            .unsigned_radix(radix)
+            .build();
+        let s = util::ser_with(&reg, cfg);
+        assert_eq!(s, expected);
+        assert_eq!(keon::from_str::<Register>(&s).unwrap(), reg);
+    }
+}
+
+#[test]
+fn hex_float_adapter() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Exact {
+        #[serde(with = "keon::adapters::hex_float")]
+        a: f64,
+        #[serde(with = "keon::adapters::hex_float")]
+        b: f32,
+        c: f64,
+    }
+
+    util::rt_min(
+        &Exact {
+            a: 0.1,
+            b: 12.0,
+            c: 0.1,
+        },
+        "{a:0x1.999999999999ap-4,b:0x1.8p3,c:0.1}",
+    )
+    .unwrap();
+    util::rt_min(
+        &Exact {
+            a: -0.0,
+            b: f32::MIN_POSITIVE,
+            c: 0.0,
+        },
+        "{a:-0x0p0,b:0x1p-126,c:0.0}",
+    )
+    .unwrap();
+    util::rt_min(
+        &Exact {
+            a: 5e-324,
+            b: f32::NEG_INFINITY,
+            c: 1.0,
+        },
+        "{a:0x0.0000000000001p-1022,b:-inf,c:1.0}",
+    )
+    .unwrap();
+
+    // Plain newtypes elsewhere.
+    let json = serde_json::to_string(&Exact { a: 1.5, b: 2.0, c: 3.0 }).unwrap();
+    assert_eq!(json, r#"{"a":1.5,"b":2.0,"c":3.0}"#);
+}
+
+#[test]
+fn nan_payloads() {
+    let cfg = keon::SerializeConfig::builder().nan_payloads(true).build();
+    let roundtrip = |v: f64| {
+        let s = keon::to_string_with(&v, &cfg).unwrap();
+        assert_eq!(keon::from_str::<f64>(&s).unwrap().to_bits(), v.to_bits());
+        s
+    };
+    assert_eq!(roundtrip(f64::NAN), "NaN");
+    assert_eq!(roundtrip(-f64::NAN), "NaN:0xfff8000000000000");
+    assert_eq!(
+        roundtrip(f64::from_bits(0x7ff8_0000_dead_beef)),
+        "NaN:0x7ff80000deadbeef"
+    );
+    assert_eq!(roundtrip(-0.0), "-0.0");
+    assert_eq!(roundtrip(1.5), "1.5");
+
+    let v = f32::from_bits(0xffc0_0001);
+    let s = keon::to_string_with(&vec![f32::NAN, v], &cfg).unwrap();
+    assert_eq!(s, "[\n    NaN,\n    NaN:0xffc00001,\n]");
+    let back = keon::from_str::<Vec<f32>>(&s).unwrap();
+    assert_eq!(
+        back.into_iter().map(f32::to_bits).collect::<Vec<_>>(),
+        [f32::NAN.to_bits(), v.to_bits()]
+    );
+
+    // Also with hexadecimal floats, and in values.
+    let cfg = keon::SerializeConfig::builder()
+        .nan_payloads(true)
+        .hex_floats(true)
+        .build();
+    let value = keon::from_str::<keon::Value>("[NaN:0x7ff0000000000001, 0x1p0]").unwrap();
+    assert_eq!(
+        keon::to_string_with(&value, &cfg).unwrap(),
+        "[\n    NaN:0x7ff0000000000001,\n    0x1p0,\n]"
+    );
+
+    // Written as they were otherwise.
+    assert_eq!(
+        keon::to_string(&-f64::NAN).unwrap(),
+        keon::to_string(&f64::NAN).unwrap()
+    );
+
+    let e = keon::from_str::<f64>("NaN:0x3ff0000000000000").unwrap_err();
+    assert_eq!(e.kind, keon::ErrorKind::InvalidNanPayload);
+    assert!(keon::from_str::<f64>("NaN:0x7ff800").is_err());
+}
+
+#[test]
+fn incomplete_nan_payloads() {
+    use keon::Value;
+
+    // Only `:0x` and 8 or 16 hexadecimal digits are a payload, anything else is left after `NaN`.
+    for s in [
+        "NaN:",
+        "NaN:0",
+        "NaN:0x123",
+        "NaN:0x7ff800000",
+        "[NaN: 1]",
+        "{a: 1} NaN:",
+    ] {
+        assert!(keon::from_str::<Value>(s).is_err(), "{s:?}");
+        assert!(keon::from_str::<f64>(s).is_err(), "{s:?}");
+        keon::debug::dump_tokens(s);
+    }
+    assert_eq!(
+        keon::debug::dump_tokens("NaN:0"),
+        ":1:1 0..3 literal Float(NaN)\n:1:4 3..4 `:`\n:1:5 4..5 literal UInt(0)\n"
+    );
 }
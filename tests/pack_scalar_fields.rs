@@ -0,0 +1,71 @@
+use keon::SerializeConfig;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Anchor {
+    Center,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Sprite {
+    x: i32,
+    y: i32,
+    visible: bool,
+    anchor: Anchor,
+    frames: Vec<u8>,
+    name: String,
+    tint: Option<u32>,
+}
+
+fn serialize(value: &Sprite, width: usize) -> String {
+    let cfg = SerializeConfig::builder().pack_scalar_fields(Some(width)).build();
+    let s = keon::to_string_with(value, &cfg).unwrap();
+    assert_eq!(&keon::from_str::<Sprite>(&s).unwrap(), value);
+    s
+}
+
+#[test]
+fn pack_scalar_fields() {
+    let sprite = Sprite {
+        x: 1,
+        y: 2,
+        visible: true,
+        anchor: Anchor::Center,
+        frames: vec![3, 4],
+        name: "hero".into(),
+        tint: Some(0xff0000),
+    };
+    assert_eq!(
+        serialize(&sprite, 80),
+        "(Sprite) {
+    x: 1, y: 2, visible: true, anchor: Anchor::Center,
+    frames: [
+        3,
+        4,
+    ],
+    name: \"hero\", tint: ? 16711680,
+}"
+    );
+    assert_eq!(
+        serialize(&sprite, 30),
+        "(Sprite) {
+    x: 1, y: 2, visible: true,
+    anchor: Anchor::Center,
+    frames: [
+        3,
+        4,
+    ],
+    name: \"hero\",
+    tint: ? 16711680,
+}"
+    );
+
+    // Written as they were otherwise.
+    assert!(keon::to_string_pretty(&sprite)
+        .unwrap()
+        .contains("    x: 1,\n    y: 2,\n"));
+    let mut cfg = SerializeConfig::minimal();
+    let minimal = keon::to_string_with(&sprite, &cfg).unwrap();
+    cfg.pack_scalar_fields = Some(80);
+    assert_eq!(keon::to_string_with(&sprite, &cfg).unwrap(), minimal);
+}
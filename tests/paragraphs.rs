@@ -1,3 +1,5 @@
+#![cfg(feature = "syntax-paragraph")]
+
 mod util;
 
 #[test]
@@ -49,3 +51,255 @@ int main() {
     )
     .unwrap();
 }
+
+#[test]
+fn wrapping() {
+    use keon::{SerializeConfig, Serializer};
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    fn wrapped<T: Serialize>(value: &T, width: usize) -> String {
+        let mut cfg = SerializeConfig::comfort();
+        cfg.wrap_width = Some(width);
+        let mut buf = Vec::new();
+        value.serialize(&mut Serializer::new(&mut buf, cfg)).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+    fn roundtrip<T: Serialize + for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug>(value: &T, width: usize) {
+        let s = wrapped(value, width);
+        assert_eq!(&keon::from_str::<T>(&s).unwrap(), value, "{}", s);
+    }
+
+    let hamlet = String::from("To be, or not to be, that is the question:\nWhether 'tis nobler in the mind to suffer");
+    assert_eq!(
+        wrapped(&hamlet, 16),
+        "| To be, or not to
+         | be, that is the
+         | question:
+         ` Whether 'tis
+         | nobler in the
+         | mind to suffer"
+            .replace("         ", "")
+    );
+    assert_eq!(wrapped(&"short", 16), "\"short\"");
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Note {
+        Text(String),
+        Pair(String, String),
+    }
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Doc {
+        title: String,
+        body: String,
+        notes: Vec<Note>,
+        extra: Option<String>,
+        keys: BTreeMap<String, String>,
+    }
+
+    let long = "lorem ipsum dolor sit amet consectetur".to_string();
+    let doc = Doc {
+        title: "Doc".into(),
+        body: hamlet.clone(),
+        notes: vec![Note::Text(long.clone()), Note::Pair(long.clone(), long.clone())],
+        extra: Some(long.clone()),
+        keys: BTreeMap::from_iter([(long.clone(), long.clone())]),
+    };
+    let s = wrapped(&doc, 16);
+    assert_eq!(
+        s,
+        r#"(Doc) {
+    title: "Doc",
+    body: | To be, or not to
+          | be, that is the
+          | question:
+          ` Whether 'tis
+          | nobler in the
+          | mind to suffer
+    , notes: [
+        Note::Text(| lorem ipsum
+                   | dolor sit amet
+                   | consectetur
+        ),
+        Note::Pair(
+            | lorem ipsum
+            | dolor sit amet
+            | consectetur
+            , | lorem ipsum
+              | dolor sit amet
+              | consectetur
+        ),
+    ],
+    extra: ? | lorem ipsum
+             | dolor sit amet
+             | consectetur
+    , keys: {
+        "lorem ipsum dolor sit amet consectetur" => | lorem ipsum
+                                                    | dolor sit amet
+                                                    | consectetur
+    },
+}"#
+    );
+    roundtrip(&doc, 16);
+
+    // Edge cases, which are quoted if a paragraph cannot represent them.
+    for s in [
+        "",
+        "\n",
+        "\n\nafter blank lines",
+        "trailing newline\n",
+        "double  spaces  between  words  here",
+        "  leading spaces are kept for sure",
+        "trailing spaces are quoted     ",
+        "a very_long_word_without_any_spaces_at_all b",
+        "tabs\tare fine\tin the middle of lines",
+        "control \x01 characters are quoted",
+        "中文 也可以 按空格 换行 的吧",
+    ] {
+        roundtrip(&s.to_string(), 8);
+        roundtrip(&vec![s.to_string(), s.to_string()], 8);
+    }
+    assert_eq!(
+        wrapped(&"trailing spaces are quoted     ", 8),
+        "\"trailing spaces are quoted     \""
+    );
+}
+
+#[test]
+fn whitespace_rules() {
+    use keon::{DeserializeConfig, Deserializer};
+    use serde::Deserialize;
+
+    let read = |s: &str| keon::from_str::<String>(s).unwrap();
+
+    // One space after the start sign is removed, anything else is content.
+    assert_eq!(read("|text"), "text");
+    assert_eq!(read("|   three spaces"), "  three spaces");
+    assert_eq!(read("|\ttab"), "\ttab");
+    assert_eq!(read("| \ttab"), "\ttab");
+    assert_eq!(read("| a | b < c ` d"), "a | b < c ` d");
+    assert_eq!(
+        read("| fn main() {\n`     body();\n`\t\tbody();\n` }"),
+        "fn main() {\n    body();\n\t\tbody();\n}"
+    );
+
+    // Indentation before the start sign is ignored.
+    assert_eq!(read("| a\n\t  | b\n        ` c"), "a b\nc");
+
+    // Trailing whitespace is removed, so are whitespace-only lines.
+    assert_eq!(read("| a  \t\n| b"), "a b");
+    assert_eq!(read("| a\r\n| b\r\n"), "a b");
+    assert_eq!(read("| code\n`     \n`     more"), "code\n\n    more");
+
+    // Unless configured for `` ` `` lines.
+    let preserved = |s: &str| {
+        let mut cfg = DeserializeConfig::strict();
+        cfg.preserve_paragraph_indent = true;
+        String::deserialize(&mut Deserializer::with_config(s, cfg)).unwrap()
+    };
+    assert_eq!(preserved("| code\n`     \n`     more  "), "code\n    \n    more  ");
+    assert_eq!(preserved("| code\r\n`     more  \r\n"), "code\n    more  ");
+    assert_eq!(preserved("| a  \n| b  "), "a b");
+}
+
+#[test]
+fn crlf_wrapped() {
+    use keon::{Newline, SerializeConfig, Serializer};
+    use serde::Serialize;
+
+    let text = "To be, or not to be,\nthat is the question.".to_string();
+    let cfg = SerializeConfig::builder()
+        .wrap_width(Some(12))
+        .newline(Newline::CrLf)
+        .build();
+    let mut buf = Vec::new();
+    text.serialize(&mut Serializer::new(&mut buf, cfg)).unwrap();
+    let s = String::from_utf8(buf).unwrap();
+    assert_eq!(s, "| To be, or\r\n| not to be,\r\n` that is the\r\n| question.");
+    assert_eq!(keon::from_str::<String>(&s).unwrap(), text);
+}
+
+#[test]
+fn multiline_only() {
+    use keon::{SerializeConfig, Serializer};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Item {
+        name: String,
+        description: String,
+    }
+
+    let item = Item {
+        name: "A string long enough that it would otherwise be wrapped".into(),
+        description: "First line, which is also rather long.\nSecond line.".into(),
+    };
+    let cfg = SerializeConfig::builder().wrap_width(Some(usize::MAX)).build();
+    let mut buf = Vec::new();
+    item.serialize(&mut Serializer::new(&mut buf, cfg)).unwrap();
+    let s = String::from_utf8(buf).unwrap();
+    assert_eq!(
+        s,
+        r#"(Item) {
+    name: "A string long enough that it would otherwise be wrapped",
+    description: | First line, which is also rather long.
+                 ` Second line.
+}"#
+    );
+    assert_eq!(keon::from_str::<Item>(&s).unwrap(), item);
+}
+
+#[test]
+fn nested_layout() {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Scene {
+        name: String,
+        actor: Actor,
+        lines: Vec<String>,
+        notes: BTreeMap<String, String>,
+    }
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Actor {
+        role: String,
+        speech: String,
+        mood: u8,
+    }
+
+    let scene = Scene {
+        name: "Act III".into(),
+        actor: Actor {
+            role: "Hamlet".into(),
+            speech: "To be, or not to be,\nthat is the question.".into(),
+            mood: 3,
+        },
+        lines: vec!["one\ntwo".into(), "x".into()],
+        notes: BTreeMap::from_iter([("k".into(), "a\nb".into())]),
+    };
+    let cfg = keon::SerializeConfig::builder().wrap_width(Some(usize::MAX)).build();
+    let s = keon::to_string_with(&scene, &cfg).unwrap();
+    assert_eq!(
+        s,
+        r#"(Scene) {
+    name: "Act III",
+    actor: (Actor) {
+        role: "Hamlet",
+        speech: | To be, or not to be,
+                ` that is the question.
+        , mood: 3,
+    },
+    lines: [
+        | one
+        ` two
+        , "x",
+    ],
+    notes: {
+        "k" => | a
+               ` b
+    },
+}"#
+    );
+    assert_eq!(keon::from_str::<Scene>(&s).unwrap(), scene);
+}
@@ -37,3 +37,59 @@ fn roundtrips() {
     )
     .unwrap();
 }
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "Weird Name")]
+enum Weird {
+    Unit,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Server {
+    #[serde(rename = "0-port")]
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "Not an identifier")]
+struct NonIdents {
+    #[serde(rename = "weird key!")]
+    weird: Weird,
+    #[serde(rename = "")]
+    empty: u8,
+    server: Server,
+    #[serde(rename = "über")]
+    uber: bool,
+}
+
+/// Keys that are not identifiers are quoted, names are left out.
+#[test]
+fn non_identifiers() {
+    let value = NonIdents {
+        weird: Weird::Unit,
+        empty: 1,
+        server: Server { port: 80 },
+        uber: true,
+    };
+    util::rt_min(&value, r#"{"weird key!"=>Unit,""=>1,server:{"0-port"=>80},über:true}"#).unwrap();
+    util::rt_pre(
+        &value,
+        r#"{
+    "weird key!" => Unit,
+    "" => 1,
+    server: (Server) {
+        "0-port" => 80,
+    },
+    über: true,
+}"#,
+    )
+    .unwrap();
+
+    let cfg = keon::SerializeConfig::builder().dotted_keys(true).build();
+    let s = keon::to_string_with(&value, &cfg).unwrap();
+    assert!(
+        s.contains("\n    server: (Server) {\n        \"0-port\" => 80,\n    },\n"),
+        "{}",
+        s
+    );
+}
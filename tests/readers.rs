@@ -0,0 +1,181 @@
+use keon::Value;
+use serde::Deserialize;
+use std::io::{self, Read};
+
+/// Yields 1 to 7 bytes per read in turn, to split characters at every possible position.
+struct Chunked<'a> {
+    src: &'a [u8],
+    n: usize,
+}
+impl Read for Chunked<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.n = self.n % 7 + 1;
+        let len = self.n.min(buf.len()).min(self.src.len());
+        buf[..len].copy_from_slice(&self.src[..len]);
+        self.src = &self.src[len..];
+        Ok(len)
+    }
+}
+
+#[test]
+fn multi_byte_at_chunk_boundaries() {
+    // Characters of 2, 3 and 4 bytes, split by reads at every position.
+    let doc = r#"{ name: "café", keys: ['✱', '😀'], "ひらがな" => "é✱😀" }"#;
+    let expected = Value::from_str(doc).unwrap();
+    for n in 0..7 {
+        let reader = Chunked { src: doc.as_bytes(), n };
+        assert_eq!(
+            keon::from_reader::<_, Value>(reader).unwrap(),
+            expected,
+            "chunk sizes starting at {}",
+            n + 1
+        );
+    }
+
+    // Invalid UTF-8 is located in the whole input, not in the read it came in.
+    let doc = b"['\xE2\x9C\xB1', \"\xF0\x9F\x98\x80\"]\n\"\xE9\"";
+    for n in 0..7 {
+        let e = keon::from_reader::<_, Value>(Chunked { src: doc, n }).unwrap_err();
+        assert_eq!(e.to_string(), ":2:2 invalid UTF-8 sequence `E9` at byte offset 17");
+    }
+}
+
+#[test]
+fn typed_from_reader() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Action {
+        Up,
+        Down,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Keybinds {
+        up: (Action, char),
+        down: (Action, char),
+    }
+
+    let doc = b"(Keybinds) { up: (Action::Up, 'W'), down: (Down, 'S') }";
+    let keybinds: Keybinds = keon::from_reader(Chunked { src: doc, n: 0 }).unwrap();
+    assert_eq!(
+        keybinds,
+        Keybinds {
+            up: (Action::Up, 'W'),
+            down: (Action::Down, 'S'),
+        }
+    );
+}
+
+#[test]
+fn value_from_reader() {
+    let doc = "{ side: Side::Left, keys: ['W', 'S'], speed: 1.5 }";
+    let expected = Value::from_str(doc).unwrap();
+    assert_eq!(
+        Value::from_reader(Chunked {
+            src: doc.as_bytes(),
+            n: 0
+        })
+        .unwrap(),
+        expected
+    );
+    assert_eq!(Value::from_bytes(doc.as_bytes()).unwrap(), expected);
+
+    let e = Value::from_bytes(b"[\"caf\xE9\"]").unwrap_err();
+    assert_eq!(e.to_string(), ":1:6 invalid UTF-8 sequence `E9` at byte offset 5");
+}
+
+#[test]
+fn invalid_utf8() {
+    use keon::ErrorKind;
+
+    let doc = b"{\n  name: \"caf\xE9\",\n}";
+    let e = keon::from_reader::<_, Value>(Chunked { src: doc, n: 0 }).unwrap_err();
+    assert_eq!(
+        e.kind,
+        ErrorKind::InvalidUtf8 {
+            offset: 14,
+            bytes: vec![0xE9],
+            error: std::str::from_utf8(&doc[14..]).unwrap_err(),
+        }
+    );
+    assert_eq!(e.to_string(), ":2:13 invalid UTF-8 sequence `E9` at byte offset 14");
+
+    // Only the invalid sequence is shown, up to 4 bytes.
+    let e = keon::from_bytes::<Value>(b"\"\xF0\x9F\x98\" \xFF").unwrap_err();
+    assert_eq!(e.to_string(), ":1:2 invalid UTF-8 sequence `F0 9F 98` at byte offset 1");
+
+    // Truncated at the end.
+    let e = keon::from_bytes::<Value>("'✱'".as_bytes().split_at(3).0).unwrap_err();
+    assert_eq!(e.to_string(), ":1:2 invalid UTF-8 sequence `E2 9C` at byte offset 1");
+}
+
+#[test]
+#[cfg(feature = "syntax-paragraph")]
+fn lossy_utf8() {
+    use keon::ErrorKind;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Record {
+        name: String,
+        note: String,
+    }
+
+    let doc = b"{ name: \"caf\xE9\", note:\n    | na\xEFve\n    | r\xE9sum\xE9 \xF0\x9F\x98\n}";
+    let record: Record = keon::from_reader_lossy(Chunked { src: doc, n: 0 }).unwrap();
+    assert_eq!(
+        record,
+        Record {
+            name: "caf\u{FFFD}".into(),
+            note: "na\u{FFFD}ve r\u{FFFD}sum\u{FFFD} \u{FFFD}".into(),
+        }
+    );
+
+    // Strict outside of strings.
+    assert!(keon::from_bytes::<Record>(b"{ name: \"caf\xE9\", note: \"\" }").is_err());
+    let doc = b"{ name: \"ok\", note: \"\" } // \xE9";
+    let e = keon::from_bytes_lossy::<Record>(doc).unwrap_err();
+    assert_eq!(
+        e.kind,
+        ErrorKind::InvalidUtf8 {
+            offset: 28,
+            bytes: vec![0xE9],
+            error: std::str::from_utf8(&doc[28..]).unwrap_err(),
+        }
+    );
+    let e = keon::from_bytes_lossy::<char>(b"'\xE9'").unwrap_err();
+    assert_eq!(e.to_string(), ":1:2 invalid UTF-8 sequence `E9` at byte offset 1");
+    let e = keon::from_bytes_lossy::<Value>(b"[\"\xE9\", b\"\xE9\"]").unwrap_err();
+    assert_eq!(e.to_string(), ":1:9 invalid UTF-8 sequence `E9` at byte offset 8");
+}
+
+#[test]
+fn lossy_encoding() {
+    let doc = "{ name: \"café\", keys: ['✱', '😀'] }";
+    let expected = Value::from_str(doc).unwrap();
+
+    let utf16 = |bom: [u8; 2], unit: fn(u16) -> [u8; 2]| {
+        let mut bytes = bom.to_vec();
+        doc.encode_utf16().for_each(|u| bytes.extend(unit(u)));
+        bytes
+    };
+    let utf8 = [&[0xEF, 0xBB, 0xBF], doc.as_bytes()].concat();
+    for bytes in [
+        doc.as_bytes().to_vec(),
+        utf8,
+        utf16([0xFF, 0xFE], u16::to_le_bytes),
+        utf16([0xFE, 0xFF], u16::to_be_bytes),
+    ] {
+        assert_eq!(keon::from_slice_lossy_encoding::<Value>(&bytes).unwrap(), expected);
+    }
+
+    // An unpaired surrogate, and a dangling byte.
+    let bytes = [0xFF, 0xFE, b'"', 0, 0x00, 0xD8, b'"', 0, b' '];
+    assert_eq!(
+        keon::from_slice_lossy_encoding::<String>(&bytes[..8]).unwrap(),
+        "\u{FFFD}"
+    );
+    assert!(keon::from_slice_lossy_encoding::<String>(&bytes).is_err());
+
+    // Byte offsets count the BOM.
+    let e = keon::from_slice_lossy_encoding::<Value>(b"\xEF\xBB\xBF[\"\xE9\", \xE9]").unwrap_err();
+    assert_eq!(e.to_string(), ":1:7 invalid UTF-8 sequence `E9` at byte offset 9");
+}
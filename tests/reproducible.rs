@@ -0,0 +1,98 @@
+use keon::SerializeConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Byte-identical outputs on every platform, as checked by hashes or signatures of artifacts.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Artifact {
+    name: String,
+    notes: String,
+    floats: Vec<f64>,
+    ratio: f32,
+    sizes: HashMap<String, u64>,
+    #[serde(with = "keon::adapters::sorted")]
+    tags: HashSet<String>,
+}
+
+fn artifact() -> Artifact {
+    // What `0.0 / 0.0` yields on x86, it's positive elsewhere.
+    let nan = f64::from_bits(0xfff8_0000_0000_0000);
+    Artifact {
+        name: "release".into(),
+        notes: "built on Windows\r\nsigned on Linux\n".into(),
+        floats: vec![0.1, -0.0, 1e300, 5e-324, f64::INFINITY, nan, 1.0 / 3.0],
+        ratio: 1.0 / 3.0,
+        sizes: ["linux", "macos", "windows", "android", "ios"]
+            .into_iter()
+            .zip(1..)
+            .map(|(os, i)| (os.to_string(), i * 1000))
+            .collect(),
+        tags: ["stable", "lts", "x86_64", "aarch64"]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+    }
+}
+
+#[test]
+fn canonical() {
+    let expected = concat!(
+        r#"{name:"release",notes:"built on Windows\r\nsigned on Linux\n","#,
+        r#"floats:[0x1.999999999999ap-4,-0x0p0,0x1.7e43c8800759cp996,0x0.0000000000001p-1022,inf,NaN,0x1.5555555555555p-2],"#,
+        r#"ratio:0x1.555556p-2,sizes:{"android"=>4000,"ios"=>5000,"linux"=>1000,"macos"=>2000,"windows"=>3000},"#,
+        r#"tags:["aarch64","lts","stable","x86_64"]}"#,
+    );
+    // Each `HashMap` and `HashSet` is seeded differently.
+    for _ in 0..8 {
+        let s = keon::to_string_with(&artifact(), &SerializeConfig::canonical()).unwrap();
+        assert_eq!(s.as_bytes(), expected.as_bytes());
+    }
+
+    let read = keon::from_str::<Artifact>(expected).unwrap();
+    assert_eq!(read.floats[1].to_bits(), (-0.0f64).to_bits());
+    assert!(read.floats[5].is_nan());
+    assert_eq!(read.ratio, artifact().ratio);
+    assert_eq!(read.tags, artifact().tags);
+}
+
+#[test]
+fn pretty() {
+    let expected = r#"(Artifact) {
+    name: "release",
+    notes: "built on Windows\r\nsigned on Linux\n",
+    floats: [
+        0.1,
+        -0.0,
+        1.0e300,
+        5.0e-324,
+        inf,
+        NaN,
+        0.3333333333333333,
+    ],
+    ratio: 0.33333334,
+    sizes: {
+        "android" => 4000,
+        "ios" => 5000,
+        "linux" => 1000,
+        "macos" => 2000,
+        "windows" => 3000,
+    },
+    tags: [
+        "aarch64",
+        "lts",
+        "stable",
+        "x86_64",
+    ],
+}"#;
+    let cfg = SerializeConfig::builder().sort_keys(true).build();
+    let s = keon::to_string_with(&artifact(), &cfg).unwrap();
+    assert_eq!(s.as_bytes(), expected.as_bytes());
+
+    // Only where asked for.
+    let cfg = SerializeConfig::builder()
+        .sort_keys(true)
+        .newline(keon::Newline::CrLf)
+        .build();
+    let s = keon::to_string_with(&artifact(), &cfg).unwrap();
+    assert_eq!(s, expected.replace('\n', "\r\n"));
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "schemars")]
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+
+#[allow(dead_code)]
+#[derive(Deserialize, JsonSchema)]
+struct Config {
+    name: String,
+    retries: Option<u32>,
+    separator: char,
+    #[serde(with = "serde_bytes")]
+    #[schemars(schema_with = "keon::schema::bytes")]
+    magic: Vec<u8>,
+    shape: Shape,
+    side: Option<Side>,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, JsonSchema)]
+enum Shape {
+    Dot,
+    Circle(f64),
+    Rect { w: f64, h: f64 },
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, JsonSchema)]
+enum Side {
+    Left,
+    Right,
+}
+
+#[test]
+fn conventions() {
+    let schema = keon::schema::schema_for::<Config>().to_value();
+    let props = &schema["properties"];
+
+    assert_eq!(props["name"].get("x-keon-option"), None);
+    assert_eq!(props["retries"]["x-keon-option"], json!(true));
+    assert_eq!(props["separator"]["x-keon-char"], json!(true));
+    assert_eq!(props["magic"]["x-keon-bytes"], json!(true));
+    assert_eq!(props["side"]["x-keon-option"], json!(true));
+
+    assert_eq!(
+        schema["$defs"]["Shape"]["x-keon-variants"],
+        json!(["Shape::Dot", "Shape::Circle", "Shape::Rect"]),
+    );
+    assert_eq!(
+        schema["$defs"]["Side"]["x-keon-variants"],
+        json!(["Side::Left", "Side::Right"])
+    );
+    assert_eq!(
+        schema["$defs"]["Shape"]["oneOf"][2]["properties"]["Rect"]["required"],
+        json!(["w", "h"])
+    );
+}
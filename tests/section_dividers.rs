@@ -0,0 +1,67 @@
+mod util;
+
+use keon::{Expand, SerializeConfig};
+use serde::{Deserialize, Serialize};
+use util::ser_with;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Save {
+    name: String,
+    inventory: Vec<Item>,
+    position: (i32, i32),
+    quests: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Item {
+    id: u32,
+    count: u8,
+}
+
+fn save() -> Save {
+    Save {
+        name: "Alex".into(),
+        inventory: vec![Item { id: 7, count: 3 }, Item { id: 12, count: 1 }],
+        position: (4, -2),
+        quests: vec!["intro".into()],
+    }
+}
+
+#[test]
+fn long_fields() {
+    let cfg = SerializeConfig::builder()
+        .section_dividers(Some(3))
+        .line_width(Some(40))
+        .build();
+    let s = ser_with(&save(), cfg);
+    assert_eq!(
+        s,
+        r#"(Save) {
+    name: "Alex",
+    // ---- inventory ----
+    inventory: [
+        (Item) { id: 7, count: 3 },
+        (Item) { id: 12, count: 1 },
+    ],
+    position: (4, -2),
+    quests: ["intro"],
+}"#
+    );
+    assert_eq!(keon::from_str::<Save>(&s).unwrap(), save());
+
+    // Without fitting, the position spans 4 lines too, but not the quests.
+    let cfg = SerializeConfig::builder().section_dividers(Some(3)).build();
+    let s = ser_with(&save(), cfg);
+    assert!(s.contains("\n    // ---- position ----\n    position: (\n"), "{s}");
+    assert!(!s.contains("// ---- quests"), "{s}");
+    assert_eq!(keon::from_str::<Save>(&s).unwrap(), save());
+
+    // Only the outermost struct, and only where pretty.
+    let nested = vec![save()];
+    assert!(!ser_with(&nested, cfg).contains("//"));
+    let cfg = SerializeConfig::builder()
+        .section_dividers(Some(0))
+        .expand(Expand::UntilDepth(1))
+        .build();
+    assert!(!ser_with(&save(), cfg).contains("//"));
+}
@@ -0,0 +1,49 @@
+use keon::{DeserializeConfig, Deserializer, Error, ErrorKind, SerializeConfig, Serializer, Value, Warning};
+use serde::Deserialize;
+use std::thread;
+
+const fn assert_send_sync<T: Send + Sync>() {}
+
+// Checked when compiled.
+const _: () = {
+    assert_send_sync::<Error>();
+    assert_send_sync::<ErrorKind>();
+    assert_send_sync::<Warning>();
+    assert_send_sync::<Value>();
+    assert_send_sync::<SerializeConfig>();
+    assert_send_sync::<DeserializeConfig>();
+    assert_send_sync::<Deserializer<'static>>();
+    assert_send_sync::<Serializer<Vec<u8>>>();
+};
+
+#[test]
+fn shared_across_threads() {
+    let cfg = SerializeConfig::minimal();
+    let source = String::from("[1, 2, 3]");
+
+    let outputs = thread::scope(|s| {
+        let handles = (0..4)
+            .map(|i| {
+                let (cfg, source) = (&cfg, &source);
+                s.spawn(move || {
+                    // Moved to this thread halfway through.
+                    let mut der = Deserializer::from_str(source);
+                    der.number_parser(|_| None);
+                    let mut values = s.spawn(move || Vec::<u8>::deserialize(&mut der)).join().unwrap()?;
+                    values.push(i);
+                    keon::to_string_with(&values, cfg)
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .collect::<Result<Vec<_>, Error>>()
+    })
+    .unwrap();
+    assert_eq!(outputs, ["[1,2,3,0]", "[1,2,3,1]", "[1,2,3,2]", "[1,2,3,3]"]);
+
+    let error = keon::from_str::<Value>("[1,").unwrap_err();
+    let error = thread::spawn(move || error).join().unwrap();
+    assert_eq!(error.kind, ErrorKind::UnexpectedEof);
+}
@@ -14,3 +14,35 @@ fn roundtrips() {
     util::rt_pre(&vec![0, 1, 2], "[\n    0,\n    1,\n    2,\n]").unwrap();
     util::rt_pre(&vec![0, 1, 2, 3], "[\n    0,\n    1,\n    2,\n    3,\n]").unwrap();
 }
+
+#[test]
+fn fixed_length() {
+    use arrayvec::ArrayVec;
+    use keon::ErrorKind;
+
+    fn err<T: serde::de::DeserializeOwned + std::fmt::Debug>(s: &str) -> keon::Error {
+        keon::from_str::<T>(s).unwrap_err()
+    }
+
+    assert_eq!(keon::from_str::<[i32; 3]>("[1, 2, 3]").unwrap(), [1, 2, 3]);
+    assert_eq!(keon::from_str::<[i32; 3]>("(1, 2, 3)").unwrap(), [1, 2, 3]);
+
+    let e = err::<[i32; 3]>("[1, 2]");
+    assert_eq!(e.kind, ErrorKind::InvalidLength { expected: 3, found: 2 });
+    assert_eq!(e.to_string(), ":1:1 expected 3 elements, found 2");
+
+    let e = err::<Vec<[i32; 2]>>("[\n    [1, 2],\n    (3, 4, 5, 6),\n]");
+    assert_eq!(e.kind, ErrorKind::InvalidLength { expected: 2, found: 4 });
+    assert_eq!(e.to_string().split(' ').next().unwrap(), ":3:5");
+
+    let e = err::<(u8, u8)>("(1, 2, 3)");
+    assert_eq!(e.kind, ErrorKind::InvalidLength { expected: 2, found: 3 });
+
+    // Elements are still located by themselves.
+    assert_eq!(err::<[u8; 2]>("[1, -2]").to_string().split(' ').next().unwrap(), ":1:5");
+
+    // Capacity is checked by the visitor of `ArrayVec`, located at the start of the sequence.
+    assert!(keon::from_str::<ArrayVec<u8, 4>>("[1, 2, 3, 4]").is_ok());
+    let e = err::<Vec<ArrayVec<u8, 4>>>("[[], [1, 2, 3, 4, 5]]");
+    assert_eq!(e.to_string().split(' ').next().unwrap(), ":1:6");
+}
@@ -0,0 +1,104 @@
+mod util;
+
+use keon::{DeserializeConfig, SerializeConfig, SerializeConfigBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use util::{de_with, ser_with};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+enum Feature {
+    Audio,
+    Video,
+    Network,
+}
+
+const SUGAR: DeserializeConfig = {
+    let mut cfg = DeserializeConfig::strict();
+    cfg.set_sugar = true;
+    cfg
+};
+
+#[test]
+fn roundtrips() {
+    let features = BTreeMap::from_iter([(Feature::Audio, ()), (Feature::Network, ())]);
+    let minimal = SerializeConfigBuilder::from(SerializeConfig::minimal())
+        .set_sugar(true)
+        .build();
+
+    let s = ser_with(&features, minimal);
+    assert_eq!(s, "{Audio,Network}");
+    assert_eq!(de_with::<BTreeMap<Feature, ()>>(&s, SUGAR).unwrap(), features);
+
+    let s = ser_with(&features, SerializeConfig::builder().set_sugar(true).build());
+    assert_eq!(s, "{\n    Feature::Audio,\n    Feature::Network,\n}");
+    assert_eq!(de_with::<BTreeMap<Feature, ()>>(&s, SUGAR).unwrap(), features);
+
+    let names = BTreeMap::from_iter([("a".to_string(), ()), ("b".to_string(), ())]);
+    let s = ser_with(&names, minimal);
+    assert_eq!(s, r#"{"a","b"}"#);
+    assert_eq!(de_with::<BTreeMap<String, ()>>(&s, SUGAR).unwrap(), names);
+
+    // Non-unit values are untouched.
+    let s = ser_with(&BTreeMap::from_iter([(1, Some(())), (2, None)]), minimal);
+    assert_eq!(s, "{1=>?(),2=>?}");
+}
+
+#[test]
+fn backwards() {
+    assert_eq!(
+        de_with::<BTreeMap<String, ()>>("{ a, b, \"c\", d => () }", SUGAR).unwrap(),
+        BTreeMap::from_iter(["a", "b", "c", "d"].map(|k| (k.to_string(), ())))
+    );
+    assert_eq!(
+        de_with::<BTreeSet<Feature>>("{ Video, Feature::Audio, }", SUGAR).unwrap(),
+        BTreeSet::from_iter([Feature::Audio, Feature::Video])
+    );
+    assert_eq!(
+        de_with::<BTreeSet<String>>("{ a, `true, \"b\" }", SUGAR).unwrap(),
+        BTreeSet::from_iter(["a", "true", "b"].map(String::from))
+    );
+    assert_eq!(de_with::<Vec<u8>>("{ 1, 2, 3 }", SUGAR).unwrap(), vec![1, 2, 3]);
+    assert_eq!(de_with::<Vec<u8>>("{}", SUGAR).unwrap(), Vec::<u8>::new());
+
+    // Off by default.
+    assert!(keon::from_str::<BTreeMap<String, ()>>("{ a, b }").is_err());
+    assert!(keon::from_str::<BTreeSet<Feature>>("{ Audio }").is_err());
+}
+
+#[test]
+fn sets() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Build {
+        features: BTreeSet<Feature>,
+        #[serde(with = "keon::adapters::set")]
+        marked: BTreeSet<Feature>,
+        #[serde(with = "keon::adapters::set")]
+        names: HashSet<String>,
+    }
+
+    let build = Build {
+        features: BTreeSet::from_iter([Feature::Audio, Feature::Video]),
+        marked: BTreeSet::from_iter([Feature::Audio, Feature::Video]),
+        names: HashSet::from_iter(["a".to_string()]),
+    };
+
+    // Sequences to Serde, unless marked.
+    let minimal = SerializeConfigBuilder::from(SerializeConfig::minimal())
+        .set_sugar(true)
+        .build();
+    let s = ser_with(&build, minimal);
+    assert_eq!(s, r#"{features:[Audio,Video],marked:{Audio,Video},names:{"a"}}"#);
+    assert_eq!(de_with::<Build>(&s, SUGAR).unwrap(), build);
+
+    // Maps of unit values otherwise, read back from either.
+    let s = keon::to_string(&build).unwrap();
+    assert_eq!(
+        s,
+        r#"{features:[Audio,Video],marked:{Audio=>(),Video=>()},names:{"a"=>()}}"#
+    );
+    assert_eq!(keon::from_str::<Build>(&s).unwrap(), build);
+    assert_eq!(
+        keon::from_str::<Build>(r#"{ features: [Audio, Video], marked: [Video, Audio], names: ["a"] }"#).unwrap(),
+        build
+    );
+}
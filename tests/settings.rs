@@ -0,0 +1,155 @@
+use keon::{settings::Store, DeserializeConfig, ErrorKind, SerializeConfig};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Settings {
+    audio: Audio,
+    mode: Mode,
+    recent: Vec<String>,
+    servers: BTreeMap<String, Server>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Audio {
+    volume: u8,
+    device: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+enum Mode {
+    #[default]
+    Windowed,
+    Fullscreen {
+        monitor: u8,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Server {
+    port: u16,
+}
+
+#[test]
+fn store() {
+    let dir = std::env::temp_dir().join(format!("keon-settings-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("settings.keon");
+    let _ = fs::remove_file(&path);
+
+    // Not written until saved.
+    let mut store = Store::<Settings>::open(&path).unwrap();
+    assert_eq!(store.get(), &Settings::default());
+    assert!(store.is_dirty());
+    assert!(!path.exists());
+
+    store.set_at("audio.volume", &80).unwrap();
+    store.set_at("audio.device", &Some("speakers")).unwrap();
+    store.set_at("mode", &Mode::Fullscreen { monitor: 1 }).unwrap();
+    store.set_at("mode.monitor", &2).unwrap();
+    store
+        .update(|settings| {
+            settings.recent.push("a.keon".into());
+            settings.servers.insert("eu".into(), Server { port: 80 });
+        })
+        .unwrap();
+    store.set_at("recent[0]", "b.keon").unwrap();
+    store.set_at("servers[\"eu\"].port", &8080).unwrap();
+
+    assert_eq!(store.get_at::<u8>("audio.volume").unwrap(), 80);
+    assert_eq!(store.get_at::<Mode>("mode").unwrap(), Mode::Fullscreen { monitor: 2 });
+    assert_eq!(
+        store.get_at::<Server>("servers[\"eu\"]").unwrap(),
+        Server { port: 8080 }
+    );
+    assert_eq!(store.get().recent, ["b.keon"]);
+    assert_eq!(store.text(), keon::to_string_pretty(store.get()).unwrap());
+
+    // Rejected as `T`, or not there.
+    assert!(store.set_at("audio.volume", &1000).is_err());
+    assert!(store.set_at("audio.volume", "loud").is_err());
+    assert_eq!(store.get().audio.volume, 80);
+    let e = store.set_at("audio.balance", &0).unwrap_err();
+    assert_eq!(e.kind, ErrorKind::UnknownSetting("audio.balance".into()));
+    assert_eq!(e.to_string(), "no setting at `audio.balance`");
+
+    store.save().unwrap();
+    assert!(!store.is_dirty());
+    assert_eq!(fs::read_to_string(&path).unwrap(), store.text());
+    assert_eq!(
+        fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>(),
+        ["settings.keon"]
+    );
+
+    // Setting a value as it is is not a change.
+    store.set_at("audio.volume", &80).unwrap();
+    assert!(!store.is_dirty());
+
+    // Edited by hand, comments are read but not kept.
+    fs::write(&path, store.text().replace("volume: 80", "volume: 30, // quieter")).unwrap();
+    let mut reopened = Store::<Settings>::open(&path).unwrap();
+    assert!(!reopened.is_dirty());
+    assert_eq!(reopened.get().audio.volume, 30);
+    assert!(!reopened.text().contains("quieter"));
+
+    store.set_at("audio.volume", &50).unwrap();
+    store.reload().unwrap();
+    assert_eq!(store.get(), reopened.get());
+    assert!(!store.is_dirty());
+
+    reopened.replace(Settings::default()).unwrap();
+    reopened.save().unwrap();
+    assert_eq!(
+        keon::from_str::<Settings>(&fs::read_to_string(&path).unwrap()).unwrap(),
+        Settings::default()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn configs() {
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Window {
+        frame: Frame,
+        plugins: BTreeMap<String, ()>,
+    }
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Frame {
+        width: u32,
+    }
+
+    let dir = std::env::temp_dir().join(format!("keon-settings-configs-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("window.keon");
+    let cfg = SerializeConfig::builder().dotted_keys(true).set_sugar(true).build();
+    let mut de_cfg = DeserializeConfig::strict();
+    de_cfg.dotted_keys = true;
+    de_cfg.set_sugar = true;
+
+    let mut store = Store::<Window>::open_with(&path, &cfg, de_cfg).unwrap();
+    store
+        .update(|window| window.plugins.insert("clock".into(), ()))
+        .unwrap();
+    store.set_at("frame.width", &640).unwrap();
+    assert_eq!(
+        store.text(),
+        "(Window) {\n    frame.width: 640,\n    plugins: {\n        \"clock\",\n    },\n}"
+    );
+    assert_eq!(store.get_at::<u32>("frame.width").unwrap(), 640);
+    store.save().unwrap();
+    store.reload().unwrap();
+    assert_eq!(store.get().frame.width, 640);
+
+    // What the store writes must be read back.
+    let e = Store::<Window>::open_with(&path, &cfg, DeserializeConfig::strict()).unwrap_err();
+    assert_eq!(e.kind, ErrorKind::UnexpectedToken);
+    fs::remove_file(&path).unwrap();
+    assert!(Store::<Window>::open_with(&path, &cfg, DeserializeConfig::strict()).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,59 @@
+mod util;
+
+use keon::{DeserializeConfig, ErrorKind, Value};
+use util::de_with;
+
+const SINGLE_QUOTED: DeserializeConfig = {
+    let mut cfg = DeserializeConfig::strict();
+    cfg.single_quoted_strings = true;
+    cfg
+};
+
+#[test]
+fn single_quoted_strings() {
+    assert_eq!(de_with::<String>("'like this'", SINGLE_QUOTED).unwrap(), "like this");
+    assert_eq!(
+        de_with::<String>(r#"'say "hi"'"#, SINGLE_QUOTED).unwrap(),
+        r#"say "hi""#
+    );
+    assert_eq!(
+        de_with::<String>(r"'it\'s \u{1F600}\n'", SINGLE_QUOTED).unwrap(),
+        "it's \u{1F600}\n"
+    );
+    assert_eq!(de_with::<String>("''", SINGLE_QUOTED).unwrap(), "");
+    assert_eq!(
+        de_with::<Vec<String>>(r#"['a', 'bc', '"', "d"]"#, SINGLE_QUOTED).unwrap(),
+        ["a", "bc", "\"", "d"]
+    );
+
+    // Still chars if exactly one.
+    assert_eq!(de_with::<Value>("'a'", SINGLE_QUOTED).unwrap(), Value::Char('a'));
+    assert_eq!(de_with::<Value>(r"'\''", SINGLE_QUOTED).unwrap(), Value::Char('\''));
+    assert_eq!(
+        de_with::<Value>("'ab'", SINGLE_QUOTED).unwrap(),
+        Value::String("ab".into())
+    );
+    assert_eq!(de_with::<char>("'✱'", SINGLE_QUOTED).unwrap(), '✱');
+    assert!(de_with::<char>("'ab'", SINGLE_QUOTED).is_err());
+
+    assert_eq!(
+        de_with::<String>("'broken\n'", SINGLE_QUOTED).unwrap_err().kind,
+        ErrorKind::UnexpectedNewline
+    );
+    assert_eq!(
+        de_with::<String>("'unclosed", SINGLE_QUOTED).unwrap_err().kind,
+        ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn strict_by_default() {
+    assert_eq!(
+        keon::from_str::<String>("'like this'").unwrap_err().kind,
+        ErrorKind::InvalidCharacterTooMany
+    );
+    assert_eq!(
+        keon::from_str::<String>("''").unwrap_err().kind,
+        ErrorKind::InvalidCharacterTooLess
+    );
+}
@@ -0,0 +1,69 @@
+use keon::{Expand, SerializeConfig, SerializeConfigBuilder};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Backend {
+    Disk { path: Option<String>, quota: Option<u64> },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    proxy: Option<String>,
+    retries: Option<Option<u8>>,
+    backend: Backend,
+}
+
+fn roundtrip(value: &Config, cfg: SerializeConfigBuilder) -> String {
+    let s = keon::to_string_with(value, &cfg.skip_none_fields(true).build()).unwrap();
+    assert_eq!(&keon::from_str::<Config>(&s).unwrap(), value);
+    s
+}
+
+#[test]
+fn skip_none_fields() {
+    let config = Config {
+        name: "main".into(),
+        proxy: None,
+        retries: Some(None),
+        backend: Backend::Disk {
+            path: None,
+            quota: None,
+        },
+    };
+    assert_eq!(
+        roundtrip(&config, SerializeConfig::builder().expand(Expand::UntilDepth(0))),
+        "{name:\"main\",retries:??,backend:Disk{}}"
+    );
+    assert_eq!(
+        roundtrip(&config, SerializeConfig::builder()),
+        "(Config) {
+    name: \"main\",
+    retries: ? ?,
+    backend: Backend::Disk {},
+}"
+    );
+
+    let config = Config {
+        name: "main".into(),
+        proxy: Some("socks5://localhost".into()),
+        retries: None,
+        backend: Backend::Disk {
+            path: None,
+            quota: Some(1024),
+        },
+    };
+    assert_eq!(
+        roundtrip(&config, SerializeConfig::builder()),
+        "(Config) {
+    name: \"main\",
+    proxy: ? \"socks5://localhost\",
+    backend: Backend::Disk {
+        quota: ? 1024,
+    },
+}"
+    );
+
+    // Written as they were otherwise.
+    assert!(keon::to_string_pretty(&config).unwrap().contains("retries: ?,"));
+}
@@ -0,0 +1,113 @@
+use keon::{PathSegment::*, SerializeConfig, Serializer};
+use serde::Serialize;
+use std::{collections::BTreeMap, sync::Arc};
+
+#[derive(Serialize)]
+enum Item {
+    Sword { damage: u8 },
+    Potion(u8),
+}
+
+#[derive(Serialize)]
+struct Server {
+    tls: Tls,
+}
+
+#[derive(Serialize)]
+struct Tls {
+    port: u16,
+}
+
+#[derive(Serialize)]
+struct Save {
+    name: &'static str,
+    inventory: Vec<Item>,
+    stash: BTreeMap<&'static str, (u8, u8)>,
+    server: Server,
+    #[serde(with = "keon::adapters::shared")]
+    left: Arc<Vec<u8>>,
+    #[serde(with = "keon::adapters::shared")]
+    right: Arc<Vec<u8>>,
+}
+
+fn save() -> Save {
+    let shared = Arc::new(vec![1, 2]);
+    Save {
+        name: "Alex",
+        inventory: vec![Item::Potion(1), Item::Sword { damage: 3 }],
+        stash: BTreeMap::from([("b", (4, 5)), ("a", (6, 7))]),
+        server: Server { tls: Tls { port: 443 } },
+        left: Arc::clone(&shared),
+        right: shared,
+    }
+}
+
+/// The text of each value, and that the source map is the same however the output is written.
+fn spans(cfg: SerializeConfig) -> Vec<(keon::FieldPath, String)> {
+    let (s, source_map) = keon::to_string_with_source_map(&save(), &cfg).unwrap();
+    assert_eq!(s, keon::to_string_with(&save(), &cfg).unwrap());
+
+    let mut ser = Serializer::new(Vec::new(), cfg).with_source_map();
+    save().serialize(&mut ser).unwrap();
+    assert_eq!(ser.source_map(), source_map);
+
+    source_map
+        .into_iter()
+        .map(|(path, range)| (path, s[range].to_string()))
+        .collect()
+}
+
+#[test]
+fn source_map() {
+    let key = |k: &str| Key(format!("{:?}", k));
+    let minimal = spans(SerializeConfig::minimal());
+    assert_eq!(
+        minimal,
+        [
+            (vec![Field("name")], r#""Alex""#),
+            (vec![Field("inventory")], "[Potion%1,Sword{damage:3}]"),
+            (vec![Field("inventory"), Index(0)], "Potion%1"),
+            (vec![Field("inventory"), Index(1)], "Sword{damage:3}"),
+            (vec![Field("inventory"), Index(1), Field("damage")], "3"),
+            (vec![Field("stash")], r#"{"a"=>(6,7),"b"=>(4,5)}"#),
+            (vec![Field("stash"), key("a")], "(6,7)"),
+            (vec![Field("stash"), key("a"), Index(0)], "6"),
+            (vec![Field("stash"), key("a"), Index(1)], "7"),
+            (vec![Field("stash"), key("b")], "(4,5)"),
+            (vec![Field("stash"), key("b"), Index(0)], "4"),
+            (vec![Field("stash"), key("b"), Index(1)], "5"),
+            (vec![Field("server")], "{tls:{port:443}}"),
+            (vec![Field("server"), Field("tls")], "{port:443}"),
+            (vec![Field("server"), Field("tls"), Field("port")], "443"),
+            (vec![Field("left")], "[1,2]"),
+            (vec![Field("left"), Index(0)], "1"),
+            (vec![Field("left"), Index(1)], "2"),
+            (vec![Field("right")], "[1,2]"),
+            (vec![Field("right"), Index(0)], "1"),
+            (vec![Field("right"), Index(1)], "2"),
+        ]
+        .map(|(path, text)| (path, text.to_string()))
+    );
+
+    // Values written into buffers first are mapped where they end up.
+    let cfg = SerializeConfig::builder()
+        .dotted_keys(true)
+        .sort_keys(true)
+        .memoize_shared(true)
+        .section_dividers(Some(3))
+        .line_width(Some(20))
+        .build();
+    let pretty = spans(cfg);
+    assert_eq!(
+        pretty.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+        minimal.iter().map(|(path, _)| path).collect::<Vec<_>>()
+    );
+    let text = |path: &[keon::PathSegment]| &pretty.iter().find(|(p, _)| p == path).unwrap().1;
+    assert_eq!(text(&[Field("inventory"), Index(1), Field("damage")]), "3");
+    assert_eq!(
+        text(&[Field("stash"), key("b")]),
+        "(\n            4,\n            5,\n        )"
+    );
+    assert_eq!(text(&[Field("server")]), "443");
+    assert_eq!(text(&[Field("right"), Index(1)]), "2");
+}
@@ -0,0 +1,61 @@
+use keon::{SerializeConfig, Serializer};
+use serde::Serialize;
+use std::{cell::Cell, io::Write, rc::Rc};
+
+#[derive(Serialize)]
+struct Record {
+    id: u32,
+    name: String,
+}
+
+fn record(id: u32) -> Record {
+    Record {
+        id,
+        name: format!("record #{}", id),
+    }
+}
+
+#[test]
+fn same_as_collected() {
+    for cfg in [
+        SerializeConfig::minimal(),
+        SerializeConfig::comfort(),
+        SerializeConfig::builder().line_width(Some(40)).build(),
+    ] {
+        let mut buf = Vec::new();
+        let mut ser = Serializer::new(&mut buf, cfg);
+        ser.collect_seq_streaming((0..5).map(record)).unwrap();
+
+        let collected = keon::to_string_with(&(0..5).map(record).collect::<Vec<_>>(), &cfg).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), collected);
+    }
+}
+
+/// Counts bytes written without keeping them.
+struct Counter(Rc<Cell<usize>>);
+
+impl Write for Counter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.set(self.0.get() + buf.len());
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn written_while_produced() {
+    let written = Rc::new(Cell::new(0));
+    let mut ser = Serializer::new(Counter(written.clone()), SerializeConfig::comfort());
+
+    let mut last = 0;
+    ser.collect_seq_streaming((0..100_000).map(|id| {
+        // Everything before this record is out already.
+        assert!(id == 0 || written.get() > last);
+        last = written.get();
+        record(id)
+    }))
+    .unwrap();
+    assert!(written.get() > last);
+}
@@ -8,6 +8,7 @@ fn roundtrips() {
     util::rt_min( &String::from("我测")     , r#""我测""#     ).unwrap();
     util::rt_min( &String::from("\n\t\r\0") , r#""\n\t\r\0""# ).unwrap();
     util::rt_min( &String::from("\x11\x23") , "\"\\x11#\""  ).unwrap();
+    util::rt_min( &String::from(r"C:\")    , r#""C:\\""#     ).unwrap();
 }
 
 #[test]
@@ -22,6 +23,161 @@ fn backwards() {
         r#""\x65\x78\x70\x61\x6E\x64\x20\x33\x32\x2D\x62\x79\x74\x65\x20\x6B""#,
     )
     .unwrap();
+    #[cfg(feature = "syntax-raw-strings")]
     util::backward(&String::from(r#"\1\2\3\x``"#), r#"`"\1\2\3\x``"`"#).unwrap();
+    #[cfg(feature = "syntax-raw-strings")]
     util::backward(&String::from(r#"\1\2\3``"`"#), r#"``"\1\2\3``"`"``"#).unwrap();
 }
+
+#[test]
+#[cfg(all(feature = "syntax-paragraph", feature = "syntax-raw-strings"))]
+fn max_string_len() {
+    use keon::{DeserializeConfig, Deserializer, ErrorKind};
+
+    let limited = |s: &str, limit| {
+        let mut cfg = DeserializeConfig::strict();
+        cfg.max_string_len = limit;
+        cfg.single_quoted_strings = true;
+        let mut der = Deserializer::with_config(s, cfg);
+        <String as serde::Deserialize>::deserialize(&mut der)
+    };
+    let at = |s: &str, limit| {
+        let e = limited(s, limit).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::ExceededStringLimit(limit), "{}", s);
+        e.to_string().split(' ').next().unwrap().to_string()
+    };
+
+    assert_eq!(limited(r#""abcd""#, 4).unwrap(), "abcd");
+    assert_eq!(limited(r#""\u{4E2D}\n""#, 4).unwrap(), "中\n");
+    assert_eq!(limited("`\"abcd\"`", 4).unwrap(), "abcd");
+    assert_eq!(limited("'abcd'", 4).unwrap(), "abcd");
+    assert_eq!(limited("| ab\n| c", 4).unwrap(), "ab c");
+
+    assert_eq!(at(r#""abcde""#, 4), ":1:1");
+    assert_eq!(at(r#""\u{4E2D}\u{4E2D}""#, 4), ":1:1");
+    assert_eq!(at("`\"abcde\"`", 4), ":1:1");
+    assert_eq!(at("'abcde'", 4), ":1:1");
+    assert_eq!(at("| abcde", 4), ":1:1");
+    assert_eq!(at("[\n    | ab\n    | cd\n]", 4), ":2:5");
+
+    // Multi-megabyte literals are rejected early, whichever the form.
+    let mb = "x".repeat(4 << 20);
+    assert_eq!(at(&format!("\"{}\"", mb), 1 << 20), ":1:1");
+    assert_eq!(at(&format!("\"{}\"", "\\n".repeat(4 << 20)), 1 << 20), ":1:1");
+    assert_eq!(at(&format!("| {}", mb), 1 << 20), ":1:1");
+    assert_eq!(at(&format!("`\"{}\"`", mb), 1 << 20), ":1:1");
+    assert_eq!(at(&"| x\n".repeat(4 << 20), 1 << 20), ":1:1");
+
+    // Unlimited by default.
+    assert_eq!(keon::from_str::<String>(&format!("\"{}\"", mb)).unwrap().len(), 4 << 20);
+    assert_eq!(
+        keon::from_str::<String>(&format!("`\"{}\"`", mb)).unwrap().len(),
+        4 << 20
+    );
+}
+
+#[test]
+#[cfg(feature = "syntax-raw-strings")]
+fn raw_output() {
+    use keon::{SerializeConfig, Serializer};
+    use serde::Serialize;
+
+    let raw = |s: &str| {
+        let cfg = SerializeConfig::builder().raw_strings(true).build();
+        let mut buf = Vec::new();
+        s.serialize(&mut Serializer::new(&mut buf, cfg)).unwrap();
+        let out = String::from_utf8(buf).unwrap();
+        assert_eq!(keon::from_str::<String>(&out).unwrap(), s, "{}", out);
+        out
+    };
+
+    assert_eq!(raw(r"C:\Users\Alex"), r#"`"C:\Users\Alex"`"#);
+    assert_eq!(raw(r#"^"(\d+)"$"#), r#"`"^"(\d+)"$"`"#);
+    assert_eq!(raw(r#"say "hi""#), r#"`"say "hi""`"#);
+
+    // More backticks than any quote inside is followed by.
+    assert_eq!(raw(r#""`\d`" and "``\w``""#), r#"```""`\d`" and "``\w``""```"#);
+
+    // Unless it would be longer than escaped.
+    assert_eq!(raw(r"\d+"), r#""\\d+""#);
+    assert_eq!(raw(r#"a "`b`" c"#), r#""a \"`b`\" c""#);
+    assert_eq!(raw("plain"), r#""plain""#);
+    assert_eq!(raw("\\\\\ttab"), r#""\\\\\ttab""#);
+}
+
+#[test]
+fn ascii_only() {
+    use keon::{SerializeConfig, Serializer};
+    use serde::Serialize;
+
+    fn ascii<T: Serialize>(value: &T) -> String {
+        let builder = SerializeConfig::builder().ascii_only(true);
+        #[cfg(feature = "syntax-paragraph")]
+        let builder = builder.wrap_width(Some(16));
+        #[cfg(feature = "syntax-raw-strings")]
+        let builder = builder.raw_strings(true);
+        let mut buf = Vec::new();
+        value
+            .serialize(&mut Serializer::new(&mut buf, builder.build()))
+            .unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    assert_eq!(ascii(&"café ✱ 😀"), r#""caf\u{e9} \u{2731} \u{1f600}""#);
+    assert_eq!(ascii(&'é'), r"'\u{e9}'");
+    assert_eq!(ascii(&"ascii\n\"é\"\\"), r#""ascii\n\"\u{e9}\"\\""#);
+    assert_eq!(
+        keon::from_str::<String>(&ascii(&"ascii\n\"é\"\\")).unwrap(),
+        "ascii\n\"é\"\\"
+    );
+
+    // Paragraphs and raw strings only for ASCII strings.
+    #[cfg(all(feature = "syntax-paragraph", feature = "syntax-raw-strings"))]
+    {
+        assert_eq!(ascii(&"line\nbreak"), "| line\n` break");
+        assert_eq!(ascii(&r"C:\Users\x"), r#"`"C:\Users\x"`"#);
+        assert_eq!(ascii(&r"C:\Users\é"), r#""C:\\Users\\\u{e9}""#);
+    }
+}
+
+#[test]
+fn escape_invisible() {
+    use keon::{SerializeConfig, Serializer};
+    use serde::Serialize;
+
+    fn escaped<T: Serialize>(value: &T) -> String {
+        let builder = SerializeConfig::builder().escape_invisible(true);
+        #[cfg(feature = "syntax-paragraph")]
+        let builder = builder.wrap_width(Some(16));
+        #[cfg(feature = "syntax-raw-strings")]
+        let builder = builder.raw_strings(true);
+        let mut buf = Vec::new();
+        value
+            .serialize(&mut Serializer::new(&mut buf, builder.build()))
+            .unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    // A bidi override hiding the rest of the line, a zero-width space and a no-break space.
+    let spoof = "access: \u{202e}user\u{2066}, admin\u{200b}\u{a0}";
+    assert_eq!(
+        escaped(&spoof),
+        r#""access: \u{202e}user\u{2066}, admin\u{200b}\u{a0}""#
+    );
+    assert_eq!(keon::from_str::<String>(&escaped(&spoof)).unwrap(), spoof);
+    assert_eq!(escaped(&'\u{feff}'), r"'\u{feff}'");
+    assert_eq!(escaped(&"\u{2028}"), r#""\u{2028}""#);
+
+    // Visible characters stay as they are.
+    assert_eq!(escaped(&"café ❤️ 日本"), "\"café ❤️ 日本\"");
+    assert_eq!(escaped(&"tab\tand space"), r#""tab\tand space""#);
+
+    // Paragraphs and raw strings only without them.
+    #[cfg(all(feature = "syntax-paragraph", feature = "syntax-raw-strings"))]
+    {
+        assert_eq!(escaped(&"line\nbreak"), "| line\n` break");
+        assert_eq!(escaped(&"line\u{200d}\nbreak"), r#""line\u{200d}\nbreak""#);
+        assert_eq!(escaped(&r"C:\Users\é"), r#"`"C:\Users\é"`"#);
+        assert_eq!(escaped(&"C:\\Users\\\u{200f}"), r#""C:\\Users\\\u{200f}""#);
+    }
+}
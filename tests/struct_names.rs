@@ -0,0 +1,99 @@
+use keon::{DeserializeConfig, Deserializer, ErrorKind};
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Save {
+    level: u32,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Point(i32, i32);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Marker;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Meters(f64);
+
+fn checked() -> DeserializeConfig {
+    let mut cfg = DeserializeConfig::strict();
+    cfg.check_struct_names = true;
+    cfg
+}
+
+fn parse<'de, T: Deserialize<'de>>(s: &'de str, aliases: &[(&str, &str)]) -> keon::Result<T> {
+    let mut der = Deserializer::with_config(s, checked());
+    for (old, name) in aliases {
+        der.alias(old, name);
+    }
+    let val = T::deserialize(&mut der)?;
+    der.finish()?;
+    Ok(val)
+}
+
+#[test]
+fn unchecked_by_default() {
+    assert_eq!(
+        keon::from_str::<Save>("(Whatever) { level: 1 }").unwrap(),
+        Save { level: 1 }
+    );
+    assert_eq!(keon::from_str::<Point>("(Whatever)(1, 2)").unwrap(), Point(1, 2));
+}
+
+#[test]
+fn mismatches() {
+    assert_eq!(parse::<Save>("(Save) { level: 1 }", &[]).unwrap(), Save { level: 1 });
+    assert_eq!(parse::<Save>("{ level: 1 }", &[]).unwrap(), Save { level: 1 });
+    assert_eq!(parse::<Point>("(Point)(1, 2)", &[]).unwrap(), Point(1, 2));
+    assert_eq!(parse::<Marker>("(Marker)", &[]).unwrap(), Marker);
+    assert_eq!(parse::<Meters>("(Meters)% 1.5", &[]).unwrap(), Meters(1.5));
+
+    let err = parse::<Save>("(SaveV1) { level: 1 }", &[]).unwrap_err();
+    assert_eq!(
+        err.kind,
+        ErrorKind::UnexpectedStructName {
+            expected: "Save".into(),
+            found: "SaveV1".into(),
+        }
+    );
+    assert_eq!(err.to_string(), ":1:2 expected struct name `Save`, found `SaveV1`");
+
+    assert!(parse::<Point>("(Pair)(1, 2)", &[]).is_err());
+    assert!(parse::<Marker>("(Mark)", &[]).is_err());
+    assert!(parse::<Meters>("(Feet)% 1.5", &[]).is_err());
+}
+
+#[test]
+fn aliases() {
+    let aliases = [("SaveV1", "Save"), ("SaveV2", "Save"), ("Pair", "Point")];
+    assert_eq!(
+        parse::<Save>("(SaveV1) { level: 1 }", &aliases).unwrap(),
+        Save { level: 1 }
+    );
+    assert_eq!(
+        parse::<Save>("(SaveV2) { level: 2 }", &aliases).unwrap(),
+        Save { level: 2 }
+    );
+    assert_eq!(parse::<Point>("(Pair)(1, 2)", &aliases).unwrap(), Point(1, 2));
+
+    // Aliases only stand for the struct they were registered for.
+    assert!(parse::<Point>("(SaveV1)(1, 2)", &aliases).is_err());
+
+    // Nested structs are checked as well.
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Slot {
+        save: Save,
+    }
+    assert_eq!(
+        parse::<Slot>("(Slot) { save: (SaveV1) { level: 3 } }", &aliases).unwrap(),
+        Slot {
+            save: Save { level: 3 }
+        }
+    );
+    assert_eq!(
+        parse::<Slot>("{\n    save: (Old) { level: 3 },\n}", &aliases)
+            .unwrap_err()
+            .to_string(),
+        ":2:12 expected struct name `Save`, found `Old`"
+    );
+}
@@ -0,0 +1,25 @@
+#[test]
+#[cfg(not(feature = "syntax-paragraph"))]
+fn without_paragraphs() {
+    let e = keon::from_str::<keon::Value>("| To be,\n| or not to be.").unwrap_err();
+    assert_eq!(e.kind, keon::ErrorKind::UnexpectedToken);
+}
+
+#[test]
+#[cfg(not(feature = "syntax-raw-strings"))]
+fn without_raw_strings() {
+    for s in [r#"`"raw"`"#, r#"b`"raw"`"#] {
+        let e = keon::from_str::<keon::Value>(s).unwrap_err();
+        assert_eq!(e.kind, keon::ErrorKind::UnexpectedToken, "{}", s);
+    }
+}
+
+#[test]
+#[cfg(not(feature = "syntax-basexx"))]
+fn without_basexx() {
+    for s in [r#"b16"48""#, r#"b32"JA""#, r#"b64"SA""#] {
+        assert!(keon::from_str::<serde_bytes::ByteBuf>(s).is_err(), "{}", s);
+    }
+    // Written as escaped bytes instead.
+    assert_eq!(keon::to_string(&serde_bytes::Bytes::new(b"H")).unwrap(), r#"b"H""#);
+}
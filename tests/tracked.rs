@@ -0,0 +1,104 @@
+use keon::{PathSegment, SerializeConfig, Value};
+
+fn settings() -> Value {
+    keon::from_str(
+        r#"{
+            "audio" => { "volume" => 80, "muted" => false },
+            "video" => { "resolution" => (1920, 1080), "vsync" => ? true },
+            "recent" => ["a.keon", "b.keon"],
+        }"#,
+    )
+    .unwrap()
+}
+
+fn key(s: &str) -> PathSegment {
+    PathSegment::Key(format!("{:?}", s))
+}
+
+#[test]
+fn tracked() {
+    let mut value = settings();
+    let mut tracked = value.tracked();
+
+    let mut audio = tracked.field("audio").unwrap();
+    assert_eq!(audio.field("volume").unwrap().set(50), Value::from(80u64));
+    // Not a change.
+    audio.field("muted").unwrap().set(false);
+    assert!(audio.is_changed());
+    assert!(!tracked.field("video").unwrap().is_changed());
+
+    tracked.field("recent").unwrap().push("c.keon");
+    tracked
+        .field("video")
+        .unwrap()
+        .insert("vsync", Value::Opt(Some(Box::new(true.into()))));
+    tracked.field("video").unwrap().insert("hdr", true);
+    assert!(tracked.field("missing").is_none());
+    assert!(tracked.index(0).is_none());
+
+    assert_eq!(
+        tracked.into_changes(),
+        [
+            vec![key("audio"), key("volume")],
+            vec![key("recent"), PathSegment::Index(2)],
+            vec![key("video"), key("hdr")],
+        ]
+    );
+    assert_eq!(
+        value.to_string().unwrap(),
+        concat!(
+            r#"{"audio"=>{"muted"=>false,"volume"=>50},"recent"=>["a.keon","b.keon","c.keon"],"#,
+            r#""video"=>{"hdr"=>true,"resolution"=>[1920,1080],"vsync"=>?true}}"#,
+        )
+    );
+}
+
+#[test]
+fn minimal() {
+    let mut value = settings();
+    let mut tracked = value.tracked();
+
+    tracked.field("audio").unwrap().field("volume").unwrap().set(50);
+    tracked.field("audio").unwrap().remove("muted");
+    // Covers both changes above.
+    tracked.field("audio").unwrap().get_mut();
+    // Covered by the change above.
+    tracked.field("audio").unwrap().insert("muted", true);
+    assert_eq!(tracked.changes(), [vec![key("audio")]]);
+
+    tracked.set(Value::Unit);
+    assert_eq!(tracked.into_changes(), [Vec::<PathSegment>::new()]);
+}
+
+#[test]
+fn source_map_paths() {
+    let mut value = settings();
+    let mut tracked = value.tracked();
+
+    // Through options.
+    tracked
+        .field("video")
+        .unwrap()
+        .field("vsync")
+        .unwrap()
+        .set(Value::Opt(None));
+    tracked
+        .field("video")
+        .unwrap()
+        .field("resolution")
+        .unwrap()
+        .index(1)
+        .unwrap()
+        .set(1200);
+    let changes = tracked.into_changes();
+
+    let (output, source_map) = keon::to_string_with_source_map(&value, &SerializeConfig::comfort()).unwrap();
+    let written = changes
+        .iter()
+        .map(|path| {
+            let (_, range) = source_map.iter().find(|(written, _)| written == path).unwrap();
+            &output[range.clone()]
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(written, ["?", "1200"]);
+}
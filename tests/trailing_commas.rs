@@ -0,0 +1,111 @@
+mod util;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct Unit;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Newtype(i32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Pair(i32, i32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Dot,
+    Wrap(i32),
+    Pair(i32, i32),
+    Single((i32,)),
+    Point { x: i32, y: i32 },
+}
+
+/// Each case is accepted both without and with a trailing comma.
+#[test]
+fn accepted() {
+    macro_rules! grid {
+        ($($ty:ty: $obj:expr => [$($s:literal),+ $(,)?];)+) => {$($(
+            if let Err(e) = util::backward::<$ty>(&$obj, $s) {
+                panic!("`{}`: {:?}", $s, e);
+            }
+        )+)+};
+    }
+
+    grid! {
+        Vec<i32>: vec![1] => ["[1]", "[1,]", "[ 1 , ]"];
+        Vec<i32>: vec![1, 2] => ["[1, 2]", "[1, 2,]"];
+
+        (i32,): (1,) => ["(1,)"];
+        (i32, i32): (1, 2) => ["(1, 2)", "(1, 2,)"];
+        [i32; 3]: [1, 2, 3] => ["(1, 2, 3)", "(1, 2, 3,)", "[1, 2, 3,]"];
+
+        BTreeMap<i32, i32>: BTreeMap::from([(1, 2)]) => ["{1 => 2}", "{1 => 2,}"];
+        BTreeMap<String, i32>: BTreeMap::from([("k".into(), 2)]) => ["{k: 2}", "{k: 2,}", "{\"k\" => 2,}"];
+        Point: Point { x: 1, y: 2 } => ["{x: 1, y: 2}", "{x: 1, y: 2,}", "(Point){x: 1, y: 2,}"];
+
+        Newtype: Newtype(1) => ["%1", "(Newtype)(1)", "(Newtype)(1,)", "()(1,)"];
+        Pair: Pair(1, 2) => ["(1, 2,)", "(Pair)(1, 2,)", "()(1, 2,)"];
+
+        Shape: Shape::Wrap(1) => ["Wrap(1)", "Wrap(1,)", "Shape::Wrap(1,)"];
+        Shape: Shape::Pair(1, 2) => ["Pair(1, 2)", "Pair(1, 2,)"];
+        (Shape, i32): (Shape::Dot, 1) => ["(Dot, 1)", "(Dot, 1,)", "(Shape::Dot, 1,)"];
+        Shape: Shape::Single((1,)) => ["Single((1,))", "Single((1,),)"];
+        Shape: Shape::Point { x: 1, y: 2 } => ["Point{x: 1, y: 2,}", "Shape::Point{x: 1, y: 2,}"];
+        Vec<Option<i32>>: vec![None, Some(1)] => ["[?, ?1]", "[?, ?1,]", "[? , ? 1 ,]"];
+        (Option<i32>,): (None,) => ["(?,)"];
+        Vec<()>: vec![(), ()] => ["[(), ()]", "[(), (),]", "[(Unit), ()]"];
+        BTreeMap<Unit, Unit>: BTreeMap::from([(Unit, Unit)]) => ["{() => ()}", "{() => (),}", "{(Unit) => (Unit),}"];
+        Vec<Shape>: vec![Shape::Dot, Shape::Wrap(1)] => ["[Dot, Wrap(1)]", "[Dot, Wrap(1,),]", "[Dot,Wrap%1,]"];
+    }
+}
+
+/// A lone or doubled comma is never accepted.
+#[test]
+fn rejected() {
+    macro_rules! grid {
+        ($($ty:ty: [$($s:literal),+ $(,)?];)+) => {$($(
+            assert!(keon::from_str::<$ty>($s).is_err(), "{} accepted `{}`", stringify!($ty), $s);
+        )+)+};
+    }
+
+    grid! {
+        Vec<i32>: ["[,]", "[1,,]", "[1 2]"];
+        (i32,): ["(1)", "(,)", "(1,,)"];
+        (i32, i32): ["(1, 2,,)", "(1 2)"];
+        BTreeMap<i32, i32>: ["{,}", "{1 => 2,,}"];
+        Point: ["{x: 1,, y: 2}", "{x: 1, y: 2,,}"];
+        Pair: ["(Pair)(1, 2,,)", "()(,)"];
+        Vec<Option<i32>>: ["[?,,]", "[??]"];
+        Shape: ["Wrap(,)", "Wrap(1,,)", "Pair(1, 2,,)", "(Pair,,)", "Point{,}"];
+    }
+}
+
+/// Pretty outputs without trailing commas are read back the same, except unary tuples keep theirs.
+#[test]
+fn written_without() {
+    fn check<T: core::fmt::Debug + PartialEq + Serialize + serde::de::DeserializeOwned>(obj: &T, expected: &str) {
+        let cfg = keon::SerializeConfig::builder().trailing_commas(false).build();
+        let mut buf = Vec::new();
+        obj.serialize(&mut keon::Serializer::new(&mut buf, cfg)).unwrap();
+        let s = String::from_utf8(buf).unwrap();
+        assert_eq!(s, expected);
+        assert_eq!(keon::from_str::<T>(&s).unwrap(), *obj);
+    }
+
+    check(&vec![1, 2], "[\n    1,\n    2\n]");
+    check(&(1,), "(\n    1,\n)");
+    check(&BTreeMap::from([(1, 2)]), "{\n    1 => 2\n}");
+    check(&Point { x: 1, y: 2 }, "(Point) {\n    x: 1,\n    y: 2\n}");
+    check(&Pair(1, 2), "(Pair)(\n    1,\n    2\n)");
+    check(
+        &vec![Shape::Dot, Shape::Pair(1, 2)],
+        "[\n    Shape::Dot,\n    Shape::Pair(\n        1,\n        2\n    )\n]",
+    );
+}
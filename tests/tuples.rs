@@ -12,3 +12,45 @@ fn roundtrips() {
     util::rt_pre(&(0, 1, 2), "(\n    0,\n    1,\n    2,\n)").unwrap();
     util::rt_pre(&(0, 1, 2, 3), "(\n    0,\n    1,\n    2,\n    3,\n)").unwrap();
 }
+
+#[test]
+fn struct_name_continuations() {
+    use keon::{ErrorKind, Value};
+
+    let ok = |s| keon::from_str::<Value>(s).unwrap();
+    let found = |s| match keon::from_str::<Value>(s).unwrap_err().kind {
+        ErrorKind::ExpectedNonUnitStruct(found) => found,
+        kind => panic!("unexpected {:?}", kind),
+    };
+
+    // Accepted: tuple, map, newtype, nullary, and unit before a delimiter or EOF.
+    ok("(Name)(1, 2)");
+    ok("(Name){ a: 1 }");
+    ok("(Name)% 1");
+    ok("(Name)%");
+    ok("(Name)");
+    ok("[(Name), (Name)]");
+    ok("{ (Name) => (Name) }");
+
+    #[rustfmt::skip]
+    let rejected = [
+        ("(Name) 5",           "literal `5`"),
+        ("(Name) \"str\"",     "literal `\"str\"`"),
+        ("(Name) X",           "identifier `X`"),
+        ("(Name) [1]",         "`[`"),
+        ("(Name) :",           "`:`"),
+        ("(Name) ?",           "`?`"),
+        ("(Name) ::X",         "`::`"),
+        ("(Name) \"abcdefghijklmnopqrstuvwxyz\"", "literal `\"abcdefghijklmnopqrstuvw...`"),
+    ];
+    for (s, expected) in rejected {
+        assert_eq!(found(s), expected, "{}", s);
+    }
+
+    let err = keon::from_str::<Value>("(Name) 5").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        ":1:8 expected `(`, `{` or `%` to continue the struct name, \
+         or a delimiter to end it as a unit struct, found literal `5`"
+    );
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "unit-suffixes")]
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Limits {
+    #[serde(with = "keon::adapters::si")]
+    requests: u32,
+    #[serde(with = "keon::adapters::si")]
+    ratio: f64,
+    #[serde(with = "keon::adapters::byte_size")]
+    memory: u64,
+    #[serde(with = "keon::adapters::byte_size")]
+    upload: u64,
+    #[serde(with = "keon::adapters::duration")]
+    timeout: Duration,
+}
+
+#[test]
+fn unit_suffixes() {
+    let limits =
+        keon::from_str::<Limits>("{ requests: 10k, ratio: 2.5m, memory: 2GiB, upload: 1.5kB, timeout: 250ms }")
+            .unwrap();
+    assert_eq!(
+        limits,
+        Limits {
+            requests: 10_000,
+            ratio: 0.0025,
+            memory: 2 << 30,
+            upload: 1500,
+            timeout: Duration::from_millis(250),
+        }
+    );
+    assert_eq!(
+        keon::to_string(&limits).unwrap(),
+        r#"{requests:10k,ratio:0.0025,memory:2GiB,upload:1500B,timeout:"0.25s"}"#
+    );
+
+    let plain =
+        keon::from_str::<Limits>(r#"{ requests: 1500, ratio: 1, memory: 0, upload: 1_000, timeout: "1s" }"#).unwrap();
+    assert_eq!(
+        keon::to_string(&plain).unwrap(),
+        r#"{requests:1500,ratio:1,memory:0B,upload:1kB,timeout:"1s"}"#
+    );
+
+    // Units are read as strings, wherever a string is accepted.
+    assert_eq!(keon::from_str::<String>("-3.5ms").unwrap(), "-3.5ms");
+    assert_eq!(
+        keon::from_str::<keon::Value>("[1k, 2x]").unwrap_err().to_string(),
+        ":1:6 number `2x` has a suffix, but no number parser claimed it"
+    );
+}
+
+#[test]
+fn unit_errors() {
+    fn err(s: &str) -> String {
+        keon::from_str::<Limits>(s).unwrap_err().to_string()
+    }
+
+    assert_eq!(
+        err("{ requests: 5M, ratio: 1, memory: 1.0001kB, upload: 0, timeout: 1s }"),
+        ":1:35 (deserialize) invalid value: string \"1.0001kB\", expected a size in bytes like `2GiB`"
+    );
+    assert_eq!(
+        err("{ requests: 1.5k, ratio: 1u, memory: 0, upload: -1B, timeout: 1s }"),
+        ":1:49 (deserialize) invalid value: string \"-1B\", expected a size in bytes like `2GiB`"
+    );
+    assert_eq!(
+        err("{ requests: 1.5, ratio: 1, memory: 0, upload: 0, timeout: 1s }"),
+        ":1:13 (deserialize) invalid type: floating point `1.5`, expected u32"
+    );
+    assert_eq!(
+        err("{ requests: 5G, ratio: 1, memory: 0, upload: 0, timeout: 1s }"),
+        ":1:13 (deserialize) invalid value: integer `5000000000`, expected u32"
+    );
+
+    // More fractional digits than powers of ten fit in the integers the size is computed with.
+    let tiny = format!("0.{}1kB", "0".repeat(42));
+    assert_eq!(
+        err(&format!(
+            "{{ requests: 0, ratio: 0, memory: {}, upload: 0, timeout: 1s }}",
+            tiny
+        )),
+        format!(
+            ":1:34 (deserialize) invalid value: string \"{}\", expected a size in bytes like `2GiB`",
+            tiny
+        )
+    );
+}
+
+#[test]
+fn milli_and_minutes() {
+    // `m` is milli for numbers, but minutes for durations.
+    let limits =
+        keon::from_str::<Limits>(r#"{ requests: 0, ratio: 3m, memory: 0, upload: 0, timeout: "3m" }"#).unwrap();
+    assert_eq!(limits.ratio, 0.003);
+    assert_eq!(limits.timeout, Duration::from_secs(180));
+}
@@ -0,0 +1,119 @@
+mod util;
+use keon::Value;
+use serde::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Fill {
+    None,
+    Solid(u32),
+    Gradient(u32, u32),
+    Pattern { name: String, side: Side },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Circle {
+    r: f64,
+    fill: Fill,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Rect {
+    w: f64,
+    h: f64,
+    fill: Fill,
+    side: Option<Side>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Shape {
+    Circle(Circle),
+    Rect(Rect),
+    Fill(Fill),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum Tagged {
+    Circle(Circle),
+    Rect(Rect),
+}
+
+#[test]
+fn untagged_named_structs() {
+    let shapes = vec![
+        Shape::Circle(Circle {
+            r: 1.0,
+            fill: Fill::Solid(0xFF0000),
+        }),
+        Shape::Rect(Rect {
+            w: 2.0,
+            h: 3.0,
+            fill: Fill::Pattern {
+                name: "dots".into(),
+                side: Side::Left,
+            },
+            side: Some(Side::Right),
+        }),
+        Shape::Rect(Rect {
+            w: 4.0,
+            h: 5.0,
+            fill: Fill::Gradient(1, 2),
+            side: None,
+        }),
+        Shape::Fill(Fill::None),
+        Shape::Fill(Fill::Solid(7)),
+    ];
+
+    for s in [
+        keon::to_string(&shapes).unwrap(),
+        keon::to_string_pretty(&shapes).unwrap(),
+    ] {
+        assert_eq!(keon::from_str::<Vec<Shape>>(&s).unwrap(), shapes, "{}", s);
+    }
+
+    util::backward(
+        &shapes[..2].to_vec(),
+        "[
+            (Circle) { r: 1.0, fill: Fill::Solid(0xFF0000) },
+            (Rect) { w: 2.0, h: 3.0, fill: Pattern { name: \"dots\", side: Left }, side: ? Side::Right },
+        ]",
+    )
+    .unwrap();
+}
+
+#[test]
+fn internally_tagged() {
+    let tagged = Tagged::Rect(Rect {
+        w: 1.0,
+        h: 2.0,
+        fill: Fill::Gradient(3, 4),
+        side: Some(Side::Left),
+    });
+    assert_eq!(
+        keon::from_str::<Tagged>("{ kind: \"Rect\", w: 1.0, h: 2.0, fill: Gradient(3, 4), side: ? Left }").unwrap(),
+        tagged
+    );
+}
+
+#[test]
+fn values() {
+    assert_eq!(keon::from_str::<Value>("Side::Left").unwrap(), Value::from("Left"));
+    assert_eq!(
+        keon::to_string(
+            &keon::from_str::<Value>("[Solid%1, Gradient(1, 2), Pattern { side: Left }, Gradient%]").unwrap()
+        )
+        .unwrap(),
+        r#"[{"Solid"=>1},{"Gradient"=>[1,2]},{"Pattern"=>{"side"=>"Left"}},{"Gradient"=>[]}]"#
+    );
+
+    // Still not strings where the type is known.
+    assert!(keon::from_str::<String>("Left").is_err());
+    assert!(keon::from_str::<Option<String>>("? Left").is_err());
+}
@@ -1,7 +1,8 @@
 #![allow(unused)]
 
 use core::fmt::Debug;
-use serde::{de::DeserializeOwned, Serialize};
+use keon::{DeserializeConfig, Deserializer, SerializeConfig};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 type AssertResult<T> = Result<T, Msg>;
 
@@ -39,6 +40,19 @@ pub fn backward<T: Debug + PartialEq + Serialize + DeserializeOwned>(obj: &T, s:
         .ok_or(format!("backward:\nLeft: {:?}\nRight: {:?}", obj, obj_back).into())
 }
 
+/// Serializes `obj` with `cfg`, for the tests of an option.
+pub fn ser_with<T: ?Sized + Serialize>(obj: &T, cfg: SerializeConfig) -> String {
+    keon::to_string_with(obj, &cfg).unwrap()
+}
+
+/// Deserializes the whole of `s` with `cfg`, for the tests of an option.
+pub fn de_with<'de, T: Deserialize<'de>>(s: &'de str, cfg: DeserializeConfig) -> keon::Result<T> {
+    let mut der = Deserializer::with_config(s, cfg);
+    let val = T::deserialize(&mut der)?;
+    der.finish()?;
+    Ok(val)
+}
+
 //------------------------------------------------------------------------------
 
 fn serialize_min<T: Debug + PartialEq + Serialize + DeserializeOwned>(obj: &T) -> AssertResult<String> {
@@ -68,3 +82,93 @@ fn roundtrip<T: Debug + PartialEq + Serialize + DeserializeOwned>(
 
     Ok(())
 }
+
+/// Documents of the grammar, shared by the tests of the parsers.
+///
+/// Together they take every choice of the syntax rules, see `coverage` in `tests/grammar.rs`.
+pub const ACCEPTED: &[&str] = &[
+    "true",
+    "-0x_1F",
+    "1.5e3",
+    "'c'",
+    "\"str\"",
+    "b\"\\xFF\"",
+    "// comment\n/* nested /* block */ */ 1",
+    "?",
+    "? 1",
+    "? ?",
+    "%",
+    "% 1",
+    "% % %",
+    "()",
+    "(Name)",
+    "()(1)",
+    "(Name)()",
+    "(Name)(1, 2,)",
+    "(Name) { a: 1 }",
+    "(Name) % 1",
+    "(Name) %",
+    "(1,)",
+    "(1, 2)",
+    "(1, 2,)",
+    "(Variant,)",
+    "(Variant(1), 2)",
+    "(Enum::Variant, 2)",
+    "[]",
+    "[1]",
+    "[1, 2,]",
+    "[?, %, ()]",
+    "[(), (Unit), Variant]",
+    "{}",
+    "{ a: 1 }",
+    "{ a: 1, }",
+    "{ 1 => 2, \"k\" => [3] }",
+    "{ Variant => 1 }",
+    "{ Enum::Variant { a: 1 } => ? }",
+    "{ a: ?, b: % }",
+    "Variant",
+    "`true",
+    "Enum::Variant",
+    "Variant %",
+    "Variant % 1",
+    "Variant()",
+    "Variant(1)",
+    "Variant(1, 2)",
+    "Variant { a: 1 }",
+    "Enum::Variant { a: Inner(1) }",
+    include_str!("../../examples/roundtrip.keon"),
+];
+
+/// Documents that are not, lexically or syntactically.
+pub const REJECTED: &[&str] = &[
+    "",
+    "// only a comment",
+    "1 2",
+    "(1)",
+    "(1",
+    "(1 2)",
+    "(Variant)(",
+    "(Enum::Variant)",
+    "(Name) 1",
+    "(Name) :",
+    "[1 2]",
+    "[,]",
+    "[1,,]",
+    "{ a }",
+    "{ a 1 }",
+    "{ \"k\": 1 }",
+    "{ 1: 2 }",
+    "{ a: 1 b: 2 }",
+    "{,}",
+    "? :",
+    "% :",
+    "Variant 1",
+    "Variant [1]",
+    "Enum::",
+    "Enum::Variant::Inner",
+    "::Variant",
+    "Variant =>",
+    "\"unclosed",
+    "'ab'",
+    "@",
+];
@@ -0,0 +1,169 @@
+use keon::Value;
+use serde::Deserialize;
+
+fn opt(v: Option<Value>) -> Value {
+    Value::from(v)
+}
+
+#[test]
+fn nullish() {
+    assert!(Value::Unit.is_nullish());
+    assert!(opt(None).is_nullish());
+
+    assert!(!opt(Some(Value::Unit)).is_nullish());
+    assert!(!Value::Newtype(Box::new(Value::Unit)).is_nullish());
+    assert!(!Value::Seq(vec![]).is_nullish());
+    assert!(!Value::from(false).is_nullish());
+}
+
+#[test]
+fn flatten_options() {
+    let v: Value = keon::from_str("[? ? 1, ? ?, ? 2, ?, %? ? ()]").unwrap();
+    assert_eq!(
+        keon::to_string(&v.flatten_options()).unwrap(),
+        keon::to_string(&keon::from_str::<Value>("[?1, ?, ?2, ?, %?()]").unwrap()).unwrap(),
+    );
+
+    // `? ? 1` is ordered after `? 1`.
+    let v: Value = keon::from_str("{ ? ? 1 => ? ? ?, ? 1 => 2, 3 => ? ? 4 }").unwrap();
+    let v = v.flatten_options();
+    assert_eq!(v, keon::from_str::<Value>("{ ? 1 => ?, 3 => ? 4 }").unwrap());
+}
+
+#[test]
+#[cfg(feature = "syntax-raw-strings")]
+fn structural_hash() {
+    use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+
+    let hash = |v: &Value| {
+        let mut state = DefaultHasher::new();
+        v.structural_hash(&mut state);
+        state.finish()
+    };
+    let parse = |s| keon::from_str::<Value>(s).unwrap();
+
+    // Formatting doesn't matter.
+    assert_eq!(
+        hash(&parse("{ b: [1, 2,], a: ? \"x\" }")),
+        hash(&parse(
+            "{\n    \"a\" => ?`\"x\"`,\n    // comment\n    \"b\" => [1, 2],\n}"
+        ))
+    );
+
+    // Numbers are normalized.
+    assert_eq!(hash(&parse("1")), hash(&parse("1.0")));
+    assert_eq!(hash(&parse("-3")), hash(&parse("-3.0")));
+    assert_eq!(hash(&Value::from(5i64)), hash(&Value::from(5u64)));
+    assert_eq!(hash(&parse("0.0")), hash(&parse("-0.0")));
+    assert_eq!(hash(&parse("NaN")), hash(&parse("-NaN")));
+    assert_ne!(hash(&parse("1")), hash(&parse("1.5")));
+
+    // Structure does.
+    assert_ne!(hash(&parse("[[1], 2]")), hash(&parse("[[1, 2]]")));
+    assert_ne!(hash(&parse("?()")), hash(&parse("()")));
+    assert_ne!(hash(&parse("%1")), hash(&parse("1")));
+    assert_ne!(hash(&parse("{ 1 => 2 }")), hash(&parse("{ 2 => 1 }")));
+}
+
+#[test]
+fn deduplicate() {
+    let tile = "{ kind: \"grass\", height: 1.5, tags: [\"walkable\", \"green\"] }";
+    let tiles = vec![tile; 1000].join(", ");
+    let s = format!("{{ tiles: [{}], spawn: [0, 0], name: \"grass\" }}", tiles);
+
+    let original: Value = keon::from_str(&s).unwrap();
+    let mut v = original.clone();
+    let saved = v.deduplicate();
+    assert!(saved > 999 * 100, "{}", saved);
+
+    // Transparent.
+    assert_eq!(v, original);
+    assert_eq!(v.to_string().unwrap(), original.to_string().unwrap());
+    assert_eq!(keon::from_str::<Value>(&v.to_string().unwrap()).unwrap(), v);
+
+    let Value::Map(map) = &v else { panic!() };
+    let Value::Seq(tiles) = &map[&Value::from("tiles")] else {
+        panic!()
+    };
+    let (Value::Shared(a), Value::Shared(b)) = (&tiles[0], &tiles[999]) else {
+        panic!()
+    };
+    assert!(std::sync::Arc::ptr_eq(a, b));
+
+    // Unique subtrees stay as they were.
+    assert!(matches!(map[&Value::from("spawn")], Value::Seq(_)));
+
+    // Deserializing from a deduplicated value.
+    #[derive(Debug, serde::Deserialize)]
+    struct Tile {
+        kind: String,
+        #[allow(dead_code)]
+        height: f64,
+    }
+    #[derive(Debug, serde::Deserialize)]
+    struct World {
+        tiles: Vec<Tile>,
+    }
+    let world = World::deserialize(v).unwrap();
+    assert_eq!(world.tiles.len(), 1000);
+    assert_eq!(world.tiles[999].kind, "grass");
+}
+
+#[test]
+fn from_static() {
+    let v = Value::from_static("grass");
+    assert_eq!(v, Value::from("grass"));
+    assert_eq!(v, keon::from_str::<Value>("\"grass\"").unwrap());
+    assert_eq!(v.to_string().unwrap(), "\"grass\"");
+    assert_eq!(String::deserialize(v).unwrap(), "grass");
+
+    #[cfg(feature = "cow-strings")]
+    assert!(matches!(
+        Value::from_static("grass"),
+        Value::String(std::borrow::Cow::Borrowed("grass"))
+    ));
+}
+
+#[test]
+fn seq_pages() {
+    let s = format!("[{}]", (0..100).map(|i| i.to_string()).collect::<Vec<_>>().join(", "));
+    let v: Value = keon::from_str(&s).unwrap();
+
+    assert_eq!(v.seq_len_hint(), Some(100));
+    assert_eq!(v.seq_page(10..13).unwrap(), [10u64, 11, 12].map(Value::from));
+    assert_eq!(v.seq_page(98..).unwrap(), [98u64, 99].map(Value::from));
+    assert_eq!(v.seq_page(..=1).unwrap(), [0u64, 1].map(Value::from));
+    // Clamped.
+    assert_eq!(v.seq_page(95..200).unwrap().len(), 5);
+    assert!(v.seq_page(200..300).unwrap().is_empty());
+
+    let pages = v.seq_pages(30).unwrap();
+    assert_eq!(pages.map(<[Value]>::len).collect::<Vec<_>>(), [30, 30, 30, 10]);
+    assert_eq!(v.seq_iter().unwrap().rev().nth(1), Some(&Value::from(98u64)));
+
+    // Through shared elements and sequences.
+    let mut shared = Value::Seq(vec![v.clone(), v.clone()]);
+    shared.deduplicate();
+    assert!(matches!(shared.seq_page(1..).unwrap()[0], Value::Shared(_)));
+    assert_eq!(shared.seq_iter().unwrap().nth(1), Some(&v));
+    assert_eq!(shared.seq_iter().unwrap().len(), 2);
+    assert_eq!(Value::Shared(std::sync::Arc::new(v.clone())).seq_len_hint(), Some(100));
+
+    assert_eq!(Value::from("not a seq").seq_len_hint(), None);
+    assert!(Value::Map(Default::default()).seq_page(..).is_none());
+}
+
+#[test]
+fn semantically_equal() {
+    let original = r#"(Config) {
+    // The name shown in the title bar.
+    name: "main",
+    size: (0x10, 9),
+    tags: { "b" => 2, "a" => 1 },
+    side: Side::Left,
+}"#;
+    assert!(keon::semantically_equal(original, r#"{name:"main",size:(16,9),tags:{"a"=>1,"b"=>2},side:Left}"#).unwrap());
+    assert!(!keon::semantically_equal(original, r#"{name:"main",size:(16,9),tags:{"a"=>1},side:Left}"#).unwrap());
+    assert!(!keon::semantically_equal("1", "1.0").unwrap());
+    assert!(keon::semantically_equal("[1, 2", "[1, 2]").is_err());
+}
@@ -0,0 +1,114 @@
+mod util;
+
+use keon::{DeserializeConfig, Expand, SerializeConfig, SerializeConfigBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use util::{de_with, ser_with};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+const INDICES: DeserializeConfig = {
+    let mut cfg = DeserializeConfig::strict();
+    cfg.variant_indices = true;
+    cfg
+};
+
+#[test]
+fn roundtrips() {
+    let keybinds = BTreeMap::from_iter([(Action::Up, 'W'), (Action::Right, 'D')]);
+
+    let cfg = SerializeConfigBuilder::from(SerializeConfig::minimal()).unit_variant_indices(true);
+    let s = ser_with(&keybinds, cfg.build());
+    assert_eq!(s, "{0=>'W',3=>'D'}");
+    assert_eq!(de_with::<BTreeMap<Action, char>>(&s, INDICES).unwrap(), keybinds);
+
+    // Pretty parts keep their names.
+    let s = ser_with(&keybinds, SerializeConfig::builder().unit_variant_indices(true).build());
+    assert_eq!(s, "{\n    Action::Up => 'W',\n    Action::Right => 'D',\n}");
+    assert_eq!(de_with::<BTreeMap<Action, char>>(&s, INDICES).unwrap(), keybinds);
+}
+
+#[test]
+fn backwards() {
+    assert_eq!(de_with::<Action>("2", INDICES).unwrap(), Action::Left);
+    assert_eq!(
+        de_with::<Vec<Action>>("[1, Up, 3]", INDICES).unwrap(),
+        vec![Action::Down, Action::Up, Action::Right]
+    );
+
+    let e = de_with::<Action>("4", INDICES).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        ":1:1 (deserialize) invalid value: integer `4`, expected variant index 0 <= i < 4"
+    );
+
+    assert!(keon::from_str::<Action>("2").is_err());
+}
+
+#[test]
+fn comments() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Dot,
+        Circle(f64),
+        Rect(f64, f64),
+        Poly { sides: u8 },
+    }
+
+    let shapes = vec![
+        Shape::Dot,
+        Shape::Circle(1.5),
+        Shape::Rect(1.0, 2.0),
+        Shape::Poly { sides: 5 },
+    ];
+    let keybinds = BTreeMap::from_iter([(Action::Left, 'A')]);
+
+    let mut cfg = SerializeConfig::comfort();
+    cfg.variant_index_comments = true;
+
+    let s = ser_with(&shapes, cfg);
+    assert_eq!(
+        s,
+        "[\n    Shape::Dot /* =0 */,\n    Shape::Circle /* =1 */(1.5),\n    \
+         Shape::Rect /* =2 */(\n        1.0,\n        2.0,\n    ),\n    \
+         Shape::Poly /* =3 */ {\n        sides: 5,\n    },\n]"
+    );
+    assert_eq!(keon::from_str::<Vec<Shape>>(&s).unwrap(), shapes);
+
+    let s = ser_with(&keybinds, cfg);
+    assert_eq!(s, "{\n    Action::Left /* =2 */ => 'A',\n}");
+    assert_eq!(keon::from_str::<BTreeMap<Action, char>>(&s).unwrap(), keybinds);
+
+    // Not where minimized.
+    cfg.expand = Expand::UntilDepth(0);
+    assert_eq!(ser_with(&shapes, cfg), "[Dot,Circle%1.5,Rect(1.0,2.0),Poly{sides:5}]");
+}
+
+#[test]
+fn mixed_enums() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Circle(f64),
+        Dot,
+        Rect { w: f64, h: f64 },
+        Empty,
+    }
+
+    let shapes = vec![Shape::Dot, Shape::Circle(1.0), Shape::Empty];
+    let cfg = SerializeConfigBuilder::from(SerializeConfig::minimal()).unit_variant_indices(true);
+    let s = ser_with(&shapes, cfg.build());
+    assert_eq!(s, "[1,Circle%1.0,3]");
+    assert_eq!(de_with::<Vec<Shape>>(&s, INDICES).unwrap(), shapes);
+
+    // Indices count all variants, but only name unit ones.
+    for s in ["0", "0%1.0", "0(1.0)", "2", "2{w:1.0,h:2.0}"] {
+        let e = de_with::<Shape>(s, INDICES).unwrap_err();
+        assert_eq!(e.kind, keon::ErrorKind::ExpectedUnitVariant, "{}", s);
+    }
+}
@@ -0,0 +1,85 @@
+mod util;
+
+use keon::{DeserializeConfig, VariantMatching};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use util::de_with;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+enum Item {
+    Water,
+    IdCard(u32),
+    RocketLauncher { damage: i32 },
+}
+
+#[test]
+fn exact_by_default() {
+    assert!(keon::from_str::<Item>("water").is_err());
+    assert!(de_with::<Item>("water", DeserializeConfig::strict()).is_err());
+}
+
+#[test]
+fn case_insensitive() {
+    let mut cfg = DeserializeConfig::strict();
+    cfg.variant_matching = VariantMatching::CaseInsensitive;
+    assert_eq!(de_with::<Item>("water", cfg).unwrap(), Item::Water);
+    assert_eq!(de_with::<Item>("Item::WATER", cfg).unwrap(), Item::Water);
+    assert_eq!(de_with::<Item>("idcard(7)", cfg).unwrap(), Item::IdCard(7));
+    assert!(de_with::<Item>("rocket_launcher { damage: 1 }", cfg).is_err());
+    assert_eq!(de_with::<Item>("\"WATER\"", cfg).unwrap(), Item::Water);
+}
+
+#[test]
+fn normalized() {
+    let mut cfg = DeserializeConfig::strict();
+    cfg.variant_matching = VariantMatching::Normalized;
+    assert_eq!(
+        de_with::<Item>("rocket_launcher { damage: 1 }", cfg).unwrap(),
+        Item::RocketLauncher { damage: 1 }
+    );
+    assert_eq!(
+        de_with::<Vec<Item>>("[ROCKET_LAUNCHER { damage: 2 }, id_card%3]", cfg).unwrap(),
+        vec![Item::RocketLauncher { damage: 2 }, Item::IdCard(3)]
+    );
+    assert_eq!(
+        de_with::<BTreeMap<Item, u8>>("{ water => 1, Item::id_card(2) => 2 }", cfg).unwrap(),
+        BTreeMap::from_iter([(Item::Water, 1), (Item::IdCard(2), 2)])
+    );
+    assert_eq!(de_with::<(Item, u8)>("(water, 1)", cfg).unwrap(), (Item::Water, 1));
+
+    // Variants in kebab-case are matched by identifiers, as hyphens cannot be written.
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    enum Kebab {
+        RocketLauncher,
+    }
+    assert_eq!(de_with::<Kebab>("rocket_launcher", cfg).unwrap(), Kebab::RocketLauncher);
+    assert_eq!(de_with::<Kebab>("RocketLauncher", cfg).unwrap(), Kebab::RocketLauncher);
+    assert!(de_with::<Kebab>("rocket-launcher", cfg).is_err());
+}
+
+#[test]
+fn quoted() {
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+    enum Weapon {
+        Sword,
+        RocketLauncher,
+    }
+
+    let mut cfg = DeserializeConfig::strict();
+    cfg.variant_matching = VariantMatching::Normalized;
+    assert_eq!(
+        de_with::<Weapon>("\"rocket-launcher\"", cfg).unwrap(),
+        Weapon::RocketLauncher
+    );
+    assert_eq!(
+        de_with::<BTreeMap<Weapon, u8>>("{ \"rocket-launcher\" => 1, \"sword\" => 2 }", cfg).unwrap(),
+        BTreeMap::from_iter([(Weapon::RocketLauncher, 1), (Weapon::Sword, 2)])
+    );
+    assert!(de_with::<Weapon>("\"rocket-hammer\"", cfg).is_err());
+
+    // Unit variants only, and not when matched exactly.
+    assert!(de_with::<Item>("\"rocket-launcher\" { damage: 1 }", cfg).is_err());
+    assert!(de_with::<Item>("\"id-card\" % 1", cfg).is_err());
+    assert!(de_with::<Weapon>("\"RocketLauncher\"", DeserializeConfig::strict()).is_err());
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "notify")]
+
+use serde::Deserialize;
+use std::{path::PathBuf, sync::mpsc, time::Duration};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    level: u8,
+}
+
+/// Removes the directory however the test ends.
+struct TempDir(PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn reloads() {
+    let dir = TempDir(std::env::temp_dir().join(format!("keon-watch-{}", std::process::id())));
+    std::fs::create_dir_all(&dir.0).unwrap();
+    let path = dir.0.join("config.keon");
+    std::fs::write(&path, "{ level: 256 }").unwrap();
+
+    // Read on this thread first, where the error is kept for the next value that deserializes.
+    let (tx, rx) = mpsc::channel();
+    let watcher = keon::watch(&path, move |config: Config, failures| {
+        tx.send((config, failures)).unwrap();
+    })
+    .unwrap();
+    assert!(rx.try_recv().is_err());
+
+    let next = || rx.recv_timeout(Duration::from_secs(10)).unwrap();
+    std::fs::write(&path, "{ level: 2 }").unwrap();
+    let (config, failures) = next();
+    assert_eq!(config, Config { level: 2 });
+    assert!(failures.iter().any(|e| e.to_string().contains("256")), "{:?}", failures);
+
+    std::fs::write(&path, "{ level: 3 }").unwrap();
+    let (config, failures) = next();
+    assert_eq!(config, Config { level: 3 });
+    assert!(
+        failures.iter().all(|e| !e.to_string().contains("256")),
+        "{:?}",
+        failures
+    );
+
+    drop(watcher);
+}